@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SandboxError {
+    #[error("Code is empty")]
+    Empty,
+    #[error("Unsupported edition '{0}', expected 2015, 2018, 2021 or 2024")]
+    InvalidEdition(String),
+    #[error("Dependency '{name}' is not available offline, vendored crates: {available}")]
+    UnknownDependency { name: String, available: String },
+    #[error("Could not start {program}: {source}")]
+    Spawn {
+        program: &'static str,
+        source: std::io::Error,
+    },
+    #[error(
+        "Could not cut the build off from the network and the user's files, building and running snippets needs unprivileged user namespaces on Linux"
+    )]
+    Isolation,
+    #[error("Build did not finish within {0} seconds")]
+    BuildTimedOut(u64),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,265 @@
+use std::{
+    ffi::{CStr, CString},
+    fs::File,
+    io,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    ptr,
+    sync::Arc,
+};
+use tempfile::TempDir;
+
+// what the child reports when it can't be isolated. pre_exec only hands an errno back to the
+// parent, so it needs one the rlimits, the mounts and exec never return
+pub const ISOLATION_FAILED: i32 = libc::ENETDOWN;
+
+// read only, what a build needs for the C linker, the libraries it links against and ld.so's config
+const SYSTEM_DIRS: &[&str] = &["/usr", "/bin", "/lib", "/lib64", "/etc"];
+const DEVICES: &[&str] = &["/dev/null", "/dev/zero", "/dev/random", "/dev/urandom"];
+// who the user is inside the jail. not root, so a snippet keeps no capabilities after exec and
+// can't undo the mounts or leave the chroot
+const JAILED_ID: u32 = 1000;
+
+struct Bind {
+    source: CString,
+    target: CString,
+    read_only: bool,
+    // the flags a user namespace may not drop when it remounts a bind read only
+    locked: libc::c_ulong,
+}
+
+struct Layout {
+    root: CString,
+    // where a fresh /proc goes, for toolchains that find their own files through /proc/self/exe
+    proc: Option<CString>,
+    binds: Vec<Bind>,
+    uid_map: CString,
+    gid_map: CString,
+}
+
+// an otherwise empty root holding the crate being built, the toolchain, the vendor dir and the
+// system directories, each mounted at its own path. commands started in it get fresh user,
+// mount and network namespaces, so they see none of the user's files and no network
+pub struct Jail {
+    // the mount points, the mounts themselves only exist in each command's namespace
+    _root: TempDir,
+    layout: Arc<Layout>,
+}
+
+fn c_path(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes()).map_err(io::Error::other)
+}
+
+fn locked_flags(path: &Path) -> io::Result<libc::c_ulong> {
+    let path = c_path(path)?;
+    // SAFETY: statvfs is plain data, zeroed is a valid value for it
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: statvfs only writes to the struct passed by reference
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok([
+        (libc::ST_NOSUID, libc::MS_NOSUID),
+        (libc::ST_NODEV, libc::MS_NODEV),
+        (libc::ST_NOEXEC, libc::MS_NOEXEC),
+        (libc::ST_NOATIME, libc::MS_NOATIME),
+        (libc::ST_NODIRATIME, libc::MS_NODIRATIME),
+        (libc::ST_RELATIME, libc::MS_RELATIME),
+    ]
+    .into_iter()
+    .filter(|(st, _)| stat.f_flag & st != 0)
+    .fold(0, |flags, (_, ms)| flags | ms))
+}
+
+fn check(result: libc::c_int) -> io::Result<()> {
+    match result {
+        0 => Ok(()),
+        _ => Err(io::Error::from_raw_os_error(ISOLATION_FAILED)),
+    }
+}
+
+fn write_file(path: &CStr, contents: &CStr) -> io::Result<()> {
+    // SAFETY: both strings are nul terminated and outlive the calls
+    unsafe {
+        let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+        if fd < 0 {
+            return Err(io::Error::from_raw_os_error(ISOLATION_FAILED));
+        }
+        let bytes = contents.to_bytes();
+        let written = libc::write(fd, bytes.as_ptr().cast(), bytes.len());
+        libc::close(fd);
+        check(if written == bytes.len() as isize {
+            0
+        } else {
+            -1
+        })
+    }
+}
+
+// waits for the jailed process and exits the way it did. closes everything but stdio first,
+// spawn() reads the exec error pipe until every copy of it is closed
+fn reap(pid: libc::pid_t) -> ! {
+    // SAFETY: only async-signal-safe calls, and this process never returns to Rust code
+    unsafe {
+        if libc::syscall(libc::SYS_close_range, 3, libc::c_uint::MAX, 0) != 0 {
+            for fd in 3..libc::sysconf(libc::_SC_OPEN_MAX).clamp(3, 65536) as libc::c_int {
+                libc::close(fd);
+            }
+        }
+        let mut status = 0;
+        while libc::waitpid(pid, &mut status, 0) < 0 {
+            if *libc::__errno_location() != libc::EINTR {
+                libc::_exit(1);
+            }
+        }
+        if libc::WIFSIGNALED(status) {
+            let signal = libc::WTERMSIG(status);
+            let no_core = libc::rlimit {
+                rlim_cur: 0,
+                rlim_max: 0,
+            };
+            libc::setrlimit(libc::RLIMIT_CORE, &no_core);
+            libc::signal(signal, libc::SIG_DFL);
+            libc::kill(libc::getpid(), signal);
+            libc::_exit(128 + signal);
+        }
+        libc::_exit(libc::WEXITSTATUS(status))
+    }
+}
+
+// runs in the forked child before exec, so it only makes raw syscalls on what the parent prepared
+fn enter(layout: &Layout, work_dir: &CStr) -> io::Result<()> {
+    // SAFETY: every call only affects this process and its namespaces, on nul terminated
+    // strings allocated before the fork
+    unsafe {
+        // a fresh /proc only shows the processes of a pid namespace of its own
+        let pid_namespace = match layout.proc {
+            Some(_) => libc::CLONE_NEWPID,
+            None => 0,
+        };
+        check(libc::unshare(
+            libc::CLONE_NEWUSER | libc::CLONE_NEWNS | libc::CLONE_NEWNET | pid_namespace,
+        ))?;
+        write_file(c"/proc/self/uid_map", &layout.uid_map)?;
+        write_file(c"/proc/self/setgroups", c"deny")?;
+        write_file(c"/proc/self/gid_map", &layout.gid_map)?;
+        // only children join the new pid namespace, the command runs in one while this waits
+        if pid_namespace != 0 {
+            match libc::fork() {
+                -1 => return Err(io::Error::from_raw_os_error(ISOLATION_FAILED)),
+                0 => check(libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL))?,
+                pid => reap(pid),
+            }
+        }
+        check(libc::mount(
+            ptr::null(),
+            c"/".as_ptr(),
+            ptr::null(),
+            libc::MS_REC | libc::MS_PRIVATE,
+            ptr::null(),
+        ))?;
+        for bind in &layout.binds {
+            check(libc::mount(
+                bind.source.as_ptr(),
+                bind.target.as_ptr(),
+                ptr::null(),
+                libc::MS_BIND | libc::MS_REC,
+                ptr::null(),
+            ))?;
+            if bind.read_only {
+                check(libc::mount(
+                    ptr::null(),
+                    bind.target.as_ptr(),
+                    ptr::null(),
+                    libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY | bind.locked,
+                    ptr::null(),
+                ))?;
+            }
+        }
+        if let Some(proc) = &layout.proc {
+            check(libc::mount(
+                c"proc".as_ptr(),
+                proc.as_ptr(),
+                c"proc".as_ptr(),
+                libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC,
+                ptr::null(),
+            ))?;
+        }
+        check(libc::chroot(layout.root.as_ptr()))?;
+        check(libc::chdir(work_dir.as_ptr()))?;
+    }
+    Ok(())
+}
+
+impl Jail {
+    // `work_dir` is mounted writable, `read_only` holds the toolchain and vendor dirs. only
+    // builds need /proc, a snippet runs without one and so keeps its own pid
+    pub fn new(work_dir: &Path, read_only: &[PathBuf], with_proc: bool) -> io::Result<Self> {
+        let root = tempfile::tempdir()?;
+        let mut binds = Vec::new();
+        let mut mount = |source: &Path, read_only: bool| -> io::Result<()> {
+            let target = root.path().join(source.strip_prefix("/").unwrap_or(source));
+            match source.is_dir() {
+                true => std::fs::create_dir_all(&target)?,
+                false => {
+                    std::fs::create_dir_all(target.parent().unwrap_or(root.path()))?;
+                    File::create(&target)?;
+                }
+            }
+            binds.push(Bind {
+                source: c_path(source)?,
+                target: c_path(&target)?,
+                read_only,
+                locked: match read_only {
+                    true => locked_flags(source)?,
+                    false => 0,
+                },
+            });
+            Ok(())
+        };
+        for dir in SYSTEM_DIRS.iter().map(Path::new).filter(|dir| dir.exists()) {
+            mount(dir, true)?;
+        }
+        for device in DEVICES
+            .iter()
+            .map(Path::new)
+            .filter(|device| device.exists())
+        {
+            mount(device, false)?;
+        }
+        for dir in read_only {
+            mount(dir, true)?;
+        }
+        mount(work_dir, false)?;
+        let proc = root.path().join("proc");
+        std::fs::create_dir_all(&proc)?;
+        std::fs::create_dir_all(root.path().join("tmp"))?;
+        // SAFETY: getuid and getgid can't fail
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        let layout = Layout {
+            root: c_path(root.path())?,
+            proc: with_proc.then(|| c_path(&proc)).transpose()?,
+            binds,
+            uid_map: CString::new(format!("{} {} 1", JAILED_ID, uid))?,
+            gid_map: CString::new(format!("{} {} 1", JAILED_ID, gid))?,
+        };
+        Ok(Self {
+            _root: root,
+            layout: Arc::new(layout),
+        })
+    }
+    // confines the command to the jail, starting it in `work_dir`
+    pub fn confine(
+        &self,
+        command: &mut tokio::process::Command,
+        work_dir: &Path,
+    ) -> io::Result<()> {
+        let layout = self.layout.clone();
+        let work_dir = c_path(work_dir)?;
+        // SAFETY: enter only makes async-signal-safe syscalls
+        unsafe {
+            command.pre_exec(move || enter(&layout, &work_dir));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use crate::agent_tools::code::{review_rust::ReviewRust, run_rust::RunRust, sandbox::Sandbox};
+use rig::tool::ToolDyn;
+use std::path::Path;
+
+pub mod error;
+#[cfg(target_os = "linux")]
+pub mod jail;
+pub mod review_rust;
+pub mod run_rust;
+pub mod sandbox;
+
+pub fn code_tools(vendor_dir: Option<&Path>) -> Vec<Box<dyn ToolDyn>> {
+    let sandbox = Sandbox::new(vendor_dir.map(Path::to_path_buf));
+    vec![
+        Box::new(RunRust::new(sandbox.clone())),
+        Box::new(ReviewRust::new(sandbox)),
+    ]
+}
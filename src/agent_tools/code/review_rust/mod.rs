@@ -0,0 +1,40 @@
+pub mod request;
+pub mod review;
+
+use crate::agent_tools::{code::sandbox::Sandbox, ToToolResult};
+use request::ReviewRustArgs;
+use review::{review, ReviewRustOutput};
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::schema_for;
+
+pub struct ReviewRust {
+    sandbox: Sandbox,
+}
+
+impl ReviewRust {
+    pub fn new(sandbox: Sandbox) -> Self {
+        Self { sandbox }
+    }
+}
+
+impl Tool for ReviewRust {
+    const NAME: &'static str = "review_rust";
+    type Args = ReviewRustArgs;
+    type Output = ReviewRustOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "runs rustfmt --check and cargo clippy on Rust code in a scratch crate and returns a formatting diff plus structured compiler and clippy diagnostics, each with lint name, line/column span, message and suggested fixes. Cite these lints when giving feedback instead of guessing".to_string(),
+            parameters: serde_json::to_value(schema_for!(ReviewRustArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        review(&self.sandbox, &args).await.to_tool_result()
+    }
+}
@@ -0,0 +1,14 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ReviewRustArgs {
+    #[schemars(
+        description = "Rust source to review: a program with fn main, or library items like functions, structs and impls"
+    )]
+    pub code: String,
+    #[schemars(description = "Crate names the code depends on, from the offline vendored set")]
+    pub dependencies: Option<Vec<String>>,
+    #[schemars(description = "Rust edition: 2015, 2018, 2021 or 2024. Defaults to 2024")]
+    pub edition: Option<String>,
+}
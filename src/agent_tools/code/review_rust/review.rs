@@ -0,0 +1,230 @@
+use crate::agent_tools::code::{
+    error::SandboxError,
+    review_rust::request::ReviewRustArgs,
+    sandbox::{capped, compiler_messages, Sandbox, DEFAULT_EDITION},
+};
+use serde::Serialize;
+use serde_json::Value;
+use similar::TextDiff;
+use std::process::Stdio;
+use tokio::{io::AsyncWriteExt, process::Command};
+
+#[derive(Debug, Serialize)]
+pub struct Span {
+    pub line_start: u64,
+    pub column_start: u64,
+    pub line_end: u64,
+    pub column_end: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Suggestion {
+    pub message: String,
+    pub span: Span,
+    pub replacement: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Diagnostic {
+    pub level: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lint: Option<String>,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<Suggestion>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewRustOutput {
+    pub compiled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format_diff: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format_error: Option<String>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+fn span(value: &Value) -> Option<Span> {
+    Some(Span {
+        line_start: value["line_start"].as_u64()?,
+        column_start: value["column_start"].as_u64()?,
+        line_end: value["line_end"].as_u64()?,
+        column_end: value["column_end"].as_u64()?,
+        label: value["label"].as_str().map(str::to_string),
+    })
+}
+
+fn diagnostic(message: &Value) -> Option<Diagnostic> {
+    let primary = message["spans"]
+        .as_array()
+        .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true));
+    let mut suggestions = Vec::new();
+    let mut notes = Vec::new();
+    for child in message["children"].as_array().into_iter().flatten() {
+        let child_message = child["message"].as_str().unwrap_or_default();
+        let replacements: Vec<Suggestion> = child["spans"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|child_span| {
+                Some(Suggestion {
+                    message: child_message.to_string(),
+                    replacement: child_span["suggested_replacement"].as_str()?.to_string(),
+                    span: span(child_span)?,
+                })
+            })
+            .collect();
+        if replacements.is_empty() {
+            notes.push(format!(
+                "{}: {}",
+                child["level"].as_str().unwrap_or("note"),
+                child_message
+            ));
+        }
+        suggestions.extend(replacements);
+    }
+    Some(Diagnostic {
+        level: message["level"].as_str()?.to_string(),
+        lint: message["code"]["code"].as_str().map(str::to_string),
+        message: message["message"].as_str()?.to_string(),
+        span: primary.and_then(span),
+        suggestions,
+        notes,
+    })
+}
+
+async fn rustfmt(
+    sandbox: &Sandbox,
+    edition: &str,
+    source: &str,
+) -> Result<Result<String, String>, SandboxError> {
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", edition])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|source| SandboxError::Spawn {
+            program: "rustfmt",
+            source,
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(source.as_bytes()).await?;
+    }
+    let timeout = sandbox.limits().build_timeout;
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| SandboxError::BuildTimedOut(timeout.as_secs()))??;
+    Ok(match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).to_string()),
+        false => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    })
+}
+
+pub async fn review(
+    sandbox: &Sandbox,
+    args: &ReviewRustArgs,
+) -> Result<ReviewRustOutput, SandboxError> {
+    let file_name = match args.code.contains("fn main") {
+        true => "main.rs",
+        false => "lib.rs",
+    };
+    let project = sandbox.project(
+        args.edition.as_deref(),
+        args.dependencies.as_deref(),
+        file_name,
+        &args.code,
+    )?;
+    let clippy = sandbox
+        .cargo(
+            project.path(),
+            &[
+                "clippy",
+                "--offline",
+                "--quiet",
+                "--message-format=json",
+                "--",
+                "-A",
+                "dead_code",
+            ],
+        )
+        .await?;
+    let max_len = sandbox.limits().max_output_bytes;
+    let edition = args.edition.as_deref().unwrap_or(DEFAULT_EDITION);
+    let (formatted, format_diff, format_error) = match rustfmt(sandbox, edition, &args.code).await?
+    {
+        Ok(formatted) if formatted == args.code => (Some(true), None, None),
+        Ok(formatted) => {
+            let diff = TextDiff::from_lines(args.code.as_str(), formatted.as_str())
+                .unified_diff()
+                .context_radius(1)
+                .header("original", "formatted")
+                .to_string();
+            (Some(false), Some(capped(diff.as_bytes(), max_len)), None)
+        }
+        Err(error) => (None, None, Some(capped(error.as_bytes(), max_len))),
+    };
+    Ok(ReviewRustOutput {
+        compiled: clippy.status.success(),
+        formatted,
+        format_diff,
+        format_error,
+        diagnostics: compiler_messages(&clippy.stdout)
+            .iter()
+            .filter_map(diagnostic)
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_review_reports_lints_and_formatting() {
+        let args = ReviewRustArgs {
+            code: "pub fn add(a: i32, b: i32) -> i32 {\n    return a+b;\n}\n".to_string(),
+            dependencies: None,
+            edition: None,
+        };
+        let output = review(&Sandbox::new(None), &args).await.unwrap();
+        assert!(output.compiled);
+        assert_eq!(output.formatted, Some(false));
+        assert!(output.format_diff.unwrap().contains("+    return a + b;"));
+        let needless_return = output
+            .diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.lint.as_deref() == Some("clippy::needless_return"))
+            .unwrap();
+        assert_eq!(needless_return.level, "warning");
+        assert_eq!(needless_return.span.as_ref().unwrap().line_start, 2);
+        assert!(needless_return
+            .suggestions
+            .iter()
+            .any(|suggestion| suggestion.replacement.contains("a+b")));
+    }
+
+    #[tokio::test]
+    async fn test_review_reports_compile_errors() {
+        let args = ReviewRustArgs {
+            code: "fn main() {\n    let x: u32 = \"four\";\n}\n".to_string(),
+            dependencies: None,
+            edition: None,
+        };
+        let output = review(&Sandbox::new(None), &args).await.unwrap();
+        assert!(!output.compiled);
+        assert_eq!(output.formatted, Some(true));
+        assert_eq!(output.diagnostics.len(), 1);
+        assert_eq!(output.diagnostics[0].lint.as_deref(), Some("E0308"));
+        assert_eq!(output.diagnostics[0].level, "error");
+    }
+}
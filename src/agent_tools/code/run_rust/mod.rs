@@ -0,0 +1,41 @@
+pub mod request;
+
+use crate::agent_tools::{
+    code::sandbox::{RunRustOutput, Sandbox},
+    ToToolResult,
+};
+use request::RunRustArgs;
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::schema_for;
+
+pub struct RunRust {
+    sandbox: Sandbox,
+}
+
+impl RunRust {
+    pub fn new(sandbox: Sandbox) -> Self {
+        Self { sandbox }
+    }
+}
+
+impl Tool for RunRust {
+    const NAME: &'static str = "run_rust";
+    type Args = RunRustArgs;
+    type Output = RunRustOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "compiles and runs a Rust snippet in a temporary Cargo project and returns compiler diagnostics, exit code, stdout and stderr. Builds are offline with vendored dependencies only, and the program runs with a timeout, memory and CPU limits and no network. Neither can read files outside the scratch crate, so the user's files are not available to the snippet. Use it to check an answer or a user's attempt before explaining it".to_string(),
+            parameters: serde_json::to_value(schema_for!(RunRustArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.sandbox.run(&args).await.to_tool_result()
+    }
+}
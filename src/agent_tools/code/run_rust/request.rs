@@ -0,0 +1,18 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct RunRustArgs {
+    #[schemars(
+        description = "A complete Rust program with fn main, or statements to run, which are wrapped in fn main"
+    )]
+    pub code: String,
+    #[schemars(
+        description = "Crate names to depend on. Only crates from the offline vendored set are available, there is no network access"
+    )]
+    pub dependencies: Option<Vec<String>>,
+    #[schemars(description = "Rust edition: 2015, 2018, 2021 or 2024. Defaults to 2024")]
+    pub edition: Option<String>,
+    #[schemars(description = "Text passed to the program on standard input")]
+    pub stdin: Option<String>,
+}
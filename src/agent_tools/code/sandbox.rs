@@ -0,0 +1,582 @@
+#[cfg(target_os = "linux")]
+use crate::agent_tools::code::jail::{Jail, ISOLATION_FAILED};
+use crate::agent_tools::code::{error::SandboxError, run_rust::request::RunRustArgs};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::{Output, Stdio},
+    time::Duration,
+};
+use tempfile::TempDir;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    process::Command,
+};
+
+const EDITIONS: &[&str] = &["2015", "2018", "2021", "2024"];
+pub const DEFAULT_EDITION: &str = "2024";
+const PACKAGE_NAME: &str = "snippet";
+const MAX_DIAGNOSTICS: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct Limits {
+    pub build_timeout: Duration,
+    pub run_timeout: Duration,
+    pub memory_bytes: u64,
+    pub max_output_bytes: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            build_timeout: Duration::from_secs(120),
+            run_timeout: Duration::from_secs(10),
+            memory_bytes: 1024 * 1024 * 1024,
+            max_output_bytes: 16 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RunRustOutput {
+    pub compiled: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub stdout: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub stderr: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub wrapped_in_main: bool,
+}
+
+// holds the jail's root for as long as a command runs in it
+#[cfg(target_os = "linux")]
+type Confinement = Jail;
+#[cfg(not(target_os = "linux"))]
+type Confinement = ();
+
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    vendor_dir: Option<PathBuf>,
+    limits: Limits,
+}
+
+fn package_field(manifest: &str, field: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=')
+            && in_package
+            && key.trim() == field
+        {
+            return Some(value.trim().trim_matches('"').to_string());
+        }
+    }
+    None
+}
+
+pub fn capped(bytes: &[u8], max_len: usize) -> String {
+    if bytes.len() <= max_len {
+        return String::from_utf8_lossy(bytes).to_string();
+    }
+    format!(
+        "{}\n... [output truncated at {} bytes]",
+        String::from_utf8_lossy(&bytes[..max_len]),
+        max_len
+    )
+}
+
+// keeps draining the pipe past the cap, a writer whose pipe closes early dies of SIGPIPE or fails
+// its next print. only the first max_len + 1 bytes are kept, enough to tell output was cut.
+// the buffer is the caller's so what was read survives a timeout
+pub async fn read_capped(
+    reader: Option<impl AsyncRead + Unpin>,
+    max_len: usize,
+    buffer: &mut Vec<u8>,
+) {
+    let Some(mut reader) = reader else {
+        return;
+    };
+    let mut chunk = [0; 8192];
+    while let Ok(read @ 1..) = reader.read(&mut chunk).await {
+        let keep = (max_len + 1).saturating_sub(buffer.len()).min(read);
+        buffer.extend_from_slice(&chunk[..keep]);
+    }
+}
+
+pub fn compiler_messages(cargo_stdout: &[u8]) -> Vec<Value> {
+    String::from_utf8_lossy(cargo_stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|line| line["reason"] == "compiler-message")
+        .map(|mut line| line["message"].take())
+        .filter(|message| {
+            let text = message["message"].as_str().unwrap_or_default();
+            !(text.starts_with("aborting due to")
+                || text.starts_with("For more information")
+                || text.ends_with("emitted"))
+        })
+        .take(MAX_DIAGNOSTICS)
+        .collect()
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type Resource = libc::c_int;
+
+#[cfg(unix)]
+fn set_limit(resource: Resource, value: u64) -> std::io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: value as libc::rlim_t,
+        rlim_max: value as libc::rlim_t,
+    };
+    // SAFETY: setrlimit only reads the struct passed by reference
+    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+// runs in the forked child before exec, so it only makes raw syscalls
+#[cfg(unix)]
+fn restrict_child(cpu_seconds: u64, memory_bytes: u64) -> std::io::Result<()> {
+    set_limit(libc::RLIMIT_CPU, cpu_seconds)?;
+    set_limit(libc::RLIMIT_AS, memory_bytes)?;
+    set_limit(libc::RLIMIT_CORE, 0)?;
+    Ok(())
+}
+
+// the active toolchain's own directory, builds run its binaries directly since rustup's proxies
+// need the user's home
+async fn sysroot() -> Result<PathBuf, SandboxError> {
+    let output = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|source| SandboxError::Spawn {
+            program: "rustc",
+            source,
+        })?;
+    if !output.status.success() {
+        return Err(SandboxError::Spawn {
+            program: "rustc",
+            source: std::io::Error::other(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ),
+        });
+    }
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn spawn_error(program: &'static str, source: std::io::Error) -> SandboxError {
+    match source.raw_os_error() == Some(ISOLATION_FAILED) {
+        true => SandboxError::Isolation,
+        false => SandboxError::Spawn { program, source },
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn spawn_error(program: &'static str, source: std::io::Error) -> SandboxError {
+    SandboxError::Spawn { program, source }
+}
+
+impl Sandbox {
+    pub fn new(vendor_dir: Option<PathBuf>) -> Self {
+        Self {
+            vendor_dir,
+            limits: Limits::default(),
+        }
+    }
+    #[cfg(test)]
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+    pub fn vendored_crates(&self) -> BTreeMap<String, String> {
+        let Some(entries) = self
+            .vendor_dir
+            .as_ref()
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+        else {
+            return BTreeMap::new();
+        };
+        let mut crates: Vec<(String, String)> = entries
+            .filter_map(|ent| std::fs::read_to_string(ent.ok()?.path().join("Cargo.toml")).ok())
+            .filter_map(|manifest| {
+                Some((
+                    package_field(&manifest, "name")?,
+                    package_field(&manifest, "version")?,
+                ))
+            })
+            .collect();
+        crates.sort();
+        crates.into_iter().collect()
+    }
+    fn manifest(&self, edition: &str, dependencies: &[String]) -> Result<String, SandboxError> {
+        let vendored = self.vendored_crates();
+        let mut manifest = format!(
+            "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"{}\"\n\n[dependencies]\n",
+            PACKAGE_NAME, edition
+        );
+        for name in dependencies {
+            let version = vendored
+                .get(name)
+                .ok_or_else(|| SandboxError::UnknownDependency {
+                    name: name.clone(),
+                    available: match vendored.is_empty() {
+                        true => "none".to_string(),
+                        false => vendored.keys().cloned().collect::<Vec<_>>().join(", "),
+                    },
+                })?;
+            manifest.push_str(&format!("{} = \"={}\"\n", name, version));
+        }
+        manifest.push_str("\n[profile.dev]\ndebug = false\n");
+        Ok(manifest)
+    }
+    pub fn project(
+        &self,
+        edition: Option<&str>,
+        dependencies: Option<&[String]>,
+        file_name: &str,
+        source: &str,
+    ) -> Result<TempDir, SandboxError> {
+        if source.trim().is_empty() {
+            return Err(SandboxError::Empty);
+        }
+        let edition = edition.unwrap_or(DEFAULT_EDITION);
+        if !EDITIONS.contains(&edition) {
+            return Err(SandboxError::InvalidEdition(edition.to_string()));
+        }
+        let manifest = self.manifest(edition, dependencies.unwrap_or_default())?;
+        let project = tempfile::tempdir()?;
+        let dir = project.path();
+        std::fs::create_dir_all(dir.join("src"))?;
+        std::fs::write(dir.join("Cargo.toml"), manifest)?;
+        std::fs::write(dir.join("src").join(file_name), source)?;
+        if let Some(vendor_dir) = &self.vendor_dir {
+            std::fs::create_dir_all(dir.join(".cargo"))?;
+            std::fs::write(
+                dir.join(".cargo").join("config.toml"),
+                format!(
+                    "[source.crates-io]\nreplace-with = \"vendored-sources\"\n\n[source.vendored-sources]\ndirectory = {:?}\n\n[net]\noffline = true\n",
+                    vendor_dir.canonicalize()?
+                ),
+            )?;
+        }
+        Ok(project)
+    }
+    #[cfg(target_os = "linux")]
+    fn confine(
+        &self,
+        command: &mut Command,
+        dir: &Path,
+        toolchain: Option<PathBuf>,
+    ) -> Result<Confinement, SandboxError> {
+        let mut read_only: Vec<PathBuf> = toolchain.iter().cloned().collect();
+        if let Some(vendor_dir) = &self.vendor_dir {
+            read_only.push(vendor_dir.canonicalize()?);
+        }
+        let jail = Jail::new(dir, &read_only, toolchain.is_some())?;
+        jail.confine(command, dir)?;
+        Ok(jail)
+    }
+    #[cfg(not(target_os = "linux"))]
+    fn confine(
+        &self,
+        _command: &mut Command,
+        _dir: &Path,
+        _toolchain: Option<PathBuf>,
+    ) -> Result<Confinement, SandboxError> {
+        Err(SandboxError::Isolation)
+    }
+    // a build only sees its crate, the toolchain and the vendor dir, so neither it nor a macro
+    // it expands can read the user's files or marvin's environment
+    pub async fn cargo(&self, dir: &Path, args: &[&str]) -> Result<Output, SandboxError> {
+        let dir = dir.canonicalize()?;
+        let sysroot = sysroot().await?;
+        let bin = sysroot.join("bin");
+        let mut command = Command::new(bin.join("cargo"));
+        command
+            .args(args)
+            .current_dir(&dir)
+            .env_clear()
+            .env("PATH", format!("{}:/usr/bin:/bin", bin.display()))
+            .env("CARGO_HOME", dir.join(".cargo-home"))
+            .env("CARGO_TARGET_DIR", dir.join("target"))
+            .env("CARGO_NET_OFFLINE", "true")
+            .stdin(Stdio::null())
+            .kill_on_drop(true);
+        let _jail = self.confine(&mut command, &dir, Some(sysroot))?;
+        tokio::time::timeout(self.limits.build_timeout, command.output())
+            .await
+            .map_err(|_| SandboxError::BuildTimedOut(self.limits.build_timeout.as_secs()))?
+            .map_err(|source| spawn_error("cargo", source))
+    }
+    async fn build(&self, dir: &Path, output: &mut RunRustOutput) -> Result<(), SandboxError> {
+        let build = self
+            .cargo(
+                dir,
+                &["build", "--offline", "--quiet", "--message-format=json"],
+            )
+            .await?;
+        output.compiled = build.status.success();
+        output.diagnostics = compiler_messages(&build.stdout)
+            .iter()
+            .filter_map(|message| message["rendered"].as_str())
+            .map(|rendered| rendered.trim_end().to_string())
+            .collect();
+        if !output.compiled && output.diagnostics.is_empty() {
+            output.stderr = capped(&build.stderr, self.limits.max_output_bytes);
+        }
+        Ok(())
+    }
+    // starts the built snippet in a jail of its own crate, or not at all
+    fn spawn(
+        &self,
+        binary: &Path,
+        dir: &Path,
+    ) -> Result<(tokio::process::Child, Confinement), SandboxError> {
+        let mut command = Command::new(binary);
+        command
+            .current_dir(dir)
+            .env_clear()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(unix)]
+        {
+            let cpu_seconds = self.limits.run_timeout.as_secs() + 1;
+            let memory_bytes = self.limits.memory_bytes;
+            // SAFETY: the closure only calls setrlimit, which is async-signal-safe
+            unsafe {
+                command.pre_exec(move || restrict_child(cpu_seconds, memory_bytes));
+            }
+        }
+        let jail = self.confine(&mut command, dir, None)?;
+        let child = command
+            .spawn()
+            .map_err(|source| spawn_error("snippet", source))?;
+        Ok((child, jail))
+    }
+    async fn execute(
+        &self,
+        dir: &Path,
+        stdin: Option<&str>,
+        output: &mut RunRustOutput,
+    ) -> Result<(), SandboxError> {
+        let binary = dir.join("target").join("debug").join(PACKAGE_NAME);
+        let (mut child, _jail) = self.spawn(&binary, dir)?;
+        let max_len = self.limits.max_output_bytes;
+        let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+        // a snippet that never reads its input can't stall the write past the timeout
+        let child_stdin = child.stdin.take();
+        let write = async move {
+            if let Some(mut child_stdin) = child_stdin {
+                let _ = child_stdin
+                    .write_all(stdin.unwrap_or_default().as_bytes())
+                    .await;
+            }
+        };
+        let (mut stdout_bytes, mut stderr_bytes) = (Vec::new(), Vec::new());
+        let run = async {
+            tokio::join!(
+                write,
+                read_capped(stdout, max_len, &mut stdout_bytes),
+                read_capped(stderr, max_len, &mut stderr_bytes),
+                child.wait()
+            )
+        };
+        match tokio::time::timeout(self.limits.run_timeout, run).await {
+            Ok(((), (), (), status)) => {
+                let status = status?;
+                output.exit_code = status.code();
+                #[cfg(unix)]
+                {
+                    use std::os::unix::process::ExitStatusExt;
+                    output.signal = status.signal();
+                }
+            }
+            Err(_) => {
+                output.timed_out = true;
+                let _ = child.kill().await;
+            }
+        }
+        // on a timeout, what the snippet printed before it hung
+        output.stdout = capped(&stdout_bytes, max_len);
+        output.stderr = capped(&stderr_bytes, max_len);
+        Ok(())
+    }
+    pub async fn run(&self, args: &RunRustArgs) -> Result<RunRustOutput, SandboxError> {
+        if args.code.trim().is_empty() {
+            return Err(SandboxError::Empty);
+        }
+        let mut output = RunRustOutput {
+            wrapped_in_main: !args.code.contains("fn main"),
+            ..Default::default()
+        };
+        let source = match output.wrapped_in_main {
+            true => format!("fn main() {{\n{}\n}}\n", args.code),
+            false => args.code.clone(),
+        };
+        let project = self.project(
+            args.edition.as_deref(),
+            args.dependencies.as_deref(),
+            "main.rs",
+            &source,
+        )?;
+        let dir = project.path().canonicalize()?;
+        self.build(&dir, &mut output).await?;
+        if output.compiled {
+            self.execute(&dir, args.stdin.as_deref(), &mut output)
+                .await?;
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(code: &str) -> RunRustArgs {
+        RunRustArgs {
+            code: code.to_string(),
+            dependencies: None,
+            edition: None,
+            stdin: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_snippet() {
+        let mut snippet = args(
+            "let mut line = String::new();\nstd::io::stdin().read_line(&mut line).unwrap();\nprintln!(\"hello {}\", line.trim());\neprintln!(\"done\");\nstd::process::exit(3);",
+        );
+        snippet.stdin = Some("marvin\n".to_string());
+        let output = Sandbox::new(None).run(&snippet).await.unwrap();
+        assert!(output.compiled);
+        assert!(output.wrapped_in_main);
+        assert_eq!(output.stdout, "hello marvin\n");
+        assert_eq!(output.stderr, "done\n");
+        assert_eq!(output.exit_code, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_build_does_not_see_marvins_environment() {
+        // SAFETY: the variable is only ever read by this test
+        unsafe { std::env::set_var("MARVIN_SANDBOX_TEST_SECRET", "hunter2") };
+        let output = Sandbox::new(None)
+            .run(&args(
+                "println!(\"{}\", env!(\"MARVIN_SANDBOX_TEST_SECRET\"));",
+            ))
+            .await
+            .unwrap();
+        assert!(!output.compiled);
+        assert!(output.diagnostics[0]
+            .contains("environment variable `MARVIN_SANDBOX_TEST_SECRET` not defined"));
+        assert!(output.stdout.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_snippet_cannot_read_outside_its_crate() {
+        let secret_dir = tempfile::tempdir().unwrap();
+        let secret = secret_dir.path().join("id_rsa");
+        std::fs::write(&secret, "hunter2").unwrap();
+        let sandbox = Sandbox::new(None);
+        let output = sandbox
+            .run(&args(&format!(
+                "print!(\"{{}}\", include_str!({:?}));",
+                secret
+            )))
+            .await
+            .unwrap();
+        assert!(!output.compiled);
+        assert!(output.diagnostics[0].contains("couldn't read"));
+        let output = sandbox
+            .run(&args(&format!(
+                "print!(\"{{}} {{}}\", std::fs::read_to_string({:?}).is_err(), option_env!(\"HOME\").is_none());",
+                secret
+            )))
+            .await
+            .unwrap();
+        assert_eq!(output.stdout, "true true");
+    }
+
+    #[tokio::test]
+    async fn test_compile_error_returns_diagnostics() {
+        let output = Sandbox::new(None)
+            .run(&args("fn main() {\n    let x: u32 = \"four\";\n}"))
+            .await
+            .unwrap();
+        assert!(!output.compiled);
+        assert_eq!(output.exit_code, None);
+        assert_eq!(output.diagnostics.len(), 1);
+        assert!(output.diagnostics[0].contains("error[E0308]: mismatched types"));
+    }
+
+    #[tokio::test]
+    async fn test_run_timeout() {
+        let sandbox = Sandbox::new(None).with_limits(Limits {
+            run_timeout: Duration::from_millis(500),
+            ..Default::default()
+        });
+        let output = sandbox.run(&args("loop {}")).await.unwrap();
+        assert!(output.compiled);
+        assert!(output.timed_out);
+        // more input than a pipe holds, for a snippet that never reads it
+        let mut snippet = args("loop {}");
+        snippet.stdin = Some("x".repeat(1024 * 1024));
+        let output = sandbox.run(&snippet).await.unwrap();
+        assert!(output.timed_out);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unknown_dependency_and_edition() {
+        let vendor_dir = tempfile::tempdir().unwrap();
+        let crate_dir = vendor_dir.path().join("itoa-1.0.15");
+        std::fs::create_dir_all(&crate_dir).unwrap();
+        std::fs::write(
+            crate_dir.join("Cargo.toml"),
+            "[package]\nedition = \"2018\"\nname = \"itoa\"\nversion = \"1.0.15\"\n",
+        )
+        .unwrap();
+        let sandbox = Sandbox::new(Some(vendor_dir.path().to_path_buf()));
+        assert_eq!(
+            sandbox.vendored_crates(),
+            BTreeMap::from([("itoa".to_string(), "1.0.15".to_string())])
+        );
+        let mut snippet = args("println!(\"hi\");");
+        snippet.dependencies = Some(vec!["serde".to_string()]);
+        assert!(matches!(
+            sandbox.run(&snippet).await,
+            Err(SandboxError::UnknownDependency { name, available }) if name == "serde" && available == "itoa"
+        ));
+        snippet.dependencies = None;
+        snippet.edition = Some("2027".to_string());
+        assert!(matches!(
+            sandbox.run(&snippet).await,
+            Err(SandboxError::InvalidEdition(_))
+        ));
+    }
+}
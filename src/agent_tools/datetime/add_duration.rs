@@ -0,0 +1,54 @@
+use crate::agent_tools::{
+    datetime::{
+        parse::{add_duration, parse_datetime, parse_duration, Zone},
+        DateTimeOutput,
+    },
+    ToToolResult,
+};
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct AddDurationArgs {
+    #[schemars(
+        description = "ISO-8601 date or date time to start from, e.g. '2026-03-01' or '2026-03-01T14:30:00+01:00', or 'now'"
+    )]
+    pub start: String,
+    #[schemars(
+        description = "ISO-8601 duration, e.g. 'P1Y2M3DT4H5M6S', 'P2W' or 'PT90M'. Prefix with '-' to subtract"
+    )]
+    pub duration: String,
+    #[schemars(
+        description = "Time zone for reading `start` and doing calendar arithmetic, e.g. 'Europe/Berlin'. Defaults to the offset in `start`, or UTC"
+    )]
+    pub timezone: Option<String>,
+}
+
+pub struct AddDuration;
+
+impl Tool for AddDuration {
+    const NAME: &'static str = "add_duration";
+    type Args = AddDurationArgs;
+    type Output = DateTimeOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "adds or subtracts an ISO-8601 duration to a date and time. Years, months, weeks and days are calendar steps (month ends are clamped, e.g. Jan 31 + P1M = Feb 28), hours, minutes and seconds are exact elapsed time".to_string(),
+            parameters: serde_json::to_value(schema_for!(AddDurationArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let zone = Zone::parse_optional(args.timezone.as_deref()).to_tool_result()?;
+        let start = parse_datetime(&args.start, &zone.unwrap_or_default()).to_tool_result()?;
+        let duration = parse_duration(&args.duration).to_tool_result()?;
+        let end = add_duration(&start, zone.as_ref(), &duration).to_tool_result()?;
+        Ok(DateTimeOutput::new(&end, zone.as_ref()))
+    }
+}
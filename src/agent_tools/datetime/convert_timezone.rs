@@ -0,0 +1,53 @@
+use crate::agent_tools::{
+    datetime::{
+        parse::{parse_datetime, Zone},
+        DateTimeOutput,
+    },
+    ToToolResult,
+};
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ConvertTimezoneArgs {
+    #[schemars(
+        description = "ISO-8601 date or date time, e.g. '2026-03-01T14:30:00' or '2026-03-01T14:30:00+01:00', or 'now'"
+    )]
+    pub datetime: String,
+    #[schemars(description = "Time zone to convert to, e.g. 'Asia/Tokyo', 'UTC' or '-08:00'")]
+    pub to: String,
+    #[schemars(
+        description = "Time zone of `datetime` when it has no offset of its own. Defaults to UTC"
+    )]
+    pub from: Option<String>,
+}
+
+pub struct ConvertTimezone;
+
+impl Tool for ConvertTimezone {
+    const NAME: &'static str = "convert_timezone";
+    type Args = ConvertTimezoneArgs;
+    type Output = DateTimeOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "converts a date and time from one time zone to another, accounting for daylight saving time".to_string(),
+            parameters: serde_json::to_value(schema_for!(ConvertTimezoneArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let from = Zone::parse_optional(args.from.as_deref())
+            .to_tool_result()?
+            .unwrap_or_default();
+        let to = Zone::parse(&args.to).to_tool_result()?;
+        let datetime = parse_datetime(&args.datetime, &from).to_tool_result()?;
+        Ok(DateTimeOutput::new(&to.convert(&datetime), Some(&to)))
+    }
+}
@@ -0,0 +1,44 @@
+use crate::agent_tools::{
+    datetime::{parse::Zone, DateTimeOutput},
+    ToToolResult,
+};
+use chrono::Utc;
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct CurrentTimeArgs {
+    #[schemars(
+        description = "IANA time zone like 'America/New_York', 'UTC' or an offset like '+05:30'. Defaults to UTC"
+    )]
+    pub timezone: Option<String>,
+}
+
+pub struct CurrentTime;
+
+impl Tool for CurrentTime {
+    const NAME: &'static str = "current_time";
+    type Args = CurrentTimeArgs;
+    type Output = DateTimeOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "returns the current date and time, optionally in a given time zone. Use this instead of guessing today's date".to_string(),
+            parameters: serde_json::to_value(schema_for!(CurrentTimeArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let zone = Zone::parse_optional(args.timezone.as_deref())
+            .to_tool_result()?
+            .unwrap_or_default();
+        let now = zone.convert(&Utc::now().fixed_offset());
+        Ok(DateTimeOutput::new(&now, Some(&zone)))
+    }
+}
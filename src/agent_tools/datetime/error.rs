@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DateTimeError {
+    #[error("Unknown time zone '{0}', expected an IANA name like 'Europe/Berlin', 'UTC' or an offset like '+05:30'")]
+    UnknownTimeZone(String),
+    #[error("Could not parse '{0}' as a date or time, expected ISO-8601 like '2026-03-01', '2026-03-01T14:30:00' or '2026-03-01T14:30:00+01:00'")]
+    InvalidDateTime(String),
+    #[error("Could not parse '{0}' as a duration, expected ISO-8601 like 'P1Y2M3DT4H5M6S', 'P2W' or '-PT90M'")]
+    InvalidDuration(String),
+    #[error("Local time {time} does not exist in {zone} (skipped by a daylight saving change)")]
+    NonexistentLocalTime { time: String, zone: String },
+    #[error("Result is outside the supported date range")]
+    OutOfRange,
+}
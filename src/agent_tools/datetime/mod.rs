@@ -0,0 +1,46 @@
+use crate::agent_tools::datetime::{
+    add_duration::AddDuration, convert_timezone::ConvertTimezone, current_time::CurrentTime,
+    time_between::TimeBetween,
+};
+use chrono::{DateTime, FixedOffset};
+use parse::Zone;
+use rig::tool::ToolDyn;
+use serde::Serialize;
+
+pub mod add_duration;
+pub mod convert_timezone;
+pub mod current_time;
+pub mod error;
+pub mod parse;
+pub mod time_between;
+
+#[derive(Debug, Serialize)]
+pub struct DateTimeOutput {
+    pub datetime: String,
+    pub timezone: String,
+    pub weekday: String,
+    pub unix_timestamp: i64,
+}
+
+impl DateTimeOutput {
+    pub fn new(datetime: &DateTime<FixedOffset>, zone: Option<&Zone>) -> Self {
+        Self {
+            datetime: datetime.to_rfc3339(),
+            timezone: match zone {
+                Some(zone) => zone.to_string(),
+                None => datetime.offset().to_string(),
+            },
+            weekday: datetime.format("%A").to_string(),
+            unix_timestamp: datetime.timestamp(),
+        }
+    }
+}
+
+pub fn datetime_tools() -> Vec<Box<dyn ToolDyn>> {
+    vec![
+        Box::new(CurrentTime),
+        Box::new(TimeBetween),
+        Box::new(AddDuration),
+        Box::new(ConvertTimezone),
+    ]
+}
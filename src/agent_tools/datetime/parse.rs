@@ -0,0 +1,343 @@
+use crate::agent_tools::datetime::error::DateTimeError;
+use chrono::{
+    DateTime, Days, FixedOffset, LocalResult, Months, NaiveDate, NaiveDateTime, TimeDelta,
+    TimeZone, Utc,
+};
+use chrono_tz::Tz;
+
+const NAIVE_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Zone {
+    Named(Tz),
+    Fixed(FixedOffset),
+}
+
+impl Default for Zone {
+    fn default() -> Self {
+        Zone::Named(Tz::UTC)
+    }
+}
+
+impl std::fmt::Display for Zone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Zone::Named(tz) => write!(f, "{}", tz.name()),
+            Zone::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+fn parse_offset(input: &str) -> Option<FixedOffset> {
+    let sign = match input.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits: String = input[1..].chars().filter(|c| *c != ':').collect();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (
+            digits[..2].parse::<i32>().ok()?,
+            digits[2..].parse::<i32>().ok()?,
+        ),
+        _ => return None,
+    };
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+impl Zone {
+    pub fn parse(input: &str) -> Result<Self, DateTimeError> {
+        let input = input.trim();
+        if input.eq_ignore_ascii_case("utc") || input.eq_ignore_ascii_case("z") {
+            return Ok(Zone::default());
+        }
+        if let Some(offset) = parse_offset(input) {
+            return Ok(Zone::Fixed(offset));
+        }
+        input
+            .parse::<Tz>()
+            .map(Zone::Named)
+            .map_err(|_| DateTimeError::UnknownTimeZone(input.to_string()))
+    }
+    pub fn parse_optional(input: Option<&str>) -> Result<Option<Self>, DateTimeError> {
+        input.map(Zone::parse).transpose()
+    }
+    pub fn convert(&self, datetime: &DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        match self {
+            Zone::Named(tz) => datetime.with_timezone(tz).fixed_offset(),
+            Zone::Fixed(offset) => datetime.with_timezone(offset),
+        }
+    }
+    pub fn localize(&self, naive: NaiveDateTime) -> Result<DateTime<FixedOffset>, DateTimeError> {
+        let local = match self {
+            Zone::Named(tz) => tz.from_local_datetime(&naive).map(|dt| dt.fixed_offset()),
+            Zone::Fixed(offset) => offset.from_local_datetime(&naive),
+        };
+        match local {
+            LocalResult::Single(datetime) => Ok(datetime),
+            LocalResult::Ambiguous(earliest, _) => Ok(earliest),
+            LocalResult::None => Err(DateTimeError::NonexistentLocalTime {
+                time: naive.to_string(),
+                zone: self.to_string(),
+            }),
+        }
+    }
+}
+
+pub fn parse_datetime(input: &str, zone: &Zone) -> Result<DateTime<FixedOffset>, DateTimeError> {
+    let input = input.trim();
+    let invalid = || DateTimeError::InvalidDateTime(input.to_string());
+    match input.to_lowercase().as_str() {
+        "now" => return Ok(zone.convert(&Utc::now().fixed_offset())),
+        "today" => {
+            let today = zone.convert(&Utc::now().fixed_offset()).date_naive();
+            return zone.localize(today.and_time(Default::default()));
+        }
+        _ => (),
+    }
+    if let Some(timestamp) = input.strip_prefix('@') {
+        let seconds = timestamp.parse::<i64>().map_err(|_| invalid())?;
+        let datetime = DateTime::from_timestamp(seconds, 0).ok_or(DateTimeError::OutOfRange)?;
+        return Ok(zone.convert(&datetime.fixed_offset()));
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+        return Ok(datetime);
+    }
+    if let Ok(datetime) = DateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S%.f%:z") {
+        return Ok(datetime);
+    }
+    if let Some(naive) = NAIVE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+    {
+        return zone.localize(naive);
+    }
+    let date = NaiveDate::parse_from_str(input, "%Y-%m-%d").map_err(|_| invalid())?;
+    zone.localize(date.and_time(Default::default()))
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IsoDuration {
+    pub negative: bool,
+    pub months: u32,
+    pub days: u64,
+    pub time: TimeDelta,
+}
+
+pub fn parse_duration(input: &str) -> Result<IsoDuration, DateTimeError> {
+    let trimmed = input.trim();
+    let invalid = || DateTimeError::InvalidDuration(trimmed.to_string());
+    let (negative, rest) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let rest = rest
+        .strip_prefix(['P', 'p'])
+        .ok_or_else(invalid)?
+        .to_uppercase();
+    let (date_part, time_part) = match rest.split_once('T') {
+        Some((date, time)) if !time.is_empty() => (date.to_string(), Some(time.to_string())),
+        Some(_) => return Err(invalid()),
+        None => (rest, None),
+    };
+    let mut duration = IsoDuration {
+        negative,
+        ..Default::default()
+    };
+    let mut components = 0;
+    let mut number = String::new();
+    for c in date_part.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let value = number.parse::<u32>().map_err(|_| invalid())?;
+        number.clear();
+        match c {
+            'Y' => {
+                let months = value.checked_mul(12).ok_or_else(invalid)?;
+                duration.months = duration.months.checked_add(months).ok_or_else(invalid)?;
+            }
+            'M' => duration.months = duration.months.checked_add(value).ok_or_else(invalid)?,
+            'W' => duration.days += u64::from(value) * 7,
+            'D' => duration.days += u64::from(value),
+            _ => return Err(invalid()),
+        }
+        components += 1;
+    }
+    let mut seconds = 0.0;
+    for c in time_part.unwrap_or_default().chars() {
+        if c.is_ascii_digit() || c == '.' || c == ',' {
+            number.push(if c == ',' { '.' } else { c });
+            continue;
+        }
+        let value = number.parse::<f64>().map_err(|_| invalid())?;
+        number.clear();
+        seconds += value
+            * match c {
+                'H' => 3600.0,
+                'M' => 60.0,
+                'S' => 1.0,
+                _ => return Err(invalid()),
+            };
+        components += 1;
+    }
+    if components == 0 || !number.is_empty() {
+        return Err(invalid());
+    }
+    duration.time = TimeDelta::try_milliseconds((seconds * 1000.0).round() as i64)
+        .ok_or(DateTimeError::OutOfRange)?;
+    Ok(duration)
+}
+
+fn shift<Z: TimeZone>(datetime: DateTime<Z>, duration: &IsoDuration) -> Option<DateTime<Z>> {
+    let months = Months::new(duration.months);
+    let days = Days::new(duration.days);
+    if duration.negative {
+        datetime
+            .checked_sub_months(months)?
+            .checked_sub_days(days)?
+            .checked_sub_signed(duration.time)
+    } else {
+        datetime
+            .checked_add_months(months)?
+            .checked_add_days(days)?
+            .checked_add_signed(duration.time)
+    }
+}
+
+// months and days are calendar steps in the given zone, so adding P1D across a DST change keeps the wall clock time
+pub fn add_duration(
+    datetime: &DateTime<FixedOffset>,
+    zone: Option<&Zone>,
+    duration: &IsoDuration,
+) -> Result<DateTime<FixedOffset>, DateTimeError> {
+    match zone {
+        Some(Zone::Named(tz)) => {
+            shift(datetime.with_timezone(tz), duration).map(|datetime| datetime.fixed_offset())
+        }
+        Some(Zone::Fixed(offset)) => shift(datetime.with_timezone(offset), duration),
+        None => shift(*datetime, duration),
+    }
+    .ok_or(DateTimeError::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_datetime() {
+        let berlin = Zone::parse("Europe/Berlin").unwrap();
+        assert_eq!(
+            parse_datetime("2026-07-01T12:00:00", &berlin)
+                .unwrap()
+                .to_rfc3339(),
+            "2026-07-01T12:00:00+02:00"
+        );
+        assert_eq!(
+            parse_datetime("2026-01-01", &berlin).unwrap().to_rfc3339(),
+            "2026-01-01T00:00:00+01:00"
+        );
+        assert_eq!(
+            parse_datetime("2026-01-01T08:30:00Z", &berlin)
+                .unwrap()
+                .to_rfc3339(),
+            "2026-01-01T08:30:00+00:00"
+        );
+        assert_eq!(
+            parse_datetime("@0", &Zone::default()).unwrap().to_rfc3339(),
+            "1970-01-01T00:00:00+00:00"
+        );
+        assert!(matches!(
+            parse_datetime("2026-03-29T02:30:00", &berlin),
+            Err(DateTimeError::NonexistentLocalTime { .. })
+        ));
+        assert!(matches!(
+            parse_datetime("next tuesday", &berlin),
+            Err(DateTimeError::InvalidDateTime(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_zone() {
+        assert_eq!(
+            Zone::parse("+05:30").unwrap().to_string(),
+            "+05:30".to_string()
+        );
+        assert_eq!(Zone::parse("utc").unwrap(), Zone::default());
+        assert!(matches!(
+            Zone::parse("Mars/Olympus"),
+            Err(DateTimeError::UnknownTimeZone(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        let duration = parse_duration("P1Y2M3DT4H5M6.5S").unwrap();
+        assert_eq!(duration.months, 14);
+        assert_eq!(duration.days, 3);
+        assert_eq!(duration.time, TimeDelta::milliseconds(14_706_500));
+        assert_eq!(parse_duration("P2W").unwrap().days, 14);
+        assert!(parse_duration("-PT90M").unwrap().negative);
+        for invalid in [
+            "",
+            "P",
+            "PT",
+            "1D",
+            "P1H",
+            "PT1D",
+            "P1.5D",
+            "P4294967295M1M",
+            "P357913941Y1Y",
+        ] {
+            assert!(
+                matches!(
+                    parse_duration(invalid),
+                    Err(DateTimeError::InvalidDuration(_))
+                ),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_add_duration_across_dst() {
+        let berlin = Zone::parse("Europe/Berlin").unwrap();
+        let start = parse_datetime("2026-03-28T12:00:00", &berlin).unwrap();
+        let day = parse_duration("P1D").unwrap();
+        let hours = parse_duration("PT24H").unwrap();
+        assert_eq!(
+            add_duration(&start, Some(&berlin), &day)
+                .unwrap()
+                .to_rfc3339(),
+            "2026-03-29T12:00:00+02:00"
+        );
+        assert_eq!(
+            add_duration(&start, Some(&berlin), &hours)
+                .unwrap()
+                .to_rfc3339(),
+            "2026-03-29T13:00:00+02:00"
+        );
+        let end_of_month = parse_datetime("2026-01-31", &Zone::default()).unwrap();
+        assert_eq!(
+            add_duration(&end_of_month, None, &parse_duration("P1M").unwrap())
+                .unwrap()
+                .to_rfc3339(),
+            "2026-02-28T00:00:00+00:00"
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use crate::agent_tools::{
+    datetime::parse::{parse_datetime, Zone},
+    ToToolResult,
+};
+use chrono::{DateTime, Datelike, FixedOffset, Months};
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct TimeBetweenArgs {
+    #[schemars(
+        description = "ISO-8601 date or date time, e.g. '2026-03-01' or '2026-03-01T14:30:00+01:00', or 'now'"
+    )]
+    pub start: String,
+    #[schemars(description = "ISO-8601 date or date time, or 'now'")]
+    pub end: String,
+    #[schemars(
+        description = "Time zone for dates without an offset, e.g. 'Europe/Berlin'. Defaults to UTC"
+    )]
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeBetweenOutput {
+    pub total_seconds: i64,
+    pub days: f64,
+    pub hours: f64,
+    pub breakdown: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub end_before_start: bool,
+}
+
+fn plural(value: i64, unit: &str) -> Option<String> {
+    match value {
+        0 => None,
+        1 => Some(format!("1 {}", unit)),
+        value => Some(format!("{} {}s", value, unit)),
+    }
+}
+
+pub fn breakdown(start: &DateTime<FixedOffset>, end: &DateTime<FixedOffset>) -> String {
+    let (start, end) = if start <= end {
+        (*start, end.with_timezone(start.offset()))
+    } else {
+        (end.with_timezone(start.offset()), *start)
+    };
+    let mut months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+    let mut anchor = start;
+    while months > 0 {
+        match start.checked_add_months(Months::new(months as u32)) {
+            Some(candidate) if candidate <= end => {
+                anchor = candidate;
+                break;
+            }
+            _ => months -= 1,
+        }
+    }
+    let rest = end - anchor;
+    let parts: Vec<String> = [
+        plural(i64::from(months / 12), "year"),
+        plural(i64::from(months % 12), "month"),
+        plural(rest.num_days(), "day"),
+        plural(rest.num_hours() % 24, "hour"),
+        plural(rest.num_minutes() % 60, "minute"),
+        plural(rest.num_seconds() % 60, "second"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if parts.is_empty() {
+        "0 seconds".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+pub struct TimeBetween;
+
+impl Tool for TimeBetween {
+    const NAME: &'static str = "time_between";
+    type Args = TimeBetweenArgs;
+    type Output = TimeBetweenOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "returns the time between two dates or date times, as total seconds, fractional days and hours, and a calendar breakdown in years, months, days, hours, minutes and seconds. Use 'now' for questions like how many days until a date".to_string(),
+            parameters: serde_json::to_value(schema_for!(TimeBetweenArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let zone = Zone::parse_optional(args.timezone.as_deref())
+            .to_tool_result()?
+            .unwrap_or_default();
+        let start = parse_datetime(&args.start, &zone).to_tool_result()?;
+        let end = parse_datetime(&args.end, &zone).to_tool_result()?;
+        let total_seconds = (end - start).num_seconds();
+        Ok(TimeBetweenOutput {
+            total_seconds,
+            days: total_seconds as f64 / 86_400.0,
+            hours: total_seconds as f64 / 3600.0,
+            breakdown: breakdown(&start, &end),
+            end_before_start: end < start,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn between(start: &str, end: &str) -> TimeBetweenOutput {
+        TimeBetween
+            .call(TimeBetweenArgs {
+                start: start.to_string(),
+                end: end.to_string(),
+                timezone: Some("America/New_York".to_string()),
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_time_between() {
+        let output = between("2026-01-31", "2027-03-02T06:30:00").await;
+        assert_eq!(
+            output.breakdown,
+            "1 year, 1 month, 2 days, 6 hours, 30 minutes"
+        );
+        assert!(!output.end_before_start);
+
+        let output = between("2026-03-08T00:00:00", "2026-03-09T00:00:00").await;
+        assert_eq!(output.hours, 23.0);
+        assert_eq!(output.breakdown, "23 hours");
+
+        let output = between("2026-12-25", "2026-12-24").await;
+        assert_eq!(output.days, -1.0);
+        assert_eq!(output.breakdown, "1 day");
+        assert!(output.end_before_start);
+    }
+}
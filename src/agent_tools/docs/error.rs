@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+pub const GENERATE_HINT: &str = "generate docs with `cargo +nightly rustdoc -p <crate> -- -Z unstable-options --output-format json` (written to target/doc/<crate>.json), or install the standard library's with `rustup component add rust-docs-json --toolchain nightly`";
+
+#[derive(Debug, Error)]
+pub enum DocsError {
+    #[error("Query is empty")]
+    EmptyQuery,
+    #[error("No rustdoc JSON found, {GENERATE_HINT}")]
+    NoDocs,
+    #[error("No rustdoc JSON for '{name}', available: {available}. To add it, {GENERATE_HINT}")]
+    UnknownCrate { name: String, available: String },
+    #[error("Nothing matching '{query}' in {searched}")]
+    NotFound { query: String, searched: String },
+    #[error("Could not parse rustdoc JSON {path}: {source}")]
+    Parse {
+        path: String,
+        source: serde_json::Error,
+    },
+    #[error("Loading docs failed: {0}")]
+    Join(#[from] tokio::task::JoinError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,335 @@
+use crate::agent_tools::docs::error::DocsError;
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+// std re-exports most of its API from core and alloc, so a std query searches all three
+const STD_FAMILY: &[&str] = &["std", "alloc", "core"];
+
+#[derive(Debug, Deserialize)]
+pub struct Item {
+    pub name: Option<String>,
+    pub docs: Option<String>,
+    #[serde(default)]
+    pub visibility: Value,
+    #[serde(default)]
+    pub deprecation: Value,
+    pub inner: Value,
+}
+
+impl Item {
+    pub fn kind(&self) -> &str {
+        match &self.inner {
+            Value::Object(map) => map.keys().next().map(String::as_str).unwrap_or("item"),
+            Value::String(kind) => kind,
+            _ => "item",
+        }
+    }
+    pub fn body(&self) -> &Value {
+        &self.inner[self.kind()]
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Summary {
+    crate_id: u64,
+    path: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCrate {
+    root: Value,
+    index: HashMap<String, Item>,
+    paths: HashMap<String, Summary>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub path: Vec<String>,
+    pub id: String,
+}
+
+impl Entry {
+    pub fn display(&self) -> String {
+        self.path.join("::")
+    }
+}
+
+#[derive(Debug)]
+pub struct CrateDocs {
+    pub name: String,
+    pub index: HashMap<String, Item>,
+    pub entries: Vec<Entry>,
+}
+
+// ids are integers in recent format versions and strings like "0:123" in older ones
+pub fn id_key(id: &Value) -> String {
+    match id {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    }
+}
+
+impl CrateDocs {
+    pub fn parse(json: &str, file: &Path) -> Result<Self, DocsError> {
+        let raw: RawCrate = serde_json::from_str(json).map_err(|source| DocsError::Parse {
+            path: file.display().to_string(),
+            source,
+        })?;
+        let root = id_key(&raw.root);
+        let name = raw
+            .paths
+            .get(&root)
+            .and_then(|summary| summary.path.first().cloned())
+            .or_else(|| raw.index.get(&root).and_then(|item| item.name.clone()))
+            .unwrap_or_default();
+        let mut entries: Vec<Entry> = raw
+            .paths
+            .into_iter()
+            .filter(|(id, summary)| summary.crate_id == 0 && raw.index.contains_key(id))
+            .map(|(id, summary)| Entry {
+                path: summary.path,
+                id,
+            })
+            .collect();
+        let mut members = Vec::new();
+        for entry in &entries {
+            let item = &raw.index[&entry.id];
+            let children = match item.kind() {
+                "trait" => list_ids(&item.body()["items"]),
+                _ => list_ids(&item.body()["impls"])
+                    .iter()
+                    .filter_map(|id| raw.index.get(id))
+                    .filter(|item| item.body()["trait"].is_null())
+                    .flat_map(|item| list_ids(&item.body()["items"]))
+                    .collect(),
+            };
+            for id in children {
+                if let Some(name) = raw.index.get(&id).and_then(|item| item.name.clone()) {
+                    let mut path = entry.path.clone();
+                    path.push(name);
+                    members.push(Entry { path, id });
+                }
+            }
+        }
+        entries.extend(members);
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(Self {
+            name,
+            index: raw.index,
+            entries,
+        })
+    }
+    pub fn item(&self, id: &str) -> Option<&Item> {
+        self.index.get(id)
+    }
+}
+
+pub fn list_ids(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|ids| ids.iter().filter(|id| !id.is_null()).map(id_key).collect())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rank {
+    Exact,
+    IgnoringCase,
+    Partial,
+}
+
+fn segments(query: &str) -> Vec<String> {
+    // generic arguments are not part of item paths, so Vec<T>::push looks up Vec::push
+    let mut depth = 0;
+    let stripped: String = query
+        .chars()
+        .filter(|c| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => return depth == 0 && !c.is_whitespace(),
+            }
+            false
+        })
+        .collect();
+    stripped
+        .split("::")
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn rank(entry: &Entry, query: &[String]) -> Option<Rank> {
+    let path = &entry.path[1..];
+    if path.len() >= query.len() {
+        let tail = &path[path.len() - query.len()..];
+        if tail == query {
+            return Some(Rank::Exact);
+        }
+        if tail
+            .iter()
+            .zip(query)
+            .all(|(a, b)| a.eq_ignore_ascii_case(b))
+        {
+            return Some(Rank::IgnoringCase);
+        }
+    }
+    let name = entry.path.last()?.to_lowercase();
+    let needle = query.last()?.to_lowercase();
+    name.contains(&needle).then_some(Rank::Partial)
+}
+
+pub struct Match<'a> {
+    pub docs: &'a CrateDocs,
+    pub entry: &'a Entry,
+    pub rank: Rank,
+}
+
+// a leading crate name picks the crates to search and is dropped from the path to match
+pub fn search<'a>(crates: &'a [Arc<CrateDocs>], query: &str) -> Vec<Match<'a>> {
+    let mut query = segments(query);
+    if query.len() > 1
+        && crates
+            .iter()
+            .any(|docs| docs.name == query[0] || STD_FAMILY.contains(&query[0].as_str()))
+    {
+        query.remove(0);
+    }
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches: Vec<Match> = crates
+        .iter()
+        .flat_map(|docs| {
+            docs.entries
+                .iter()
+                .filter_map(|entry| rank(entry, &query).map(|rank| Match { docs, entry, rank }))
+        })
+        .collect();
+    let best = matches.iter().map(|m| m.rank).min();
+    matches.retain(|m| Some(m.rank) == best);
+    matches.sort_by_key(|m| (m.entry.path.len(), m.entry.display()));
+    matches
+}
+
+pub struct DocsIndex {
+    dirs: Vec<PathBuf>,
+    sysroot_dirs: OnceLock<Vec<PathBuf>>,
+    loaded: Mutex<HashMap<PathBuf, Arc<CrateDocs>>>,
+}
+
+fn sysroot_docs(toolchain: Option<&str>) -> Option<PathBuf> {
+    let mut command = Command::new("rustc");
+    if let Some(toolchain) = toolchain {
+        command.arg(toolchain);
+    }
+    let output = command.args(["--print", "sysroot"]).output().ok()?;
+    let sysroot = String::from_utf8(output.stdout).ok()?;
+    let dir = Path::new(sysroot.trim()).join("share/doc/rust/json");
+    (output.status.success() && dir.is_dir()).then_some(dir)
+}
+
+impl DocsIndex {
+    pub fn new(dirs: Vec<PathBuf>) -> Self {
+        Self {
+            dirs,
+            sysroot_dirs: OnceLock::new(),
+            loaded: Mutex::new(HashMap::new()),
+        }
+    }
+    #[cfg(test)]
+    pub fn without_sysroot(dirs: Vec<PathBuf>) -> Self {
+        let index = Self::new(dirs);
+        let _ = index.sysroot_dirs.set(Vec::new());
+        index
+    }
+    // std docs come from the rust-docs-json component, usually only installed for nightly
+    fn dirs(&self) -> impl Iterator<Item = &PathBuf> {
+        let sysroot = self.sysroot_dirs.get_or_init(|| {
+            let mut dirs: Vec<PathBuf> = [sysroot_docs(None), sysroot_docs(Some("+nightly"))]
+                .into_iter()
+                .flatten()
+                .collect();
+            dirs.dedup();
+            dirs
+        });
+        self.dirs.iter().chain(sysroot)
+    }
+    // crate name to JSON file, the first directory that has a crate wins
+    pub fn available(&self) -> BTreeMap<String, PathBuf> {
+        let mut available = BTreeMap::new();
+        for dir in self.dirs() {
+            let Ok(read_dir) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for path in read_dir.flatten().map(|entry| entry.path()) {
+                if path.extension().is_some_and(|ext| ext == "json")
+                    && let Some(stem) = path.file_stem()
+                {
+                    available
+                        .entry(stem.to_string_lossy().to_string())
+                        .or_insert(path);
+                }
+            }
+        }
+        available
+    }
+    fn load(&self, file: &Path) -> Result<Arc<CrateDocs>, DocsError> {
+        if let Some(docs) = self.loaded.lock().unwrap().get(file) {
+            return Ok(docs.clone());
+        }
+        let docs = Arc::new(CrateDocs::parse(&std::fs::read_to_string(file)?, file)?);
+        self.loaded
+            .lock()
+            .unwrap()
+            .insert(file.to_path_buf(), docs.clone());
+        Ok(docs)
+    }
+    // picks the crates for a query: an explicit crate, the query's leading segment, or everything available
+    pub fn crates_for(
+        &self,
+        query: &str,
+        crate_name: Option<&str>,
+    ) -> Result<Vec<Arc<CrateDocs>>, DocsError> {
+        let available = self.available();
+        if available.is_empty() {
+            return Err(DocsError::NoDocs);
+        }
+        let first = segments(query).into_iter().next().unwrap_or_default();
+        let wanted = crate_name
+            .map(|name| name.replace('-', "_"))
+            .or_else(|| {
+                (query.contains("::") && available.contains_key(&first)).then_some(first.clone())
+            })
+            .or_else(|| {
+                STD_FAMILY
+                    .contains(&first.as_str())
+                    .then_some("std".to_string())
+            });
+        let names: Vec<&str> = match wanted.as_deref() {
+            Some(name) if STD_FAMILY.contains(&name) => STD_FAMILY
+                .iter()
+                .copied()
+                .filter(|name| available.contains_key(*name))
+                .collect(),
+            Some(name) => vec![name],
+            None => available.keys().map(String::as_str).collect(),
+        };
+        if names.is_empty() || names.iter().any(|name| !available.contains_key(*name)) {
+            return Err(DocsError::UnknownCrate {
+                name: wanted.unwrap_or_default(),
+                available: available.keys().cloned().collect::<Vec<_>>().join(", "),
+            });
+        }
+        names
+            .into_iter()
+            .map(|name| self.load(&available[name]))
+            .collect()
+    }
+}
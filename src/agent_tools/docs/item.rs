@@ -0,0 +1,330 @@
+use crate::agent_tools::docs::{
+    index::{id_key, list_ids, CrateDocs, Entry, Item},
+    render::{bounds_, function, generics, path, type_, where_clause},
+};
+use serde::Serialize;
+use serde_json::Value;
+
+const MAX_DOC_CHARS: usize = 4000;
+const MAX_MEMBERS: usize = 40;
+
+#[derive(Debug, Serialize)]
+pub struct DocItem {
+    pub path: String,
+    pub kind: String,
+    pub signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docs: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trait_impls: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub implementors: Vec<String>,
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}\n... [docs truncated]", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+fn capped(mut values: Vec<String>) -> Vec<String> {
+    if values.len() > MAX_MEMBERS {
+        let more = values.len() - MAX_MEMBERS;
+        values.truncate(MAX_MEMBERS);
+        values.push(format!("... {} more", more));
+    }
+    values
+}
+
+fn visibility(item: &Item) -> &'static str {
+    match item.visibility.as_str() {
+        Some("public") => "pub ",
+        _ => "",
+    }
+}
+
+fn impl_line(item: &Item) -> String {
+    let body = item.body();
+    let unsafety = if body["is_unsafe"].as_bool() == Some(true) {
+        "unsafe "
+    } else {
+        ""
+    };
+    let negative = if body["is_negative"].as_bool() == Some(true) {
+        "!"
+    } else {
+        ""
+    };
+    let trait_ = match &body["trait"] {
+        Value::Null => String::new(),
+        trait_ => format!("{}{} for ", negative, path(trait_)),
+    };
+    format!(
+        "{}impl{} {}{}{}",
+        unsafety,
+        generics(&body["generics"]),
+        trait_,
+        type_(&body["for"]),
+        where_clause(&body["generics"])
+    )
+}
+
+fn fields(docs: &CrateDocs, ids: &Value, stripped: bool) -> String {
+    let mut fields: Vec<String> = list_ids(ids)
+        .iter()
+        .filter_map(|id| docs.item(id))
+        .filter(|field| field.visibility.as_str() == Some("public"))
+        .map(|field| {
+            format!(
+                "{}{}: {}",
+                visibility(field),
+                field.name.as_deref().unwrap_or("_"),
+                type_(field.body())
+            )
+        })
+        .collect();
+    if stripped || fields.len() < list_ids(ids).len() {
+        fields.push("/* private fields */".to_string());
+    }
+    fields.join(", ")
+}
+
+fn variant(docs: &CrateDocs, item: &Item) -> String {
+    let name = item.name.as_deref().unwrap_or("_");
+    let kind = &item.body()["kind"];
+    if let Some(tuple) = kind.get("tuple") {
+        let types: Vec<String> = tuple
+            .as_array()
+            .map(|ids| {
+                ids.iter()
+                    .map(|id| match id {
+                        Value::Null => "_".to_string(),
+                        id => docs
+                            .item(&id_key(id))
+                            .map(|field| type_(field.body()))
+                            .unwrap_or_else(|| "_".to_string()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        return format!("{}({})", name, types.join(", "));
+    }
+    if let Some(fields_) = kind.get("struct") {
+        let stripped = fields_["has_stripped_fields"].as_bool() == Some(true);
+        return format!(
+            "{} {{ {} }}",
+            name,
+            fields(docs, &fields_["fields"], stripped)
+        );
+    }
+    name.to_string()
+}
+
+fn signature(docs: &CrateDocs, item: &Item) -> String {
+    let name = item.name.as_deref().unwrap_or("_");
+    let body = item.body();
+    let vis = visibility(item);
+    match item.kind() {
+        "function" => format!("{}{}", vis, function(name, body)),
+        "struct" | "union" => {
+            let keyword = item.kind();
+            let head = format!("{}{} {}{}", vis, keyword, name, generics(&body["generics"]));
+            let where_ = where_clause(&body["generics"]);
+            let kind = &body["kind"];
+            // unions keep their fields on the body rather than in a kind
+            if let Some(plain) = kind.get("plain").or(body.get("fields").map(|_| body)) {
+                let stripped = plain["has_stripped_fields"].as_bool() == Some(true);
+                format!(
+                    "{}{} {{ {} }}",
+                    head,
+                    where_,
+                    fields(docs, &plain["fields"], stripped)
+                )
+            } else if let Some(tuple) = kind.get("tuple") {
+                let types: Vec<String> = tuple
+                    .as_array()
+                    .map(|ids| {
+                        ids.iter()
+                            .map(|id| match id {
+                                Value::Null => "/* private */".to_string(),
+                                id => docs
+                                    .item(&id_key(id))
+                                    .map(|field| {
+                                        format!("{}{}", visibility(field), type_(field.body()))
+                                    })
+                                    .unwrap_or_else(|| "_".to_string()),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                format!("{}({}){};", head, types.join(", "), where_)
+            } else {
+                format!("{}{};", head, where_)
+            }
+        }
+        "enum" => {
+            let variants: Vec<String> = list_ids(&body["variants"])
+                .iter()
+                .filter_map(|id| docs.item(id))
+                .map(|item| variant(docs, item))
+                .collect();
+            format!(
+                "{}enum {}{}{} {{ {} }}",
+                vis,
+                name,
+                generics(&body["generics"]),
+                where_clause(&body["generics"]),
+                capped(variants).join(", ")
+            )
+        }
+        "trait" => {
+            let supertraits = match body["bounds"].as_array() {
+                Some(bounds) if !bounds.is_empty() => format!(": {}", bounds_(&body["bounds"])),
+                _ => String::new(),
+            };
+            let unsafety = if body["is_unsafe"].as_bool() == Some(true) {
+                "unsafe "
+            } else {
+                ""
+            };
+            format!(
+                "{}{}trait {}{}{}{}",
+                vis,
+                unsafety,
+                name,
+                generics(&body["generics"]),
+                supertraits,
+                where_clause(&body["generics"])
+            )
+        }
+        "type_alias" | "typedef" => format!(
+            "{}type {}{} = {};",
+            vis,
+            name,
+            generics(&body["generics"]),
+            type_(&body["type"])
+        ),
+        "constant" | "assoc_const" => {
+            let value = body["const"]["expr"]
+                .as_str()
+                .or(body["expr"].as_str())
+                .or(body["value"].as_str())
+                .map(|value| format!(" = {}", value))
+                .unwrap_or_default();
+            format!("{}const {}: {}{};", vis, name, type_(&body["type"]), value)
+        }
+        "static" => {
+            let mutability = match body["is_mutable"].as_bool().or(body["mutable"].as_bool()) {
+                Some(true) => "mut ",
+                _ => "",
+            };
+            format!(
+                "{}static {}{}: {};",
+                vis,
+                mutability,
+                name,
+                type_(&body["type"])
+            )
+        }
+        "assoc_type" => {
+            let bounds = match body["bounds"].as_array() {
+                Some(bounds) if !bounds.is_empty() => format!(": {}", bounds_(&body["bounds"])),
+                _ => String::new(),
+            };
+            let default = match &body["type"] {
+                Value::Null => String::new(),
+                ty => format!(" = {}", type_(ty)),
+            };
+            format!("type {}{}{};", name, bounds, default)
+        }
+        "macro" => truncate(body.as_str().unwrap_or(name), 500),
+        "module" => format!("{}mod {}", vis, name),
+        kind => format!("{} {}", kind, name),
+    }
+}
+
+pub fn describe(docs: &CrateDocs, entry: &Entry) -> Option<DocItem> {
+    let item = docs.item(&entry.id)?;
+    let body = item.body();
+    let mut doc_item = DocItem {
+        path: entry.display(),
+        kind: item.kind().to_string(),
+        signature: signature(docs, item),
+        docs: item
+            .docs
+            .as_deref()
+            .map(|docs| truncate(docs, MAX_DOC_CHARS)),
+        deprecated: match &item.deprecation {
+            Value::Null => None,
+            deprecation => Some(
+                [&deprecation["since"], &deprecation["note"]]
+                    .iter()
+                    .filter_map(|part| part.as_str())
+                    .collect::<Vec<_>>()
+                    .join(": "),
+            ),
+        },
+        members: Vec::new(),
+        trait_impls: Vec::new(),
+        implementors: Vec::new(),
+    };
+    match item.kind() {
+        "trait" => {
+            doc_item.members = capped(
+                list_ids(&body["items"])
+                    .iter()
+                    .filter_map(|id| docs.item(id))
+                    .map(|member| signature(docs, member))
+                    .collect(),
+            );
+            doc_item.implementors = capped(
+                list_ids(&body["implementations"])
+                    .iter()
+                    .filter_map(|id| docs.item(id))
+                    .map(impl_line)
+                    .collect(),
+            );
+        }
+        "module" => {
+            doc_item.members = capped(
+                list_ids(&body["items"])
+                    .iter()
+                    .filter_map(|id| docs.item(id))
+                    .filter(|member| member.name.is_some())
+                    .map(|member| {
+                        format!(
+                            "{} {}",
+                            member.kind(),
+                            member.name.as_deref().unwrap_or("_")
+                        )
+                    })
+                    .collect(),
+            );
+        }
+        _ => {
+            let impls: Vec<&Item> = list_ids(&body["impls"])
+                .iter()
+                .filter_map(|id| docs.item(id))
+                .filter(|item| item.body()["blanket_impl"].is_null())
+                .collect();
+            let (inherent, trait_impls): (Vec<&Item>, Vec<&Item>) = impls
+                .into_iter()
+                .partition(|item| item.body()["trait"].is_null());
+            doc_item.members = capped(
+                inherent
+                    .iter()
+                    .flat_map(|item| list_ids(&item.body()["items"]))
+                    .filter_map(|id| docs.item(&id))
+                    .map(|member| signature(docs, member))
+                    .collect(),
+            );
+            doc_item.trait_impls = capped(trait_impls.into_iter().map(impl_line).collect());
+        }
+    }
+    Some(doc_item)
+}
@@ -0,0 +1,13 @@
+pub mod error;
+pub mod index;
+pub mod item;
+pub mod render;
+pub mod rust_docs;
+
+use crate::agent_tools::docs::{index::DocsIndex, rust_docs::RustDocs};
+use rig::tool::ToolDyn;
+use std::path::PathBuf;
+
+pub fn docs_tools(dirs: Vec<PathBuf>) -> Vec<Box<dyn ToolDyn>> {
+    vec![Box::new(RustDocs::new(DocsIndex::new(dirs)))]
+}
@@ -0,0 +1,354 @@
+use serde_json::Value;
+
+// rustdoc JSON renames fields between format versions, so lookups accept either spelling
+fn field<'a>(value: &'a Value, names: &[&str]) -> &'a Value {
+    names
+        .iter()
+        .map(|name| &value[*name])
+        .find(|value| !value.is_null())
+        .unwrap_or(&Value::Null)
+}
+
+fn list(value: &Value) -> &[Value] {
+    value.as_array().map(Vec::as_slice).unwrap_or_default()
+}
+
+fn join(values: &[Value], render: fn(&Value) -> String, separator: &str) -> String {
+    values
+        .iter()
+        .map(render)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+// the single key of an externally tagged enum such as {"generic": "T"}
+fn variant(value: &Value) -> Option<(&str, &Value)> {
+    match value {
+        Value::Object(map) if map.len() == 1 => map.iter().next().map(|(k, v)| (k.as_str(), v)),
+        Value::String(name) => Some((name.as_str(), &Value::Null)),
+        _ => None,
+    }
+}
+
+fn is_mutable(value: &Value) -> bool {
+    field(value, &["is_mutable", "mutable"])
+        .as_bool()
+        .unwrap_or(false)
+}
+
+pub fn path(value: &Value) -> String {
+    let name = field(value, &["path", "name"]).as_str().unwrap_or("_");
+    let name = name.strip_prefix("$crate::").unwrap_or(name);
+    format!("{}{}", name, generic_args(&value["args"]))
+}
+
+pub fn generic_args(args: &Value) -> String {
+    match variant(args) {
+        Some(("angle_bracketed", args)) => {
+            let mut parts: Vec<String> = list(&args["args"])
+                .iter()
+                .map(|arg| match variant(arg) {
+                    Some(("type", ty)) => type_(ty),
+                    Some(("lifetime", lifetime)) => lifetime.as_str().unwrap_or("'_").to_string(),
+                    Some(("const", constant)) => {
+                        constant["expr"].as_str().unwrap_or("_").to_string()
+                    }
+                    _ => "_".to_string(),
+                })
+                .collect();
+            for constraint in list(field(args, &["constraints", "bindings"])) {
+                let name = format!(
+                    "{}{}",
+                    constraint["name"].as_str().unwrap_or("_"),
+                    generic_args(&constraint["args"])
+                );
+                parts.push(match variant(&constraint["binding"]) {
+                    Some(("equality", term)) => match variant(term) {
+                        Some(("type", ty)) => format!("{} = {}", name, type_(ty)),
+                        Some(("constant", constant)) => {
+                            format!("{} = {}", name, constant["expr"].as_str().unwrap_or("_"))
+                        }
+                        _ => format!("{} = {}", name, type_(term)),
+                    },
+                    Some(("constraint", bounds)) => format!("{}: {}", name, bounds_(bounds)),
+                    _ => name,
+                });
+            }
+            match parts.is_empty() {
+                true => String::new(),
+                false => format!("<{}>", parts.join(", ")),
+            }
+        }
+        Some(("parenthesized", args)) => {
+            let output = match &args["output"] {
+                Value::Null => String::new(),
+                output => format!(" -> {}", type_(output)),
+            };
+            format!("({}){}", join(list(&args["inputs"]), type_, ", "), output)
+        }
+        _ => String::new(),
+    }
+}
+
+pub fn type_(ty: &Value) -> String {
+    let Some((kind, inner)) = variant(ty) else {
+        return "_".to_string();
+    };
+    match kind {
+        "resolved_path" => path(inner),
+        "generic" | "primitive" => inner.as_str().unwrap_or("_").to_string(),
+        "borrowed_ref" => {
+            let lifetime = match inner["lifetime"].as_str() {
+                Some(lifetime) => format!("{} ", lifetime),
+                None => String::new(),
+            };
+            let mutability = if is_mutable(inner) { "mut " } else { "" };
+            format!("&{}{}{}", lifetime, mutability, type_(&inner["type"]))
+        }
+        "raw_pointer" => {
+            let mutability = if is_mutable(inner) { "mut" } else { "const" };
+            format!("*{} {}", mutability, type_(&inner["type"]))
+        }
+        "slice" => format!("[{}]", type_(inner)),
+        "array" => format!(
+            "[{}; {}]",
+            type_(&inner["type"]),
+            inner["len"].as_str().unwrap_or("_")
+        ),
+        "tuple" => match list(inner) {
+            [single] => format!("({},)", type_(single)),
+            types => format!("({})", join(types, type_, ", ")),
+        },
+        "impl_trait" => format!("impl {}", bounds_(inner)),
+        "dyn_trait" => {
+            let mut parts: Vec<String> = list(&inner["traits"])
+                .iter()
+                .map(|poly| path(&poly["trait"]))
+                .collect();
+            if let Some(lifetime) = inner["lifetime"].as_str() {
+                parts.push(lifetime.to_string());
+            }
+            format!("dyn {}", parts.join(" + "))
+        }
+        "qualified_path" => {
+            let name = inner["name"].as_str().unwrap_or("_");
+            let self_type = type_(&inner["self_type"]);
+            // an unambiguous associated type has a trait with an empty path, as in Self::Error
+            match &inner["trait"] {
+                trait_
+                    if field(trait_, &["path", "name"])
+                        .as_str()
+                        .unwrap_or("")
+                        .is_empty() =>
+                {
+                    format!("{}::{}", self_type, name)
+                }
+                trait_ => format!("<{} as {}>::{}", self_type, path(trait_), name),
+            }
+        }
+        "function_pointer" => {
+            let sig = field(inner, &["sig", "decl"]);
+            format!("fn({}){}", inputs(sig), output(sig))
+        }
+        "pat" => type_(&inner["type"]),
+        _ => "_".to_string(),
+    }
+}
+
+fn bound(bound: &Value) -> String {
+    match variant(bound) {
+        Some(("trait_bound", inner)) => {
+            let modifier = match inner["modifier"].as_str() {
+                Some("maybe") => "?",
+                Some("maybe_const") => "~const ",
+                _ => "",
+            };
+            format!("{}{}", modifier, path(&inner["trait"]))
+        }
+        Some(("outlives", lifetime)) => lifetime.as_str().unwrap_or("'_").to_string(),
+        Some(("use", args)) => format!("use<{}>", join(list(args), type_or_name, ", ")),
+        _ => "_".to_string(),
+    }
+}
+
+fn type_or_name(value: &Value) -> String {
+    match value {
+        Value::String(name) => name.clone(),
+        value => variant(value)
+            .and_then(|(_, name)| name.as_str())
+            .unwrap_or("_")
+            .to_string(),
+    }
+}
+
+pub fn bounds_(bounds: &Value) -> String {
+    join(list(bounds), bound, " + ")
+}
+
+pub fn generics(generics: &Value) -> String {
+    let params: Vec<String> = list(&generics["params"])
+        .iter()
+        .filter_map(|param| {
+            let name = param["name"].as_str().unwrap_or("_");
+            match variant(&param["kind"]) {
+                Some(("lifetime", lifetime)) => match list(&lifetime["outlives"]) {
+                    [] => Some(name.to_string()),
+                    outlives => Some(format!(
+                        "{}: {}",
+                        name,
+                        join(outlives, |l| l.as_str().unwrap_or("'_").to_string(), " + ")
+                    )),
+                },
+                // impl Trait arguments show up as synthetic parameters, they are printed in place
+                Some(("type", ty)) if ty["is_synthetic"].as_bool() == Some(true) => None,
+                Some(("type", ty)) => {
+                    let mut param = name.to_string();
+                    if !list(&ty["bounds"]).is_empty() {
+                        param = format!("{}: {}", param, bounds_(&ty["bounds"]));
+                    }
+                    if !ty["default"].is_null() {
+                        param = format!("{} = {}", param, type_(&ty["default"]));
+                    }
+                    Some(param)
+                }
+                Some(("const", constant)) => {
+                    Some(format!("const {}: {}", name, type_(&constant["type"])))
+                }
+                _ => Some(name.to_string()),
+            }
+        })
+        .collect();
+    match params.is_empty() {
+        true => String::new(),
+        false => format!("<{}>", params.join(", ")),
+    }
+}
+
+pub fn where_clause(generics: &Value) -> String {
+    let predicates: Vec<String> = list(&generics["where_predicates"])
+        .iter()
+        .map(|predicate| match variant(predicate) {
+            Some(("bound_predicate", inner)) => {
+                format!("{}: {}", type_(&inner["type"]), bounds_(&inner["bounds"]))
+            }
+            Some(("lifetime_predicate" | "region_predicate", inner)) => format!(
+                "{}: {}",
+                inner["lifetime"].as_str().unwrap_or("'_"),
+                join(
+                    list(&inner["outlives"]),
+                    |l| l.as_str().unwrap_or("'_").to_string(),
+                    " + "
+                )
+            ),
+            Some(("eq_predicate", inner)) => {
+                format!("{} = {}", type_(&inner["lhs"]), type_or_term(&inner["rhs"]))
+            }
+            _ => "_".to_string(),
+        })
+        .collect();
+    match predicates.is_empty() {
+        true => String::new(),
+        false => format!(" where {}", predicates.join(", ")),
+    }
+}
+
+fn type_or_term(term: &Value) -> String {
+    match variant(term) {
+        Some(("type", ty)) => type_(ty),
+        _ => type_(term),
+    }
+}
+
+fn inputs(sig: &Value) -> String {
+    list(&sig["inputs"])
+        .iter()
+        .map(|input| {
+            let name = input[0].as_str().unwrap_or("_");
+            let ty = &input[1];
+            if name != "self" {
+                return format!("{}: {}", name, type_(ty));
+            }
+            match variant(ty) {
+                Some(("generic", generic)) if generic == "Self" => "self".to_string(),
+                Some(("borrowed_ref", inner)) if inner["type"]["generic"] == "Self" => {
+                    let lifetime = match inner["lifetime"].as_str() {
+                        Some(lifetime) => format!("{} ", lifetime),
+                        None => String::new(),
+                    };
+                    let mutability = if is_mutable(inner) { "mut " } else { "" };
+                    format!("&{}{}self", lifetime, mutability)
+                }
+                _ => format!("self: {}", type_(ty)),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn output(sig: &Value) -> String {
+    match &sig["output"] {
+        Value::Null => String::new(),
+        output => format!(" -> {}", type_(output)),
+    }
+}
+
+pub fn function(name: &str, function: &Value) -> String {
+    let header = &function["header"];
+    let mut qualifiers = String::new();
+    for (flag, keyword) in [
+        (&["is_const", "const"], "const "),
+        (&["is_async", "async"], "async "),
+        (&["is_unsafe", "unsafe"], "unsafe "),
+    ] {
+        if field(header, flag).as_bool() == Some(true) {
+            qualifiers.push_str(keyword);
+        }
+    }
+    match variant(&header["abi"]) {
+        Some(("Rust", _)) | None => {}
+        Some((abi, _)) => qualifiers.push_str(&format!("extern \"{}\" ", abi)),
+    }
+    let sig = field(function, &["sig", "decl"]);
+    format!(
+        "{}fn {}{}({}){}{}",
+        qualifiers,
+        name,
+        generics(&function["generics"]),
+        inputs(sig),
+        output(sig),
+        where_clause(&function["generics"])
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_function_signature() {
+        let pick = json!({
+            "sig": {
+                "inputs": [
+                    ["self", {"borrowed_ref": {"lifetime": null, "is_mutable": true, "type": {"generic": "Self"}}}],
+                    ["f", {"impl_trait": [{"trait_bound": {"trait": {"path": "FnOnce", "args": {"parenthesized": {"inputs": [{"generic": "T"}], "output": {"primitive": "bool"}}}}, "modifier": "none"}}]}],
+                    ["items", {"borrowed_ref": {"lifetime": "'a", "is_mutable": false, "type": {"slice": {"tuple": [{"primitive": "u8"}]}}}}]
+                ],
+                "output": {"resolved_path": {"path": "$crate::option::Option", "args": {"angle_bracketed": {"args": [{"type": {"qualified_path": {"name": "Item", "args": null, "self_type": {"generic": "I"}, "trait": {"path": "Iterator", "args": null}}}}], "constraints": []}}}}
+            },
+            "generics": {
+                "params": [
+                    {"name": "'a", "kind": {"lifetime": {"outlives": []}}},
+                    {"name": "T", "kind": {"type": {"bounds": [{"trait_bound": {"trait": {"path": "Sized", "args": null}, "modifier": "maybe"}}], "default": null, "is_synthetic": false}}},
+                    {"name": "impl FnOnce(T) -> bool", "kind": {"type": {"bounds": [], "default": null, "is_synthetic": true}}}
+                ],
+                "where_predicates": [{"bound_predicate": {"type": {"generic": "T"}, "bounds": [{"outlives": "'a"}], "generic_params": []}}]
+            },
+            "header": {"is_const": true, "is_unsafe": false, "is_async": false, "abi": "Rust"}
+        });
+        assert_eq!(
+            function("pick", &pick),
+            "const fn pick<'a, T: ?Sized>(&mut self, f: impl FnOnce(T) -> bool, items: &'a [(u8,)]) -> option::Option<<I as Iterator>::Item> where T: 'a"
+        );
+    }
+}
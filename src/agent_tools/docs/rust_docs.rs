@@ -0,0 +1,185 @@
+use crate::agent_tools::{
+    docs::{
+        error::DocsError,
+        index::{search, DocsIndex},
+        item::{describe, DocItem},
+    },
+    ToToolResult,
+};
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const MAX_DETAILED: usize = 3;
+const MAX_OTHER_MATCHES: usize = 20;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct RustDocsArgs {
+    #[schemars(
+        description = "An item path such as 'std::pin::Pin::as_mut', 'Pin::as_mut' or 'tokio::spawn', or a search term matched against item names"
+    )]
+    pub query: String,
+    #[schemars(
+        description = "Crate to search, e.g. 'serde'. Defaults to the query's leading crate name, or every crate with local docs"
+    )]
+    pub crate_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RustDocsOutput {
+    pub matches: Vec<DocItem>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub other_matches: Vec<String>,
+}
+
+pub fn lookup(index: &DocsIndex, args: &RustDocsArgs) -> Result<RustDocsOutput, DocsError> {
+    if args.query.trim().is_empty() {
+        return Err(DocsError::EmptyQuery);
+    }
+    let crates = index.crates_for(&args.query, args.crate_name.as_deref())?;
+    let found = search(&crates, &args.query);
+    if found.is_empty() {
+        return Err(DocsError::NotFound {
+            query: args.query.clone(),
+            searched: crates
+                .iter()
+                .map(|docs| docs.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        });
+    }
+    Ok(RustDocsOutput {
+        matches: found
+            .iter()
+            .take(MAX_DETAILED)
+            .filter_map(|found| describe(found.docs, found.entry))
+            .collect(),
+        other_matches: found
+            .iter()
+            .skip(MAX_DETAILED)
+            .take(MAX_OTHER_MATCHES)
+            .map(|found| found.entry.display())
+            .collect(),
+    })
+}
+
+pub struct RustDocs {
+    index: Arc<DocsIndex>,
+}
+
+impl RustDocs {
+    pub fn new(index: DocsIndex) -> Self {
+        Self {
+            index: Arc::new(index),
+        }
+    }
+}
+
+impl Tool for RustDocs {
+    const NAME: &'static str = "rust_docs";
+    type Args = RustDocsArgs;
+    type Output = RustDocsOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "looks up Rust API documentation offline in locally built rustdoc JSON for the standard library and the project's dependencies. Returns signatures, doc comments, methods and trait impls for an item path or search term. Prefer it over web search for questions about Rust APIs".to_string(),
+            parameters: serde_json::to_value(schema_for!(RustDocsArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let index = self.index.clone();
+        // parsing std's JSON takes a moment, keep it off the async runtime
+        tokio::task::spawn_blocking(move || lookup(&index, &args))
+            .await
+            .map_err(DocsError::from)
+            .and_then(|result| result)
+            .to_tool_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> (tempfile::TempDir, DocsIndex) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("geometry.json"),
+            include_str!("fixture.json"),
+        )
+        .unwrap();
+        let index = DocsIndex::without_sysroot(vec![dir.path().to_path_buf()]);
+        (dir, index)
+    }
+
+    fn args(query: &str) -> RustDocsArgs {
+        RustDocsArgs {
+            query: query.to_string(),
+            crate_name: None,
+        }
+    }
+
+    #[test]
+    fn test_struct_signature_methods_and_impls() {
+        let (_dir, index) = index();
+        let output = lookup(&index, &args("geometry::Point")).unwrap();
+        let point = &output.matches[0];
+        assert_eq!(point.path, "geometry::Point");
+        assert_eq!(
+            point.signature,
+            "pub struct Point<T> { pub x: T, pub y: T }"
+        );
+        assert_eq!(point.docs.as_deref(), Some("A point in 2D space."));
+        assert_eq!(
+            point.members,
+            vec!["pub fn add(&self, other: &Point<T>) -> Point<T>"]
+        );
+        assert!(point
+            .trait_impls
+            .contains(&"impl Display for Point<i32>".to_string()));
+        assert!(point
+            .trait_impls
+            .contains(&"impl<T: fmt::Debug> Debug for Point<T>".to_string()));
+    }
+
+    #[test]
+    fn test_method_and_function_lookup() {
+        let (_dir, index) = index();
+        let add = lookup(&index, &args("Point<T>::add")).unwrap();
+        assert_eq!(add.matches[0].path, "geometry::Point::add");
+        assert_eq!(add.matches[0].docs.as_deref(), Some("Adds two points."));
+        let parse = lookup(&index, &args("parse")).unwrap();
+        assert_eq!(
+            parse.matches[0].signature,
+            "pub fn parse<'a>(input: &'a str, strict: bool) -> Result<Point<i32>, Box<dyn std::error::Error + 'a>>"
+        );
+        let shape = lookup(&index, &args("shape")).unwrap();
+        assert_eq!(shape.matches[0].members, vec!["fn area(&self) -> f64"]);
+    }
+
+    #[test]
+    fn test_missing_docs_explain_how_to_generate() {
+        let (_dir, index) = index();
+        assert!(matches!(
+            lookup(&index, &args("geometry::Circle")),
+            Err(DocsError::NotFound { .. })
+        ));
+        let error = lookup(
+            &index,
+            &RustDocsArgs {
+                crate_name: Some("serde".to_string()),
+                ..args("Serialize")
+            },
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("available: geometry"));
+        assert!(error.to_string().contains("--output-format json"));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::agent_tools::{
+    fs::{
+        error::FsError,
+        workspace::{read_text, Workspace},
+        write_file::PlannedWrite,
+        WriteOutput,
+    },
+    ToToolResult,
+};
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ApplyPatchArgs {
+    #[schemars(description = "Path of an existing file, relative to the workspace root")]
+    pub path: String,
+    #[schemars(
+        description = "Exact text to replace, including whitespace. It must occur exactly once in the file, so include a few surrounding lines if needed"
+    )]
+    pub search: String,
+    #[schemars(description = "Text to put in its place")]
+    pub replace: String,
+}
+
+pub struct ApplyPatch {
+    workspace: Workspace,
+}
+
+pub fn plan_patch(workspace: &Workspace, args: &ApplyPatchArgs) -> Result<PlannedWrite, FsError> {
+    let path = workspace.resolve(&args.path)?;
+    let old = read_text(&path, &args.path)?;
+    let display = workspace.relative(&path);
+    match old.matches(&args.search).count() {
+        _ if args.search.is_empty() => return Err(FsError::PatchNotFound(display)),
+        0 => return Err(FsError::PatchNotFound(display)),
+        1 => (),
+        count => {
+            return Err(FsError::PatchAmbiguous {
+                path: display,
+                count,
+            })
+        }
+    }
+    Ok(PlannedWrite {
+        new: old.replacen(&args.search, &args.replace, 1),
+        old: Some(old),
+        display,
+        path,
+    })
+}
+
+impl ApplyPatch {
+    pub fn new(workspace: Workspace) -> Self {
+        Self { workspace }
+    }
+}
+
+impl Tool for ApplyPatch {
+    const NAME: &'static str = "apply_patch";
+    type Args = ApplyPatchArgs;
+    type Output = WriteOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "edits an existing workspace file by replacing one exact, unique occurrence of `search` with `replace`. The user is shown a diff and must confirm the edit".to_string(),
+            parameters: serde_json::to_value(schema_for!(ApplyPatchArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        plan_patch(&self.workspace, &args)
+            .and_then(PlannedWrite::apply)
+            .to_tool_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_requires_unique_match() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "let a = 1;\nlet b = 1;\n").unwrap();
+        let workspace = Workspace::new(dir.path()).unwrap();
+        let args = |search: &str| ApplyPatchArgs {
+            path: "lib.rs".to_string(),
+            search: search.to_string(),
+            replace: "let b = 2;".to_string(),
+        };
+        assert!(matches!(
+            plan_patch(&workspace, &args("= 1;")),
+            Err(FsError::PatchAmbiguous { count: 2, .. })
+        ));
+        assert!(matches!(
+            plan_patch(&workspace, &args("let c")),
+            Err(FsError::PatchNotFound(_))
+        ));
+        let output = plan_patch(&workspace, &args("let b = 1;"))
+            .unwrap()
+            .apply()
+            .unwrap();
+        assert!(output.diff.contains("-let b = 1;\n+let b = 2;"));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("lib.rs")).unwrap(),
+            "let a = 1;\nlet b = 2;\n"
+        );
+    }
+}
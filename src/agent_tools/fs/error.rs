@@ -0,0 +1,33 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FsError {
+    #[error("'{0}' is outside the workspace root")]
+    OutsideRoot(String),
+    #[error("'{0}' is ignored by .gitignore")]
+    Ignored(String),
+    #[error("'{0}' may hold credentials, so it is never read or written")]
+    Secret(String),
+    #[error("'{0}' does not exist")]
+    NotFound(String),
+    #[error("'{0}' is not a file")]
+    NotAFile(String),
+    #[error("'{0}' is not a directory")]
+    NotADirectory(String),
+    #[error("'{0}' is not a text file")]
+    Binary(String),
+    #[error("Line range {start}-{end} is invalid for a file with {total} lines")]
+    InvalidLineRange {
+        start: usize,
+        end: usize,
+        total: usize,
+    },
+    #[error("Invalid pattern '{pattern}': {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+    #[error("The text to replace was not found in '{0}'")]
+    PatchNotFound(String),
+    #[error("The text to replace occurs {count} times in '{path}', include more context so it is unique")]
+    PatchAmbiguous { path: String, count: usize },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,80 @@
+use crate::agent_tools::{
+    fs::{error::FsError, workspace::Workspace},
+    ToToolResult,
+};
+use globset::GlobBuilder;
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+const MAX_RESULTS: usize = 200;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct GlobArgs {
+    #[schemars(
+        description = "Glob pattern relative to the workspace root, e.g. 'src/**/*.rs' or '**/Cargo.toml'"
+    )]
+    pub pattern: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GlobOutput {
+    pub paths: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+pub struct Glob {
+    workspace: Workspace,
+}
+
+impl Glob {
+    pub fn new(workspace: Workspace) -> Self {
+        Self { workspace }
+    }
+    fn find(&self, args: &GlobArgs) -> Result<GlobOutput, FsError> {
+        let matcher = GlobBuilder::new(&args.pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| FsError::InvalidPattern {
+                pattern: args.pattern.clone(),
+                reason: e.kind().to_string(),
+            })?
+            .compile_matcher();
+        let mut paths: Vec<String> = self
+            .workspace
+            .walk(self.workspace.root(), None)
+            .map(|path| self.workspace.relative(&path))
+            .filter(|relative| matcher.is_match(relative))
+            .take(MAX_RESULTS + 1)
+            .collect();
+        let truncated = paths.len() > MAX_RESULTS;
+        paths.truncate(MAX_RESULTS);
+        Ok(GlobOutput { paths, truncated })
+    }
+}
+
+impl Tool for Glob {
+    const NAME: &'static str = "glob";
+    type Args = GlobArgs;
+    type Output = GlobOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "finds workspace paths matching a glob pattern, skipping anything ignored by .gitignore. Returns at most {} paths",
+                MAX_RESULTS
+            ),
+            parameters: serde_json::to_value(schema_for!(GlobArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.find(&args).to_tool_result()
+    }
+}
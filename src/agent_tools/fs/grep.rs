@@ -0,0 +1,184 @@
+use crate::agent_tools::{
+    fs::{
+        error::FsError,
+        workspace::{read_text, Workspace},
+    },
+    ToToolResult,
+};
+use globset::Glob;
+use regex::RegexBuilder;
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+const MAX_MATCHES: usize = 200;
+const MAX_LINE_LEN: usize = 300;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct GrepArgs {
+    #[schemars(description = "Regular expression to search for")]
+    pub pattern: String,
+    #[schemars(
+        description = "File or directory to search, relative to the workspace root. Defaults to the root"
+    )]
+    pub path: Option<String>,
+    #[schemars(description = "Only search files whose path matches this glob, e.g. '*.rs'")]
+    pub glob: Option<String>,
+    #[schemars(description = "Match case-insensitively. Defaults to false")]
+    pub case_insensitive: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GrepMatch {
+    pub path: String,
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GrepOutput {
+    pub matches: Vec<GrepMatch>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+pub struct Grep {
+    workspace: Workspace,
+}
+
+impl Grep {
+    pub fn new(workspace: Workspace) -> Self {
+        Self { workspace }
+    }
+    fn search(&self, args: &GrepArgs) -> Result<GrepOutput, FsError> {
+        let invalid = |pattern: &str, reason: String| FsError::InvalidPattern {
+            pattern: pattern.to_string(),
+            reason,
+        };
+        let regex = RegexBuilder::new(&args.pattern)
+            .case_insensitive(args.case_insensitive.unwrap_or(false))
+            .build()
+            .map_err(|e| invalid(&args.pattern, e.to_string()))?;
+        let glob = args
+            .glob
+            .as_deref()
+            .map(|glob| {
+                Glob::new(glob)
+                    .map(|glob| glob.compile_matcher())
+                    .map_err(|e| invalid(glob, e.kind().to_string()))
+            })
+            .transpose()?;
+        let display = args.path.as_deref().unwrap_or(".");
+        let root = self.workspace.resolve(display)?;
+        if !root.exists() {
+            return Err(FsError::NotFound(display.to_string()));
+        }
+        let files: Vec<_> = match root.is_file() {
+            true => vec![root],
+            false => self
+                .workspace
+                .walk(&root, None)
+                .filter(|path| path.is_file())
+                .collect(),
+        };
+        let mut matches = Vec::new();
+        for file in files {
+            let relative = self.workspace.relative(&file);
+            if glob.as_ref().is_some_and(|glob| {
+                !glob.is_match(&relative)
+                    && !file.file_name().is_some_and(|name| glob.is_match(name))
+            }) {
+                continue;
+            }
+            let Ok(text) = read_text(&file, &relative) else {
+                continue;
+            };
+            for (index, line) in text.lines().enumerate() {
+                if !regex.is_match(line) {
+                    continue;
+                }
+                if matches.len() == MAX_MATCHES {
+                    return Ok(GrepOutput {
+                        matches,
+                        truncated: true,
+                    });
+                }
+                matches.push(GrepMatch {
+                    path: relative.clone(),
+                    line: index + 1,
+                    text: line.trim().chars().take(MAX_LINE_LEN).collect(),
+                });
+            }
+        }
+        Ok(GrepOutput {
+            matches,
+            truncated: false,
+        })
+    }
+}
+
+impl Tool for Grep {
+    const NAME: &'static str = "grep";
+    type Args = GrepArgs;
+    type Output = GrepOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "searches workspace text files for lines matching a regular expression, skipping anything ignored by .gitignore. Returns at most {} matches with path and line number",
+                MAX_MATCHES
+            ),
+            parameters: serde_json::to_value(schema_for!(GrepArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.search(&args).to_tool_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grep_with_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "// TODO one\nfn a() {}\n// todo two\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "TODO docs\n").unwrap();
+        let tool = Grep::new(Workspace::new(dir.path()).unwrap());
+        let output = tool
+            .search(&GrepArgs {
+                pattern: "todo".to_string(),
+                path: None,
+                glob: Some("*.rs".to_string()),
+                case_insensitive: Some(true),
+            })
+            .unwrap();
+        let found: Vec<_> = output
+            .matches
+            .iter()
+            .map(|m| (m.path.as_str(), m.line))
+            .collect();
+        assert_eq!(found, vec![("src/lib.rs", 1), ("src/lib.rs", 3)]);
+        assert!(matches!(
+            tool.search(&GrepArgs {
+                pattern: "(".to_string(),
+                path: None,
+                glob: None,
+                case_insensitive: None,
+            }),
+            Err(FsError::InvalidPattern { .. })
+        ));
+    }
+}
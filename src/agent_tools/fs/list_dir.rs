@@ -0,0 +1,90 @@
+use crate::agent_tools::{
+    fs::{error::FsError, workspace::Workspace},
+    ToToolResult,
+};
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ListDirArgs {
+    #[schemars(description = "Directory relative to the workspace root. Defaults to the root")]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DirEntry {
+    pub name: String,
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListDirOutput {
+    pub path: String,
+    pub entries: Vec<DirEntry>,
+}
+
+pub struct ListDir {
+    workspace: Workspace,
+}
+
+impl ListDir {
+    pub fn new(workspace: Workspace) -> Self {
+        Self { workspace }
+    }
+    fn list(&self, args: &ListDirArgs) -> Result<ListDirOutput, FsError> {
+        let display = args.path.as_deref().unwrap_or(".");
+        let dir = self.workspace.resolve(display)?;
+        if !dir.exists() {
+            return Err(FsError::NotFound(display.to_string()));
+        }
+        if !dir.is_dir() {
+            return Err(FsError::NotADirectory(display.to_string()));
+        }
+        let entries = self
+            .workspace
+            .walk(&dir, Some(1))
+            .filter_map(|path| {
+                let metadata = path.symlink_metadata().ok()?;
+                let (kind, size) = match metadata.file_type() {
+                    kind if kind.is_dir() => ("dir", None),
+                    kind if kind.is_symlink() => ("symlink", None),
+                    _ => ("file", Some(metadata.len())),
+                };
+                Some(DirEntry {
+                    name: path.file_name()?.to_string_lossy().to_string(),
+                    kind,
+                    size,
+                })
+            })
+            .collect();
+        Ok(ListDirOutput {
+            path: self.workspace.relative(&dir),
+            entries,
+        })
+    }
+}
+
+impl Tool for ListDir {
+    const NAME: &'static str = "list_dir";
+    type Args = ListDirArgs;
+    type Output = ListDirOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "lists the files and directories in a workspace directory, skipping anything ignored by .gitignore".to_string(),
+            parameters: serde_json::to_value(schema_for!(ListDirArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.list(&args).to_tool_result()
+    }
+}
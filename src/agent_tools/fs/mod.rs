@@ -0,0 +1,73 @@
+use crate::agent_tools::fs::{
+    apply_patch::{plan_patch, ApplyPatch},
+    error::FsError,
+    glob::Glob,
+    grep::Grep,
+    list_dir::ListDir,
+    read_file::ReadFile,
+    workspace::Workspace,
+    write_file::{plan_write, WriteFile},
+};
+use rig::tool::{Tool, ToolDyn};
+use serde::Serialize;
+use similar::TextDiff;
+
+pub mod apply_patch;
+pub mod error;
+pub mod glob;
+pub mod grep;
+pub mod list_dir;
+pub mod read_file;
+pub mod workspace;
+pub mod write_file;
+
+const MAX_DIFF_LINES: usize = 200;
+
+#[derive(Debug, Serialize)]
+pub struct WriteOutput {
+    pub path: String,
+    pub created: bool,
+    pub diff: String,
+}
+
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    let diff = TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(2)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string();
+    let lines: Vec<&str> = diff.lines().collect();
+    if lines.len() <= MAX_DIFF_LINES {
+        return diff;
+    }
+    format!(
+        "{}\n... {} more diff lines",
+        lines[..MAX_DIFF_LINES].join("\n"),
+        lines.len() - MAX_DIFF_LINES
+    )
+}
+
+// writes are confirmed by the user before the tool runs, so the chat asks for the same diff the tool will apply
+pub fn write_preview(
+    workspace: &Workspace,
+    name: &str,
+    args: &str,
+) -> Option<Result<String, FsError>> {
+    let planned = match name {
+        WriteFile::NAME => plan_write(workspace, &serde_json::from_str(args).ok()?),
+        ApplyPatch::NAME => plan_patch(workspace, &serde_json::from_str(args).ok()?),
+        _ => return None,
+    };
+    Some(planned.map(|planned| planned.diff()))
+}
+
+pub fn fs_tools(workspace: Workspace) -> Vec<Box<dyn ToolDyn>> {
+    vec![
+        Box::new(ReadFile::new(workspace.clone())),
+        Box::new(ListDir::new(workspace.clone())),
+        Box::new(Glob::new(workspace.clone())),
+        Box::new(Grep::new(workspace.clone())),
+        Box::new(WriteFile::new(workspace.clone())),
+        Box::new(ApplyPatch::new(workspace)),
+    ]
+}
@@ -0,0 +1,124 @@
+use crate::agent_tools::{
+    fs::{
+        error::FsError,
+        workspace::{read_text, Workspace},
+    },
+    ToToolResult,
+};
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+
+const MAX_LINES: usize = 400;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ReadFileArgs {
+    #[schemars(description = "Path relative to the workspace root")]
+    pub path: String,
+    #[schemars(description = "First line to read, starting at 1. Defaults to 1")]
+    pub start_line: Option<usize>,
+    #[schemars(description = "Last line to read, inclusive. Defaults to the end of the file")]
+    pub end_line: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadFileOutput {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub total_lines: usize,
+    pub content: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+pub struct ReadFile {
+    workspace: Workspace,
+}
+
+impl ReadFile {
+    pub fn new(workspace: Workspace) -> Self {
+        Self { workspace }
+    }
+    fn read(&self, args: &ReadFileArgs) -> Result<ReadFileOutput, FsError> {
+        let path = self.workspace.resolve(&args.path)?;
+        let text = read_text(&path, &args.path)?;
+        let lines: Vec<&str> = text.lines().collect();
+        let total_lines = lines.len();
+        let start_line = args.start_line.unwrap_or(1);
+        let end_line = args.end_line.unwrap_or(total_lines).min(total_lines);
+        if start_line == 0 || (start_line > end_line && total_lines > 0) {
+            return Err(FsError::InvalidLineRange {
+                start: start_line,
+                end: args.end_line.unwrap_or(total_lines),
+                total: total_lines,
+            });
+        }
+        let truncated = end_line >= start_line && end_line + 1 - start_line > MAX_LINES;
+        let end_line = match truncated {
+            true => start_line + MAX_LINES - 1,
+            false => end_line,
+        };
+        Ok(ReadFileOutput {
+            path: self.workspace.relative(&path),
+            start_line,
+            end_line,
+            total_lines,
+            content: lines
+                .get(start_line.saturating_sub(1)..end_line)
+                .unwrap_or_default()
+                .join("\n"),
+            truncated,
+        })
+    }
+}
+
+impl Tool for ReadFile {
+    const NAME: &'static str = "read_file";
+    type Args = ReadFileArgs;
+    type Output = ReadFileOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "reads a text file in the workspace, optionally a range of lines. At most {} lines are returned per call, use start_line to page through longer files",
+                MAX_LINES
+            ),
+            parameters: serde_json::to_value(schema_for!(ReadFileArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.read(&args).to_tool_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_line_range() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "one\ntwo\nthree\nfour\n").unwrap();
+        let tool = ReadFile::new(Workspace::new(dir.path()).unwrap());
+        let args = |start_line, end_line| ReadFileArgs {
+            path: "notes.txt".to_string(),
+            start_line,
+            end_line,
+        };
+        let output = tool.read(&args(Some(2), Some(3))).unwrap();
+        assert_eq!(output.content, "two\nthree");
+        assert_eq!(output.total_lines, 4);
+        assert_eq!(tool.read(&args(None, None)).unwrap().end_line, 4);
+        assert!(matches!(
+            tool.read(&args(Some(4), Some(2))),
+            Err(FsError::InvalidLineRange { .. })
+        ));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::agent_tools::fs::error::FsError;
+use ignore::{gitignore::GitignoreBuilder, Match, WalkBuilder};
+use std::path::{Component, Path, PathBuf};
+
+const GIT_DIR: &str = ".git";
+// directories of keys and credentials, denied wherever they sit in the workspace
+const SECRET_DIRS: &[&str] = &[".ssh", ".aws", ".gnupg"];
+const SECRET_FILES: &[&str] = &[".netrc", ".pgpass", ".npmrc", ".pypirc"];
+const SECRET_EXTENSIONS: &[&str] = &["pem", "key", "p12", "pfx"];
+const SSH_KEY_PREFIXES: &[&str] = &["id_rsa", "id_dsa", "id_ecdsa", "id_ed25519"];
+
+// .env files, private keys and credential stores, denied whatever .gitignore says since
+// .env is where marvin's own API keys live
+fn is_secret(relative: &Path) -> bool {
+    relative.components().any(|component| {
+        let Some(name) = component.as_os_str().to_str() else {
+            return false;
+        };
+        name == ".env"
+            || name.starts_with(".env.")
+            || SECRET_DIRS.contains(&name)
+            || SECRET_FILES.contains(&name)
+            || SSH_KEY_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+            || Path::new(name)
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| SECRET_EXTENSIONS.contains(&extension))
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    root: PathBuf,
+}
+
+impl Workspace {
+    pub fn new(root: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self {
+            root: root.as_ref().canonicalize()?,
+        })
+    }
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+    pub fn relative(&self, path: &Path) -> String {
+        match path.strip_prefix(&self.root) {
+            Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+            Ok(relative) => relative.display().to_string(),
+            Err(_) => path.display().to_string(),
+        }
+    }
+    fn lexical(&self, path: &str) -> Result<PathBuf, FsError> {
+        let outside = || FsError::OutsideRoot(path.to_string());
+        let joined = self.root.join(path);
+        let mut normalized = PathBuf::new();
+        for component in joined.components() {
+            match component {
+                Component::ParentDir => {
+                    if !normalized.pop() {
+                        return Err(outside());
+                    }
+                }
+                Component::CurDir => (),
+                component => normalized.push(component),
+            }
+        }
+        if !normalized.starts_with(&self.root) {
+            return Err(outside());
+        }
+        Ok(normalized)
+    }
+    // canonicalizing the deepest existing ancestor resolves symlinks, so a link pointing out of the root is rejected
+    pub fn resolve(&self, path: &str) -> Result<PathBuf, FsError> {
+        let lexical = self.lexical(path)?;
+        let mut existing = lexical.as_path();
+        let mut rest = Vec::new();
+        while !existing.exists() {
+            rest.push(
+                existing
+                    .file_name()
+                    .ok_or_else(|| FsError::OutsideRoot(path.to_string()))?,
+            );
+            existing = existing
+                .parent()
+                .ok_or_else(|| FsError::OutsideRoot(path.to_string()))?;
+        }
+        let mut resolved = existing.canonicalize()?;
+        if !resolved.starts_with(&self.root) {
+            return Err(FsError::OutsideRoot(path.to_string()));
+        }
+        resolved.extend(rest.into_iter().rev());
+        if resolved.strip_prefix(&self.root).is_ok_and(is_secret) {
+            return Err(FsError::Secret(path.to_string()));
+        }
+        if self.is_ignored(&resolved) {
+            return Err(FsError::Ignored(path.to_string()));
+        }
+        Ok(resolved)
+    }
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return true;
+        };
+        if is_secret(relative)
+            || relative
+                .components()
+                .any(|component| component.as_os_str() == GIT_DIR)
+        {
+            return true;
+        }
+        let mut ignored = false;
+        let mut dir = self.root.clone();
+        let mut components = relative.components().peekable();
+        while let Some(component) = components.next() {
+            let gitignore = dir.join(".gitignore");
+            if gitignore.is_file() {
+                let mut builder = GitignoreBuilder::new(&dir);
+                builder.add(&gitignore);
+                if let Ok(matcher) = builder.build() {
+                    match matcher.matched_path_or_any_parents(path, path.is_dir()) {
+                        Match::Ignore(_) => ignored = true,
+                        Match::Whitelist(_) => ignored = false,
+                        Match::None => (),
+                    }
+                }
+            }
+            if components.peek().is_none() {
+                break;
+            }
+            dir.push(component);
+        }
+        ignored
+    }
+    pub fn walk(&self, dir: &Path, max_depth: Option<usize>) -> impl Iterator<Item = PathBuf> {
+        WalkBuilder::new(dir)
+            .hidden(false)
+            .require_git(false)
+            .follow_links(false)
+            .max_depth(max_depth)
+            .filter_entry(|entry| {
+                entry.file_name() != GIT_DIR && !is_secret(Path::new(entry.file_name()))
+            })
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build()
+            .filter_map(Result::ok)
+            .filter(move |entry| entry.depth() > 0)
+            .map(|entry| entry.into_path())
+    }
+}
+
+pub fn read_text(path: &Path, display: &str) -> Result<String, FsError> {
+    if !path.exists() {
+        return Err(FsError::NotFound(display.to_string()));
+    }
+    if !path.is_file() {
+        return Err(FsError::NotAFile(display.to_string()));
+    }
+    let bytes = std::fs::read(path)?;
+    if bytes.contains(&0) {
+        return Err(FsError::Binary(display.to_string()));
+    }
+    String::from_utf8(bytes).map_err(|_| FsError::Binary(display.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_rejects_escapes() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join("main.rs"), "fn main() {}").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("link")).unwrap();
+        let workspace = Workspace::new(dir.path()).unwrap();
+
+        assert!(workspace.resolve("src/../src/main.rs").is_ok());
+        assert!(workspace.resolve("src/new/file.rs").is_ok());
+        for path in ["../secret.txt", "src/../../secret.txt", "/etc/passwd"] {
+            assert!(
+                matches!(workspace.resolve(path), Err(FsError::OutsideRoot(_))),
+                "{}",
+                path
+            );
+        }
+        #[cfg(unix)]
+        {
+            assert!(matches!(
+                workspace.resolve("link/secret.txt"),
+                Err(FsError::OutsideRoot(_))
+            ));
+            assert!(matches!(
+                workspace.resolve("link/new.txt"),
+                Err(FsError::OutsideRoot(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_gitignore_is_respected() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".gitignore"),
+            "target/\n.env\n*.log\n!keep.log\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("target").join("debug")).unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join(".gitignore"), "generated.rs\n").unwrap();
+        for file in [
+            ".env",
+            "a.log",
+            "keep.log",
+            "src/lib.rs",
+            "src/generated.rs",
+            "target/debug/x",
+        ] {
+            std::fs::write(dir.path().join(file), "").unwrap();
+        }
+        let workspace = Workspace::new(dir.path()).unwrap();
+        for ignored in ["a.log", "target/debug/x", "src/generated.rs", ".git/config"] {
+            assert!(
+                matches!(workspace.resolve(ignored), Err(FsError::Ignored(_))),
+                "{}",
+                ignored
+            );
+        }
+        assert!(workspace.resolve("keep.log").is_ok());
+        // credentials are denied even where .gitignore lets them through
+        std::fs::write(dir.path().join(".gitignore"), "!.env\n").unwrap();
+        for secret in [
+            ".env",
+            ".env.local",
+            "src/server.pem",
+            "deploy/id_ed25519",
+            ".ssh/config",
+            ".aws/credentials",
+        ] {
+            assert!(
+                matches!(workspace.resolve(secret), Err(FsError::Secret(_))),
+                "{}",
+                secret
+            );
+        }
+        std::fs::write(
+            dir.path().join(".gitignore"),
+            "target/\n.env\n*.log\n!keep.log\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("src").join("server.pem"), "").unwrap();
+        let walked: Vec<String> = workspace
+            .walk(workspace.root(), None)
+            .map(|path| workspace.relative(&path))
+            .collect();
+        assert_eq!(
+            walked,
+            vec![
+                ".gitignore",
+                "keep.log",
+                "src",
+                "src/.gitignore",
+                "src/lib.rs"
+            ]
+        );
+    }
+}
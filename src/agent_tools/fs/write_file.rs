@@ -0,0 +1,102 @@
+use crate::agent_tools::{
+    fs::{
+        error::FsError,
+        unified_diff,
+        workspace::{read_text, Workspace},
+        WriteOutput,
+    },
+    ToToolResult,
+};
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct WriteFileArgs {
+    #[schemars(
+        description = "Path relative to the workspace root. Missing parent directories are created"
+    )]
+    pub path: String,
+    #[schemars(description = "The complete new content of the file")]
+    pub content: String,
+}
+
+pub struct WriteFile {
+    workspace: Workspace,
+}
+
+pub struct PlannedWrite {
+    pub path: PathBuf,
+    pub display: String,
+    pub old: Option<String>,
+    pub new: String,
+}
+
+impl PlannedWrite {
+    pub fn diff(&self) -> String {
+        unified_diff(
+            &self.display,
+            self.old.as_deref().unwrap_or_default(),
+            &self.new,
+        )
+    }
+    pub fn apply(self) -> Result<WriteOutput, FsError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, &self.new)?;
+        Ok(WriteOutput {
+            diff: self.diff(),
+            created: self.old.is_none(),
+            path: self.display,
+        })
+    }
+}
+
+pub fn plan_write(workspace: &Workspace, args: &WriteFileArgs) -> Result<PlannedWrite, FsError> {
+    let path = workspace.resolve(&args.path)?;
+    if path.is_dir() {
+        return Err(FsError::NotAFile(args.path.clone()));
+    }
+    let old = match path.exists() {
+        true => Some(read_text(&path, &args.path)?),
+        false => None,
+    };
+    Ok(PlannedWrite {
+        display: workspace.relative(&path),
+        path,
+        old,
+        new: args.content.clone(),
+    })
+}
+
+impl WriteFile {
+    pub fn new(workspace: Workspace) -> Self {
+        Self { workspace }
+    }
+}
+
+impl Tool for WriteFile {
+    const NAME: &'static str = "write_file";
+    type Args = WriteFileArgs;
+    type Output = WriteOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "creates or overwrites a text file in the workspace. The user is shown a diff and must confirm the write; prefer apply_patch for small edits to existing files".to_string(),
+            parameters: serde_json::to_value(schema_for!(WriteFileArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        plan_write(&self.workspace, &args)
+            .and_then(PlannedWrite::apply)
+            .to_tool_result()
+    }
+}
@@ -0,0 +1,37 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CalculateError {
+    #[error("Expression is empty")]
+    Empty,
+    #[error("Unexpected character '{found}' at position {position}")]
+    UnexpectedChar { found: char, position: usize },
+    #[error("Unexpected '{found}' at position {position}")]
+    UnexpectedToken { found: String, position: usize },
+    #[error("Unexpected end of expression")]
+    UnexpectedEnd,
+    #[error("Expression is longer than {0} characters")]
+    TooLong(usize),
+    #[error("Expression nests more than {0} levels deep")]
+    TooDeep(usize),
+    #[error("Invalid number '{literal}' at position {position}")]
+    InvalidNumber { literal: String, position: usize },
+    #[error("Unknown constant '{0}'")]
+    UnknownConstant(String),
+    #[error("Unknown function '{0}'")]
+    UnknownFunction(String),
+    #[error("{name}() expects {expected} argument(s), got {found}")]
+    Arity {
+        name: String,
+        expected: String,
+        found: usize,
+    },
+    #[error("Division by zero")]
+    DivisionByZero,
+    #[error("{function}({argument}) is undefined")]
+    Domain { function: String, argument: String },
+    #[error("Overflow: {0}")]
+    Overflow(String),
+    #[error("Result is not a finite number")]
+    NotFinite,
+}
@@ -0,0 +1,446 @@
+use crate::agent_tools::math::calculate::{
+    error::CalculateError,
+    parser::{Expr, Op},
+};
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+const MAX_DECIMAL_DIGITS: usize = 30;
+pub const MAX_BITS: u64 = 100_000;
+pub const MAX_FACTORIAL: u64 = 5_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    Exact(BigRational),
+    Approx(f64),
+}
+
+impl Number {
+    fn to_f64(&self) -> f64 {
+        match self {
+            Number::Exact(value) => value.to_f64().unwrap_or(f64::NAN),
+            Number::Approx(value) => *value,
+        }
+    }
+    fn approx(value: f64) -> Result<Self, CalculateError> {
+        if value.is_finite() {
+            Ok(Number::Approx(value))
+        } else if value.is_infinite() {
+            Err(CalculateError::Overflow(
+                "result is outside the range of a 64-bit float".to_string(),
+            ))
+        } else {
+            Err(CalculateError::NotFinite)
+        }
+    }
+    fn exact(value: BigRational) -> Result<Self, CalculateError> {
+        if value.numer().bits() + value.denom().bits() > MAX_BITS {
+            return Err(CalculateError::Overflow(format!(
+                "exact result needs more than {} bits",
+                MAX_BITS
+            )));
+        }
+        Ok(Number::Exact(value))
+    }
+    fn is_zero(&self) -> bool {
+        match self {
+            Number::Exact(value) => value.is_zero(),
+            Number::Approx(value) => *value == 0.0,
+        }
+    }
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Number::Exact(_))
+    }
+    pub fn fraction(&self) -> Option<String> {
+        match self {
+            Number::Exact(value) if !value.is_integer() => Some(value.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Number::Exact(value) => match terminating_decimal(value) {
+                Some(decimal) => write!(f, "{}", decimal),
+                // a float would read inf or 0 here, the fraction is still right
+                None => match self.to_f64() {
+                    float if float.is_finite() && float != 0.0 => write!(f, "{}", float),
+                    _ => write!(f, "{}", value),
+                },
+            },
+            Number::Approx(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+fn terminating_decimal(value: &BigRational) -> Option<String> {
+    let (whole, mut remainder) = value.numer().abs().div_rem(value.denom());
+    let sign = if value.is_negative() { "-" } else { "" };
+    if remainder.is_zero() {
+        return Some(format!("{}{}", sign, whole));
+    }
+    let mut digits = String::new();
+    while !remainder.is_zero() {
+        if digits.len() == MAX_DECIMAL_DIGITS {
+            return None;
+        }
+        remainder *= 10;
+        let (digit, rest) = remainder.div_rem(value.denom());
+        digits.push_str(&digit.to_string());
+        remainder = rest;
+    }
+    Some(format!("{}{}.{}", sign, whole, digits))
+}
+
+fn integer(n: &BigRational) -> Option<&BigInt> {
+    n.is_integer().then(|| n.numer())
+}
+
+fn domain(function: &str, argument: &Number) -> CalculateError {
+    CalculateError::Domain {
+        function: function.to_string(),
+        argument: argument.to_string(),
+    }
+}
+
+fn exact_sqrt(value: &BigRational) -> Option<BigRational> {
+    if value.is_negative() {
+        return None;
+    }
+    let numer = value.numer().sqrt();
+    let denom = value.denom().sqrt();
+    (&numer * &numer == *value.numer() && &denom * &denom == *value.denom())
+        .then(|| BigRational::new(numer, denom))
+}
+
+fn constant(name: &str) -> Result<Number, CalculateError> {
+    match name {
+        "pi" | "π" => Ok(Number::Approx(std::f64::consts::PI)),
+        "e" => Ok(Number::Approx(std::f64::consts::E)),
+        "tau" | "τ" => Ok(Number::Approx(std::f64::consts::TAU)),
+        "phi" | "φ" => Ok(Number::Approx(1.618_033_988_749_895)),
+        name => Err(CalculateError::UnknownConstant(name.to_string())),
+    }
+}
+
+fn unary_f64(
+    name: &str,
+    x: &Number,
+    valid: impl Fn(f64) -> bool,
+    f: impl Fn(f64) -> f64,
+) -> Result<Number, CalculateError> {
+    let value = x.to_f64();
+    if !valid(value) {
+        return Err(domain(name, x));
+    }
+    Number::approx(f(value))
+}
+
+fn call(name: &str, args: Vec<Number>) -> Result<Number, CalculateError> {
+    match name {
+        "min" | "max" => {
+            if args.is_empty() {
+                return Err(CalculateError::Arity {
+                    name: name.to_string(),
+                    expected: "at least 1".to_string(),
+                    found: 0,
+                });
+            }
+            let mut args = args.into_iter();
+            let first = args.next().expect("checked non-empty");
+            args.try_fold(first, |acc, x| {
+                let ordering = match (&acc, &x) {
+                    (Number::Exact(a), Number::Exact(b)) => a.cmp(b),
+                    (a, b) => a.to_f64().total_cmp(&b.to_f64()),
+                };
+                let keep_acc = (name == "min") == ordering.is_le();
+                Ok(if keep_acc { acc } else { x })
+            })
+        }
+        "log" if args.len() == 2 => {
+            let (x, base) = (&args[0], &args[1]);
+            if x.to_f64() <= 0.0 {
+                return Err(domain(name, x));
+            }
+            if base.to_f64() <= 0.0 || base.to_f64() == 1.0 {
+                return Err(domain(name, base));
+            }
+            Number::approx(x.to_f64().ln() / base.to_f64().ln())
+        }
+        _ => {
+            let expected = if name == "log" { "1 or 2" } else { "1" };
+            if args.len() != 1 {
+                return Err(CalculateError::Arity {
+                    name: name.to_string(),
+                    expected: expected.to_string(),
+                    found: args.len(),
+                });
+            }
+            unary(name, &args[0])
+        }
+    }
+}
+
+fn unary(name: &str, x: &Number) -> Result<Number, CalculateError> {
+    let all = |_: f64| true;
+    let positive = |v: f64| v > 0.0;
+    match (name, x) {
+        ("abs", Number::Exact(v)) => Ok(Number::Exact(v.abs())),
+        ("abs", Number::Approx(v)) => Number::approx(v.abs()),
+        ("floor", Number::Exact(v)) => Ok(Number::Exact(v.floor())),
+        ("ceil", Number::Exact(v)) => Ok(Number::Exact(v.ceil())),
+        ("round", Number::Exact(v)) => Ok(Number::Exact(v.round())),
+        ("trunc", Number::Exact(v)) => Ok(Number::Exact(v.trunc())),
+        ("floor", _) => unary_f64(name, x, all, f64::floor),
+        ("ceil", _) => unary_f64(name, x, all, f64::ceil),
+        ("round", _) => unary_f64(name, x, all, f64::round),
+        ("trunc", _) => unary_f64(name, x, all, f64::trunc),
+        ("sqrt", Number::Exact(v)) if exact_sqrt(v).is_some() => {
+            Ok(Number::Exact(exact_sqrt(v).expect("checked")))
+        }
+        ("sqrt", _) => unary_f64(name, x, |v| v >= 0.0, f64::sqrt),
+        ("cbrt", _) => unary_f64(name, x, all, f64::cbrt),
+        ("exp", _) => unary_f64(name, x, all, f64::exp),
+        ("ln", _) | ("log", _) => unary_f64(name, x, positive, f64::ln),
+        ("log10", _) => unary_f64(name, x, positive, f64::log10),
+        ("log2", _) => unary_f64(name, x, positive, f64::log2),
+        ("sin", _) => unary_f64(name, x, all, f64::sin),
+        ("cos", _) => unary_f64(name, x, all, f64::cos),
+        ("tan", _) => unary_f64(name, x, all, f64::tan),
+        ("asin", _) => unary_f64(name, x, |v| (-1.0..=1.0).contains(&v), f64::asin),
+        ("acos", _) => unary_f64(name, x, |v| (-1.0..=1.0).contains(&v), f64::acos),
+        ("atan", _) => unary_f64(name, x, all, f64::atan),
+        ("sinh", _) => unary_f64(name, x, all, f64::sinh),
+        ("cosh", _) => unary_f64(name, x, all, f64::cosh),
+        ("tanh", _) => unary_f64(name, x, all, f64::tanh),
+        ("factorial", Number::Exact(v)) => match integer(v).and_then(BigInt::to_u64) {
+            Some(n) if n > MAX_FACTORIAL => Err(CalculateError::Overflow(format!(
+                "factorial is limited to n <= {}",
+                MAX_FACTORIAL
+            ))),
+            Some(n) => Number::exact(BigRational::from_integer(
+                (1..=n).map(BigInt::from).product(),
+            )),
+            None => Err(domain(name, x)),
+        },
+        ("factorial", _) => Err(domain(name, x)),
+        (name, _) => Err(CalculateError::UnknownFunction(name.to_string())),
+    }
+}
+
+fn pow(base: Number, exponent: Number) -> Result<Number, CalculateError> {
+    if let (Number::Exact(b), Number::Exact(e)) = (&base, &exponent)
+        && let Some(e) = integer(e).and_then(BigInt::to_i32)
+    {
+        if b.is_zero() && e < 0 {
+            return Err(CalculateError::DivisionByZero);
+        }
+        let bits = b.numer().bits().max(b.denom().bits());
+        if bits > 1 && bits.saturating_mul(e.unsigned_abs() as u64) > MAX_BITS {
+            return Err(CalculateError::Overflow(format!(
+                "exact result needs more than {} bits",
+                MAX_BITS
+            )));
+        }
+        return Number::exact(num_traits::Pow::pow(b, e));
+    }
+    let value = base.to_f64().powf(exponent.to_f64());
+    if value.is_nan() {
+        return Err(domain("pow", &base));
+    }
+    Number::approx(value)
+}
+
+fn binary(op: Op, lhs: Number, rhs: Number) -> Result<Number, CalculateError> {
+    if matches!(op, Op::Div | Op::Rem) && rhs.is_zero() {
+        return Err(CalculateError::DivisionByZero);
+    }
+    match (op, &lhs, &rhs) {
+        (Op::Pow, ..) => pow(lhs, rhs),
+        (op, Number::Exact(a), Number::Exact(b)) => Number::exact(match op {
+            Op::Add => a + b,
+            Op::Sub => a - b,
+            Op::Mul => a * b,
+            Op::Div => a / b,
+            Op::Rem => a - b * (a / b).floor(),
+            Op::Pow => unreachable!("handled above"),
+        }),
+        (op, a, b) => {
+            let (a, b) = (a.to_f64(), b.to_f64());
+            Number::approx(match op {
+                Op::Add => a + b,
+                Op::Sub => a - b,
+                Op::Mul => a * b,
+                Op::Div => a / b,
+                Op::Rem => a - b * (a / b).floor(),
+                Op::Pow => unreachable!("handled above"),
+            })
+        }
+    }
+}
+
+pub fn eval(expr: &Expr) -> Result<Number, CalculateError> {
+    match expr {
+        Expr::Number(value) => Ok(Number::Exact(value.clone())),
+        Expr::Constant(name) => constant(name),
+        Expr::Neg(expr) => match eval(expr)? {
+            Number::Exact(value) => Ok(Number::Exact(-value)),
+            Number::Approx(value) => Ok(Number::Approx(-value)),
+        },
+        Expr::Binary(op, lhs, rhs) => binary(*op, eval(lhs)?, eval(rhs)?),
+        Expr::Call(name, args) => call(name, args.iter().map(eval).collect::<Result<Vec<_>, _>>()?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_tools::math::calculate::parser::parse;
+    use proptest::prelude::*;
+
+    fn calc(input: &str) -> Result<String, CalculateError> {
+        eval(&parse(input)?).map(|number| number.to_string())
+    }
+
+    #[test]
+    fn test_exact_arithmetic() {
+        assert_eq!(calc("7 / 2").unwrap(), "3.5");
+        assert_eq!(calc("0.1 + 0.2").unwrap(), "0.3");
+        assert_eq!(calc("(1 + 2) * 3 - 4 / 2").unwrap(), "7");
+        assert_eq!(
+            calc("-9223372036854775808 / -1").unwrap(),
+            "9223372036854775808"
+        );
+        assert_eq!(calc("2^100").unwrap(), "1267650600228229401496703205376");
+        assert_eq!(calc("2^-2").unwrap(), "0.25");
+        assert_eq!(calc("-7 % 3").unwrap(), "2");
+        assert_eq!(calc("factorial(20)").unwrap(), "2432902008176640000");
+        assert_eq!(calc("sqrt(9/4)").unwrap(), "1.5");
+        assert_eq!(calc("1 / 3").unwrap(), (1.0f64 / 3.0).to_string());
+        assert_eq!(
+            calc("10^400 / 3").unwrap(),
+            format!("1{}/3", "0".repeat(400))
+        );
+        assert_eq!(
+            calc("1 / (3 * 10^400)").unwrap(),
+            format!("1/3{}", "0".repeat(400))
+        );
+    }
+
+    #[test]
+    fn test_functions_and_constants() {
+        assert_eq!(calc("max(1, 5, 3) + min(2, -1)").unwrap(), "4");
+        assert_eq!(calc("log(8, 2)").unwrap(), "3");
+        assert_eq!(calc("round(2.5)").unwrap(), "3");
+        assert!((calc("sin(pi / 2)").unwrap().parse::<f64>().unwrap() - 1.0).abs() < 1e-12);
+        assert_eq!(
+            calc("sqrt(2)").unwrap(),
+            std::f64::consts::SQRT_2.to_string()
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(calc("1 / 0"), Err(CalculateError::DivisionByZero)));
+        assert!(matches!(
+            calc("5 % (2 - 2)"),
+            Err(CalculateError::DivisionByZero)
+        ));
+        assert!(matches!(
+            calc("0 ^ -1"),
+            Err(CalculateError::DivisionByZero)
+        ));
+        assert!(matches!(
+            calc("sqrt(-1)"),
+            Err(CalculateError::Domain { .. })
+        ));
+        assert!(matches!(calc("ln(0)"), Err(CalculateError::Domain { .. })));
+        assert!(matches!(
+            calc("(-8) ^ 0.5"),
+            Err(CalculateError::Domain { .. })
+        ));
+        assert!(matches!(
+            calc("foo(1)"),
+            Err(CalculateError::UnknownFunction(_))
+        ));
+        assert!(matches!(
+            calc("x + 1"),
+            Err(CalculateError::UnknownConstant(_))
+        ));
+        assert!(matches!(
+            calc("sin(1, 2)"),
+            Err(CalculateError::Arity { .. })
+        ));
+        assert!(matches!(
+            calc("exp(1000)"),
+            Err(CalculateError::Overflow(_))
+        ));
+        assert!(matches!(
+            calc("2 ^ 2 ^ 40"),
+            Err(CalculateError::Overflow(_))
+        ));
+        assert!(matches!(
+            calc("factorial(100000)"),
+            Err(CalculateError::Overflow(_))
+        ));
+        assert!(matches!(
+            calc("1e999999999"),
+            Err(CalculateError::Overflow(_))
+        ));
+        assert!(matches!(
+            calc("10^30000 * 10^30000"),
+            Err(CalculateError::Overflow(_))
+        ));
+        assert!(calc("1e307 * 10 + sqrt(2)").is_ok());
+        assert!(matches!(
+            calc("1e308 * 10 + sqrt(2)"),
+            Err(CalculateError::Overflow(_))
+        ));
+    }
+
+    fn exact(input: &str) -> BigRational {
+        match eval(&parse(input).unwrap()).unwrap() {
+            Number::Exact(value) => value,
+            number => panic!("expected an exact result, got {:?}", number),
+        }
+    }
+
+    fn integer(value: i128) -> BigRational {
+        BigRational::from_integer(value.into())
+    }
+
+    proptest! {
+        #[test]
+        fn prop_integer_ops_match_i128(a: i64, b: i64) {
+            let (x, y) = (a as i128, b as i128);
+            prop_assert_eq!(exact(&format!("({}) + ({})", a, b)), integer(x + y));
+            prop_assert_eq!(exact(&format!("({}) - ({})", a, b)), integer(x - y));
+            prop_assert_eq!(exact(&format!("({}) * ({})", a, b)), integer(x * y));
+            if b == 0 {
+                let divided = eval(&parse(&format!("({}) / ({})", a, b)).unwrap());
+                prop_assert!(matches!(divided, Err(CalculateError::DivisionByZero)));
+            } else {
+                prop_assert_eq!(
+                    exact(&format!("({}) / ({})", a, b)),
+                    BigRational::new(x.into(), y.into())
+                );
+                prop_assert_eq!(exact(&format!("({}) % ({})", a, b)), integer(((x % y) + y) % y));
+            }
+        }
+
+        #[test]
+        fn prop_pow_matches_i128(a in -1000i64..1000, e in 0u32..12) {
+            let expected = (a as i128).checked_pow(e).expect("fits in i128");
+            prop_assert_eq!(exact(&format!("({}) ^ {}", a, e)), integer(expected));
+        }
+
+        #[test]
+        fn prop_never_panics(input in "[0-9a-z+*/%^().,e -]{0,40}") {
+            let _ = parse(&input).and_then(|expr| eval(&expr));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+pub mod error;
+pub mod eval;
+pub mod parser;
+pub mod request;
+
+use crate::agent_tools::ToToolResult;
+use eval::eval;
+use parser::parse;
+use request::CalculateArgs;
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::schema_for;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct CalculateOutput {
+    pub result: String,
+    pub exact: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fraction: Option<String>,
+}
+
+pub struct Calculate;
+
+impl Tool for Calculate {
+    const NAME: &'static str = "calculate";
+    type Args = CalculateArgs;
+    type Output = CalculateOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "evaluates a full arithmetic expression in one call. Integer and decimal arithmetic is exact (arbitrary precision rationals); functions and constants like sqrt, sin or pi fall back to 64-bit floats, reported with exact = false. Results too large to represent are returned as overflow errors rather than wrapped or truncated".to_string(),
+            parameters: serde_json::to_value(schema_for!(CalculateArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let number = parse(&args.expression)
+            .and_then(|expr| eval(&expr))
+            .to_tool_result()?;
+        Ok(CalculateOutput {
+            result: number.to_string(),
+            exact: number.is_exact(),
+            fraction: number.fraction(),
+        })
+    }
+}
@@ -0,0 +1,381 @@
+use crate::agent_tools::math::calculate::{error::CalculateError, eval::MAX_BITS};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::Pow;
+
+// the parser and evaluator recurse, and a stack overflow would take down the whole process
+const MAX_INPUT_CHARS: usize = 2_000;
+const MAX_DEPTH: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(BigRational),
+    Constant(String),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(BigRational),
+    Ident(String),
+    Op(Op),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "{}", number),
+            Token::Ident(ident) => write!(f, "{}", ident),
+            Token::Op(op) => write!(
+                f,
+                "{}",
+                match op {
+                    Op::Add => "+",
+                    Op::Sub => "-",
+                    Op::Mul => "*",
+                    Op::Div => "/",
+                    Op::Rem => "%",
+                    Op::Pow => "^",
+                }
+            ),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+        }
+    }
+}
+
+fn number_literal(literal: &str, position: usize) -> Result<BigRational, CalculateError> {
+    let invalid = || CalculateError::InvalidNumber {
+        literal: literal.to_string(),
+        position,
+    };
+    let (mantissa, exponent) = match literal.find(['e', 'E']) {
+        Some(index) => (
+            &literal[..index],
+            literal[index + 1..].parse::<i64>().map_err(|_| invalid())?,
+        ),
+        None => (literal, 0),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    let digits: BigInt = format!("{}{}", whole, fraction)
+        .parse()
+        .map_err(|_| invalid())?;
+    let scale = exponent.saturating_sub(fraction.len() as i64);
+    if scale.unsigned_abs().saturating_add(digits.bits() / 3) > MAX_BITS / 3 {
+        return Err(CalculateError::Overflow(format!(
+            "number literal '{}' is too large",
+            literal
+        )));
+    }
+    let ten = BigInt::from(10);
+    Ok(if scale >= 0 {
+        BigRational::from_integer(digits * Pow::pow(&ten, scale.unsigned_abs()))
+    } else {
+        BigRational::new(digits, Pow::pow(&ten, scale.unsigned_abs()))
+    })
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, CalculateError> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (position, c) = chars[i];
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].1.is_ascii_digit() || chars[i].1 == '.') {
+                    i += 1;
+                }
+                let has_exponent = i + 1 < chars.len()
+                    && matches!(chars[i].1, 'e' | 'E')
+                    && (chars[i + 1].1.is_ascii_digit()
+                        || (matches!(chars[i + 1].1, '+' | '-')
+                            && chars.get(i + 2).is_some_and(|(_, c)| c.is_ascii_digit())));
+                if has_exponent {
+                    i += 2;
+                    while i < chars.len() && chars[i].1.is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+                let end = chars.get(i).map(|(index, _)| *index).unwrap_or(input.len());
+                tokens.push((
+                    position,
+                    Token::Number(number_literal(&input[chars[start].0..end], position)?),
+                ));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].1.is_alphanumeric() || chars[i].1 == '_') {
+                    i += 1;
+                }
+                let end = chars.get(i).map(|(index, _)| *index).unwrap_or(input.len());
+                tokens.push((
+                    position,
+                    Token::Ident(input[chars[start].0..end].to_lowercase()),
+                ));
+                continue;
+            }
+            '*' if chars.get(i + 1).is_some_and(|(_, c)| *c == '*') => {
+                i += 1;
+                Token::Op(Op::Pow)
+            }
+            '+' => Token::Op(Op::Add),
+            '-' | '−' => Token::Op(Op::Sub),
+            '*' | '×' => Token::Op(Op::Mul),
+            '/' | '÷' => Token::Op(Op::Div),
+            '%' => Token::Op(Op::Rem),
+            '^' => Token::Op(Op::Pow),
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            found => return Err(CalculateError::UnexpectedChar { found, position }),
+        };
+        tokens.push((position, token));
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(_, token)| token)
+    }
+    fn next(&mut self) -> Result<(usize, Token), CalculateError> {
+        let token = self
+            .tokens
+            .get(self.index)
+            .cloned()
+            .ok_or(CalculateError::UnexpectedEnd)?;
+        self.index += 1;
+        Ok(token)
+    }
+    fn expect(&mut self, expected: Token) -> Result<(), CalculateError> {
+        match self.next()? {
+            (_, token) if token == expected => Ok(()),
+            (position, token) => Err(CalculateError::UnexpectedToken {
+                found: token.to_string(),
+                position,
+            }),
+        }
+    }
+    fn expression(&mut self) -> Result<Expr, CalculateError> {
+        let mut lhs = self.term()?;
+        while let Some(Token::Op(op @ (Op::Add | Op::Sub))) = self.peek().cloned() {
+            self.index += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+        Ok(lhs)
+    }
+    fn term(&mut self) -> Result<Expr, CalculateError> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Op(op @ (Op::Mul | Op::Div | Op::Rem))) = self.peek().cloned() {
+            self.index += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+    // every nesting, a sign, a parenthesis, a call or an exponent, passes through here
+    fn unary(&mut self) -> Result<Expr, CalculateError> {
+        if self.depth == MAX_DEPTH {
+            return Err(CalculateError::TooDeep(MAX_DEPTH));
+        }
+        self.depth += 1;
+        let expr = self.signed();
+        self.depth -= 1;
+        expr
+    }
+    fn signed(&mut self) -> Result<Expr, CalculateError> {
+        match self.peek() {
+            Some(Token::Op(Op::Sub)) => {
+                self.index += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            Some(Token::Op(Op::Add)) => {
+                self.index += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+    fn power(&mut self) -> Result<Expr, CalculateError> {
+        let base = self.primary()?;
+        if let Some(Token::Op(Op::Pow)) = self.peek() {
+            self.index += 1;
+            let exponent = self.unary()?;
+            return Ok(Expr::Binary(Op::Pow, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+    fn primary(&mut self) -> Result<Expr, CalculateError> {
+        match self.next()? {
+            (_, Token::Number(number)) => Ok(Expr::Number(number)),
+            (_, Token::Ident(name)) => {
+                if self.peek() != Some(&Token::LParen) {
+                    return Ok(Expr::Constant(name));
+                }
+                self.index += 1;
+                let mut args = Vec::new();
+                if self.peek() == Some(&Token::RParen) {
+                    self.index += 1;
+                    return Ok(Expr::Call(name, args));
+                }
+                loop {
+                    args.push(self.expression()?);
+                    match self.next()? {
+                        (_, Token::Comma) => continue,
+                        (_, Token::RParen) => break,
+                        (position, token) => {
+                            return Err(CalculateError::UnexpectedToken {
+                                found: token.to_string(),
+                                position,
+                            })
+                        }
+                    }
+                }
+                Ok(Expr::Call(name, args))
+            }
+            (_, Token::LParen) => {
+                let expr = self.expression()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            (position, token) => Err(CalculateError::UnexpectedToken {
+                found: token.to_string(),
+                position,
+            }),
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Expr, CalculateError> {
+    if input.chars().count() > MAX_INPUT_CHARS {
+        return Err(CalculateError::TooLong(MAX_INPUT_CHARS));
+    }
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        index: 0,
+        depth: 0,
+    };
+    if parser.tokens.is_empty() {
+        return Err(CalculateError::Empty);
+    }
+    let expr = parser.expression()?;
+    match parser.tokens.get(parser.index) {
+        Some((position, token)) => Err(CalculateError::UnexpectedToken {
+            found: token.to_string(),
+            position: *position,
+        }),
+        None => Ok(expr),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::One;
+
+    fn number(n: i64) -> Box<Expr> {
+        Box::new(Expr::Number(BigRational::from_integer(n.into())))
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(
+            parse("1 + 2 * 3").unwrap(),
+            Expr::Binary(
+                Op::Add,
+                number(1),
+                Box::new(Expr::Binary(Op::Mul, number(2), number(3)))
+            )
+        );
+        assert_eq!(
+            parse("-2^2").unwrap(),
+            Expr::Neg(Box::new(Expr::Binary(Op::Pow, number(2), number(2))))
+        );
+        assert_eq!(
+            parse("2**3^2").unwrap(),
+            Expr::Binary(
+                Op::Pow,
+                number(2),
+                Box::new(Expr::Binary(Op::Pow, number(3), number(2)))
+            )
+        );
+    }
+
+    #[test]
+    fn test_number_literals() {
+        assert_eq!(
+            parse("1.25").unwrap(),
+            Expr::Number(BigRational::new(5.into(), 4.into()))
+        );
+        assert_eq!(parse("2.5e3").unwrap(), *number(2500));
+        assert_eq!(
+            parse("1e-2").unwrap(),
+            Expr::Number(BigRational::new(BigInt::one(), 100.into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            parse("2 + $"),
+            Err(CalculateError::UnexpectedChar {
+                found: '$',
+                position: 4
+            })
+        ));
+        assert!(matches!(
+            parse("(1 + 2"),
+            Err(CalculateError::UnexpectedEnd)
+        ));
+        assert!(matches!(
+            parse("1 2"),
+            Err(CalculateError::UnexpectedToken { position: 2, .. })
+        ));
+        assert!(matches!(parse("  "), Err(CalculateError::Empty)));
+        assert!(matches!(
+            parse(&format!("{}1", "-".repeat(200_000))),
+            Err(CalculateError::TooLong(_))
+        ));
+        assert!(matches!(
+            parse(&format!("{}1", "-".repeat(500))),
+            Err(CalculateError::TooDeep(_))
+        ));
+        assert!(matches!(
+            parse(&format!("{}1{}", "(".repeat(500), ")".repeat(500))),
+            Err(CalculateError::TooDeep(_))
+        ));
+        assert!(parse(&format!("{}1{}", "(".repeat(50), ")".repeat(50))).is_ok());
+    }
+}
@@ -0,0 +1,10 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct CalculateArgs {
+    #[schemars(
+        description = "Arithmetic expression to evaluate, e.g. '(3.5 + 2) * 4 ^ 2 / 7' or 'sqrt(2) * sin(pi / 4)'. Supports + - * / % ^ (or **), parentheses, constants pi, e, tau, phi and functions abs, floor, ceil, round, trunc, sqrt, cbrt, exp, ln, log (1 or 2 args), log10, log2, sin, cos, tan, asin, acos, atan, sinh, cosh, tanh, min, max, factorial"
+    )]
+    pub expression: String,
+}
@@ -1,16 +1,9 @@
-use crate::agent_tools::math::{add::Add, divide::Divide, multiply::Multiply, subtract::Subtract};
+use crate::agent_tools::math::{calculate::Calculate, statistics::Statistics};
 use rig::tool::ToolDyn;
 
-pub mod add;
-pub mod divide;
-pub mod multiply;
-pub mod subtract;
+pub mod calculate;
+pub mod statistics;
 
 pub fn math_tools() -> Vec<Box<dyn ToolDyn>> {
-    vec![
-        Box::new(Add),
-        Box::new(Subtract),
-        Box::new(Multiply),
-        Box::new(Divide),
-    ]
+    vec![Box::new(Calculate), Box::new(Statistics)]
 }
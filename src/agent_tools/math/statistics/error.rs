@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StatisticsError {
+    #[error("{0} has no values")]
+    Empty(&'static str),
+    #[error("{0} contains a value that is not a finite number")]
+    NotFinite(&'static str),
+    #[error("Percentile {0} is out of range, expected a value from 0 to 100")]
+    InvalidPercentile(f64),
+    #[error("{0} needs at least 2 values to be compared")]
+    TooFewValues(&'static str),
+}
@@ -0,0 +1,60 @@
+pub mod error;
+pub mod request;
+pub mod summary;
+
+use crate::agent_tools::ToToolResult;
+use request::StatisticsArgs;
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::schema_for;
+use serde::Serialize;
+use summary::{compare, summarize, Comparison, Summary, DEFAULT_PERCENTILES};
+
+#[derive(Debug, Serialize)]
+pub struct StatisticsOutput {
+    #[serde(flatten)]
+    pub summary: Summary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<Summary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<Comparison>,
+}
+
+pub struct Statistics;
+
+impl Tool for Statistics {
+    const NAME: &'static str = "statistics";
+    type Args = StatisticsArgs;
+    type Output = StatisticsOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "summarizes a list of numbers: count, mean, median, sample standard deviation, min, max and percentiles. Given a baseline sample it also reports the percent change in mean and median and a two-sided Welch's t-test (significant when p < 0.05). Use this instead of estimating statistics for benchmark results".to_string(),
+            parameters: serde_json::to_value(schema_for!(StatisticsArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let percentiles = args.percentiles.as_deref().unwrap_or(DEFAULT_PERCENTILES);
+        let summary = summarize(&args.values, "values", percentiles).to_tool_result()?;
+        let Some(baseline) = args.baseline else {
+            return Ok(StatisticsOutput {
+                summary,
+                baseline: None,
+                comparison: None,
+            });
+        };
+        let baseline_summary = summarize(&baseline, "baseline", percentiles).to_tool_result()?;
+        let comparison =
+            compare(&args.values, &summary, &baseline, &baseline_summary).to_tool_result()?;
+        Ok(StatisticsOutput {
+            summary,
+            baseline: Some(baseline_summary),
+            comparison: Some(comparison),
+        })
+    }
+}
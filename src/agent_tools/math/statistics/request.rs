@@ -0,0 +1,16 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct StatisticsArgs {
+    #[schemars(description = "The sample, e.g. timings from cargo bench or hyperfine runs")]
+    pub values: Vec<f64>,
+    #[schemars(
+        description = "Percentiles to report, each from 0 to 100. Defaults to 25, 75, 90, 95 and 99"
+    )]
+    pub percentiles: Option<Vec<f64>>,
+    #[schemars(
+        description = "Optional second sample to compare against, e.g. the timings before a change. Adds percent change and a Welch's t-test"
+    )]
+    pub baseline: Option<Vec<f64>>,
+}
@@ -0,0 +1,272 @@
+use crate::agent_tools::math::statistics::error::StatisticsError;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+pub const DEFAULT_PERCENTILES: &[f64] = &[25.0, 75.0, 90.0, 95.0, 99.0];
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub stddev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub percentiles: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Comparison {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_change_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median_change_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub t_statistic: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub degrees_of_freedom: Option<f64>,
+    pub p_value: f64,
+    pub significant: bool,
+}
+
+fn sorted(values: &[f64], name: &'static str) -> Result<Vec<f64>, StatisticsError> {
+    if values.is_empty() {
+        return Err(StatisticsError::Empty(name));
+    }
+    if values.iter().any(|value| !value.is_finite()) {
+        return Err(StatisticsError::NotFinite(name));
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    Ok(sorted)
+}
+
+// linear interpolation between closest ranks, the same default as numpy
+fn percentile(sorted: &[f64], percentile: f64) -> f64 {
+    let rank = percentile / 100.0 * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn sample_variance(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean = mean(values);
+    values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / (values.len() - 1) as f64
+}
+
+fn percentile_key(percentile: f64) -> String {
+    format!("p{}", percentile)
+}
+
+pub fn summarize(
+    values: &[f64],
+    name: &'static str,
+    percentiles: &[f64],
+) -> Result<Summary, StatisticsError> {
+    let sorted = sorted(values, name)?;
+    if let Some(invalid) = percentiles.iter().find(|p| !(0.0..=100.0).contains(*p)) {
+        return Err(StatisticsError::InvalidPercentile(*invalid));
+    }
+    Ok(Summary {
+        count: sorted.len(),
+        mean: mean(&sorted),
+        median: percentile(&sorted, 50.0),
+        stddev: sample_variance(&sorted).sqrt(),
+        min: sorted[0],
+        max: sorted[sorted.len() - 1],
+        percentiles: percentiles
+            .iter()
+            .map(|p| (percentile_key(*p), percentile(&sorted, *p)))
+            .collect(),
+    })
+}
+
+fn percent_change(baseline: f64, value: f64) -> Option<f64> {
+    (baseline != 0.0).then(|| (value - baseline) / baseline.abs() * 100.0)
+}
+
+pub fn compare(
+    values: &[f64],
+    summary: &Summary,
+    baseline: &[f64],
+    baseline_summary: &Summary,
+) -> Result<Comparison, StatisticsError> {
+    if values.len() < 2 {
+        return Err(StatisticsError::TooFewValues("values"));
+    }
+    if baseline.len() < 2 {
+        return Err(StatisticsError::TooFewValues("baseline"));
+    }
+    let (n1, n2) = (values.len() as f64, baseline.len() as f64);
+    let (v1, v2) = (sample_variance(values) / n1, sample_variance(baseline) / n2);
+    let standard_error = (v1 + v2).sqrt();
+    let difference = summary.mean - baseline_summary.mean;
+    let (t_statistic, degrees_of_freedom, p_value) = if standard_error > 0.0 {
+        let t = difference / standard_error;
+        let df = (v1 + v2).powi(2) / (v1.powi(2) / (n1 - 1.0) + v2.powi(2) / (n2 - 1.0));
+        (Some(t), Some(df), student_t_two_sided_p(t, df))
+    } else if difference == 0.0 {
+        (None, None, 1.0)
+    } else {
+        (None, None, 0.0)
+    };
+    Ok(Comparison {
+        mean_change_percent: percent_change(baseline_summary.mean, summary.mean),
+        median_change_percent: percent_change(baseline_summary.median, summary.median),
+        t_statistic,
+        degrees_of_freedom,
+        p_value,
+        significant: p_value < SIGNIFICANCE_LEVEL,
+    })
+}
+
+fn student_t_two_sided_p(t: f64, df: f64) -> f64 {
+    regularized_incomplete_beta(df / 2.0, 0.5, df / (df + t * t)).clamp(0.0, 1.0)
+}
+
+fn ln_gamma(x: f64) -> f64 {
+    // lanczos approximation, g = 7
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| {
+            sum + c / (x + i as f64 + 1.0)
+        });
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+fn regularized_incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 300;
+    const EPSILON: f64 = 1e-15;
+    const TINY: f64 = 1e-300;
+    let guard = |value: f64| if value.abs() < TINY { TINY } else { value };
+    let mut c = 1.0;
+    let mut d = 1.0 / guard(1.0 - (a + b) * x / (a + 1.0));
+    let mut result = d;
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / guard(1.0 + even * d);
+        c = guard(1.0 + even / c);
+        result *= d * c;
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / guard(1.0 + odd * d);
+        c = guard(1.0 + odd / c);
+        let delta = d * c;
+        result *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_summarize() {
+        let summary = summarize(&[4.0, 1.0, 3.0, 2.0, 10.0], "values", &[25.0, 90.0]).unwrap();
+        assert_eq!(summary.count, 5);
+        assert_eq!(summary.mean, 4.0);
+        assert_eq!(summary.median, 3.0);
+        assert!(close(summary.stddev, 12.5_f64.sqrt()));
+        assert_eq!((summary.min, summary.max), (1.0, 10.0));
+        assert_eq!(summary.percentiles["p25"], 2.0);
+        assert!(close(summary.percentiles["p90"], 7.6));
+    }
+
+    #[test]
+    fn test_summarize_errors() {
+        assert!(matches!(
+            summarize(&[], "values", &[]),
+            Err(StatisticsError::Empty("values"))
+        ));
+        assert!(matches!(
+            summarize(&[1.0, f64::NAN], "values", &[]),
+            Err(StatisticsError::NotFinite("values"))
+        ));
+        assert!(matches!(
+            summarize(&[1.0], "values", &[101.0]),
+            Err(StatisticsError::InvalidPercentile(_))
+        ));
+    }
+
+    #[test]
+    fn test_welch_t_test() {
+        let values = [2.0, 4.0, 6.0, 8.0, 10.0];
+        let baseline = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let summary = summarize(&values, "values", &[]).unwrap();
+        let baseline_summary = summarize(&baseline, "baseline", &[]).unwrap();
+        let comparison = compare(&values, &summary, &baseline, &baseline_summary).unwrap();
+        assert_eq!(comparison.mean_change_percent, Some(100.0));
+        assert!(close(comparison.t_statistic.unwrap(), 1.897_366_596));
+        assert!(close(comparison.degrees_of_freedom.unwrap(), 5.882_352_941));
+        assert!((comparison.p_value - 0.107_531).abs() < 1e-5);
+        assert!(!comparison.significant);
+    }
+
+    #[test]
+    fn test_identical_samples() {
+        let values = [3.0, 3.0, 3.0];
+        let summary = summarize(&values, "values", &[]).unwrap();
+        let comparison = compare(&values, &summary, &values, &summary).unwrap();
+        assert_eq!(comparison.p_value, 1.0);
+        assert_eq!(comparison.t_statistic, None);
+    }
+
+    #[test]
+    fn test_student_t_p_value() {
+        assert!(close(student_t_two_sided_p(0.0, 10.0), 1.0));
+        // t = 2.228 is the 97.5th percentile for 10 degrees of freedom
+        assert!((student_t_two_sided_p(2.228_138_85, 10.0) - 0.05).abs() < 1e-6);
+    }
+}
@@ -1,8 +1,19 @@
+pub mod code;
+pub mod datetime;
+pub mod docs;
+pub mod fs;
 pub mod math;
+pub mod shell;
+pub mod units;
 pub mod web;
+pub use code::code_tools;
+pub use datetime::datetime_tools;
+pub use docs::docs_tools;
+pub use fs::fs_tools;
 pub use math::math_tools;
-use thiserror::Error;
-pub use web::WebTools;
+pub use shell::shell_tools;
+pub use units::units_tools;
+pub use web::web_tools;
 
 use rig::tool::ToolError;
 
@@ -23,7 +34,3 @@ impl<T, E: ToToolError> ToToolResult<T, E> for Result<T, E> {
         self.map_err(|e| e.to_tool_err())
     }
 }
-
-#[derive(Debug, Error)]
-#[error("{0}")]
-pub(super) struct SomeError(String);
@@ -0,0 +1,33 @@
+use crate::agent_tools::fs::error::FsError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ShellError {
+    #[error("Command is empty")]
+    Empty,
+    #[error("Unterminated quote in command")]
+    UnterminatedQuote,
+    #[error("'{0}' is shell syntax, run_command runs a single program without a shell")]
+    ShellSyntax(char),
+    #[error("'{command}' is denied by the '{pattern}' rule")]
+    Denied { command: String, pattern: String },
+    #[error("'{argument}' is not allowed in '{command}', {reason}")]
+    Argument {
+        command: String,
+        argument: String,
+        reason: &'static str,
+    },
+    #[error("'{command}' is not in the allowed commands: {allowed}")]
+    NotAllowed { command: String, allowed: String },
+    #[error("Timeout must be between 1 and {max} seconds, got {got}")]
+    InvalidTimeout { got: u64, max: u64 },
+    #[error("Could not start '{program}': {source}")]
+    Spawn {
+        program: String,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Workspace(#[from] FsError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
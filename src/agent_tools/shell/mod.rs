@@ -0,0 +1,13 @@
+pub mod error;
+pub mod policy;
+pub mod run_command;
+
+use crate::agent_tools::{
+    fs::workspace::Workspace,
+    shell::{policy::CommandPolicy, run_command::RunCommand},
+};
+use rig::tool::ToolDyn;
+
+pub fn shell_tools(workspace: Workspace, policy: CommandPolicy) -> Vec<Box<dyn ToolDyn>> {
+    vec![Box::new(RunCommand::new(workspace, policy))]
+}
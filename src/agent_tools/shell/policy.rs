@@ -0,0 +1,286 @@
+use crate::agent_tools::shell::error::ShellError;
+use std::path::{Component, Path};
+
+// nothing that takes a path to read, cat or grep would reach past the workspace rules the
+// file tools keep. read_file, grep, glob and list_dir cover those instead
+const DEFAULT_ALLOWED: &[&str] = &[
+    "cargo build",
+    "cargo check",
+    "cargo clippy",
+    "cargo fmt",
+    "cargo metadata",
+    "cargo run",
+    "cargo test",
+    "cargo tree",
+    "git diff",
+    "git log",
+    "git show",
+    "git status",
+    "echo",
+    "pwd",
+];
+const DEFAULT_DENIED: &[&str] = &[
+    "cargo publish",
+    "git clean",
+    "git push",
+    "git reset",
+    "curl",
+    "rm",
+    "sudo",
+    "wget",
+];
+
+// git options that reach past the repository, with the shortest abbreviation git accepts for each
+const GIT_DENIED_OPTIONS: &[(&str, &str, &str)] = &[
+    (
+        "--no-index",
+        "--no-i",
+        "it compares files outside the repository",
+    ),
+    ("--output", "--ou", "it writes to a file"),
+];
+
+// rules are word prefixes, so "cargo test" allows "cargo test -p marvin" but not "cargo testify"
+#[derive(Debug, Clone)]
+pub struct CommandPolicy {
+    allowed: Vec<Vec<String>>,
+    denied: Vec<Vec<String>>,
+}
+
+fn rule(pattern: &str) -> Vec<String> {
+    pattern.split_whitespace().map(str::to_string).collect()
+}
+
+fn matches(rule: &[String], words: &[String]) -> bool {
+    !rule.is_empty()
+        && rule.len() <= words.len()
+        && rule.iter().zip(words).enumerate().all(|(i, (rule, word))| {
+            // the program is matched by name so /bin/rm is still rm
+            let word = match i {
+                0 => Path::new(word)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| word.clone()),
+                _ => word.clone(),
+            };
+            *rule == word
+        })
+}
+
+fn program(words: &[String]) -> Option<String> {
+    let program = Path::new(words.first()?).file_name()?;
+    Some(program.to_string_lossy().to_string())
+}
+
+// an allowed git subcommand can still read or write any file through its arguments
+fn git_argument(words: &[String]) -> Option<(&String, &'static str)> {
+    let mut paths_only = false;
+    for word in words.iter().skip(2) {
+        if word == "--" {
+            paths_only = true;
+            continue;
+        }
+        if !paths_only && word.starts_with("--") {
+            let name = word.split('=').next().unwrap_or_default();
+            if let Some((_, _, reason)) = GIT_DENIED_OPTIONS.iter().find(|(option, shortest, _)| {
+                option.starts_with(name) && name.starts_with(shortest)
+            }) {
+                return Some((word, reason));
+            }
+            continue;
+        }
+        if !paths_only && word.starts_with('-') {
+            continue;
+        }
+        let path = Path::new(word);
+        // git diffs paths outside the work tree as if --no-index had been given
+        if path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+            return Some((word, "it points outside the workspace"));
+        }
+        if !paths_only && word.contains(':') {
+            return Some((
+                word,
+                "it prints a file as committed, read_file reads the workspace",
+            ));
+        }
+    }
+    None
+}
+
+impl Default for CommandPolicy {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_ALLOWED.iter().copied(),
+            DEFAULT_DENIED.iter().copied(),
+        )
+    }
+}
+
+impl CommandPolicy {
+    pub fn new<'a>(
+        allowed: impl IntoIterator<Item = &'a str>,
+        denied: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let rules = |patterns: &mut dyn Iterator<Item = &'a str>| {
+            patterns.map(rule).filter(|rule| !rule.is_empty()).collect()
+        };
+        Self {
+            allowed: rules(&mut allowed.into_iter()),
+            denied: rules(&mut denied.into_iter()),
+        }
+    }
+    pub fn allowed(&self) -> Vec<String> {
+        self.allowed.iter().map(|rule| rule.join(" ")).collect()
+    }
+    pub fn denied(&self) -> Vec<String> {
+        self.denied.iter().map(|rule| rule.join(" ")).collect()
+    }
+    // denied rules win over allowed ones
+    pub fn check(&self, command: &str) -> Result<Vec<String>, ShellError> {
+        let words = split_command(command)?;
+        if let Some(rule) = self.denied.iter().find(|rule| matches(rule, &words)) {
+            return Err(ShellError::Denied {
+                command: words.join(" "),
+                pattern: rule.join(" "),
+            });
+        }
+        if !self.allowed.iter().any(|rule| matches(rule, &words)) {
+            return Err(ShellError::NotAllowed {
+                command: words.join(" "),
+                allowed: self.allowed().join(", "),
+            });
+        }
+        if program(&words).as_deref() == Some("git")
+            && let Some((argument, reason)) = git_argument(&words)
+        {
+            return Err(ShellError::Argument {
+                command: words.join(" "),
+                argument: argument.clone(),
+                reason,
+            });
+        }
+        Ok(words)
+    }
+}
+
+pub fn split_command(command: &str) -> Result<Vec<String>, ShellError> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => word.extend(chars.next()),
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, '\\') => {
+                word.extend(chars.next());
+                in_word = true;
+            }
+            (None, '|' | '&' | ';' | '<' | '>' | '`') => return Err(ShellError::ShellSyntax(c)),
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(ShellError::UnterminatedQuote);
+    }
+    if in_word {
+        words.push(word);
+    }
+    if words.is_empty() {
+        return Err(ShellError::Empty);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(
+            split_command(r#"grep -n "fn main" 'src/main.rs' a\ b"#).unwrap(),
+            vec!["grep", "-n", "fn main", "src/main.rs", "a b"]
+        );
+        assert_eq!(split_command(r#"echo """#).unwrap(), vec!["echo", ""]);
+        assert!(matches!(
+            split_command("cargo test | tail"),
+            Err(ShellError::ShellSyntax('|'))
+        ));
+        assert!(matches!(
+            split_command("echo 'oops"),
+            Err(ShellError::UnterminatedQuote)
+        ));
+        assert!(matches!(split_command("   "), Err(ShellError::Empty)));
+    }
+
+    #[test]
+    fn test_policy_prefix_rules() {
+        let policy = CommandPolicy::new(["cargo test", "git"], ["git push"]);
+        assert!(policy.check("cargo test -p marvin").is_ok());
+        assert!(policy.check("git status").is_ok());
+        assert!(matches!(
+            policy.check("cargo testify"),
+            Err(ShellError::NotAllowed { .. })
+        ));
+        assert!(matches!(
+            policy.check("git push --force"),
+            Err(ShellError::Denied { .. })
+        ));
+        assert!(matches!(
+            CommandPolicy::default().check("/bin/rm -rf target"),
+            Err(ShellError::Denied { .. })
+        ));
+        for command in ["cat ~/.aws/credentials", "grep -r KEY /", "tail .env"] {
+            assert!(matches!(
+                CommandPolicy::default().check(command),
+                Err(ShellError::NotAllowed { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_git_arguments_stay_in_the_repository() {
+        let policy = CommandPolicy::default();
+        for command in [
+            "git diff --no-index ~/.ssh/id_rsa /dev/null",
+            "git diff --no-in a b",
+            "git show HEAD:.env",
+            "git show :.env",
+            "git diff --output=/tmp/x",
+            "git log -p --output /tmp/x",
+            "git diff /etc/passwd README.md",
+            "git diff ../other/secret README.md",
+            "git log -- /etc/passwd",
+        ] {
+            assert!(
+                matches!(policy.check(command), Err(ShellError::Argument { .. })),
+                "{}",
+                command
+            );
+        }
+        for command in [
+            "git diff HEAD~1..HEAD -- src/main.rs",
+            "git log --oneline --format=%h:%s main..feature",
+            "git show --stat HEAD",
+            "git status --short",
+        ] {
+            assert!(policy.check(command).is_ok(), "{}", command);
+        }
+    }
+}
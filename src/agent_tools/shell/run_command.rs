@@ -0,0 +1,212 @@
+use crate::agent_tools::{
+    code::sandbox::{capped, read_capped},
+    fs::{error::FsError, workspace::Workspace},
+    shell::{error::ShellError, policy::CommandPolicy},
+    ToToolResult,
+};
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use std::{process::Stdio, time::Duration};
+use tokio::process::Command;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+const MAX_TIMEOUT_SECS: u64 = 600;
+const MAX_OUTPUT_BYTES: usize = 16 * 1024;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct RunCommandArgs {
+    #[schemars(
+        description = "The command line, e.g. 'cargo test -p marvin'. It runs directly without a shell, so pipes, redirects and && are not supported"
+    )]
+    pub command: String,
+    #[schemars(
+        description = "Working directory relative to the workspace root. Defaults to the root"
+    )]
+    pub cwd: Option<String>,
+    #[schemars(description = "Timeout in seconds, up to 600. Defaults to 60")]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RunCommandOutput {
+    pub command: String,
+    pub cwd: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub stdout: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub stderr: String,
+}
+
+pub struct RunCommand {
+    workspace: Workspace,
+    policy: CommandPolicy,
+}
+
+impl RunCommand {
+    pub fn new(workspace: Workspace, policy: CommandPolicy) -> Self {
+        Self { workspace, policy }
+    }
+    pub async fn run(&self, args: &RunCommandArgs) -> Result<RunCommandOutput, ShellError> {
+        let words = self.policy.check(&args.command)?;
+        let timeout = match args.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS) {
+            secs @ 1..=MAX_TIMEOUT_SECS => Duration::from_secs(secs),
+            got => {
+                return Err(ShellError::InvalidTimeout {
+                    got,
+                    max: MAX_TIMEOUT_SECS,
+                })
+            }
+        };
+        let cwd_display = args.cwd.as_deref().unwrap_or(".");
+        let cwd = self.workspace.resolve(cwd_display)?;
+        if !cwd.is_dir() {
+            return Err(FsError::NotADirectory(cwd_display.to_string()).into());
+        }
+        let mut command = Command::new(&words[0]);
+        command
+            .args(&words[1..])
+            .current_dir(&cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        // a process group lets a timeout kill everything the command started, e.g. test binaries under cargo
+        #[cfg(unix)]
+        command.process_group(0);
+        let mut child = command.spawn().map_err(|source| ShellError::Spawn {
+            program: words[0].clone(),
+            source,
+        })?;
+        let mut output = RunCommandOutput {
+            command: words.join(" "),
+            cwd: self.workspace.relative(&cwd),
+            ..Default::default()
+        };
+        let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+        let (mut stdout_bytes, mut stderr_bytes) = (Vec::new(), Vec::new());
+        let run = async {
+            tokio::join!(
+                read_capped(stdout, MAX_OUTPUT_BYTES, &mut stdout_bytes),
+                read_capped(stderr, MAX_OUTPUT_BYTES, &mut stderr_bytes),
+                child.wait()
+            )
+        };
+        match tokio::time::timeout(timeout, run).await {
+            Ok(((), (), status)) => {
+                let status = status?;
+                output.exit_code = status.code();
+                #[cfg(unix)]
+                {
+                    use std::os::unix::process::ExitStatusExt;
+                    output.signal = status.signal();
+                }
+            }
+            Err(_) => {
+                output.timed_out = true;
+                #[cfg(unix)]
+                if let Some(pid) = child.id() {
+                    // SAFETY: killpg only sends a signal to the group created for this child
+                    unsafe {
+                        libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+                    }
+                }
+                let _ = child.kill().await;
+            }
+        }
+        // on a timeout, what the command printed before it hung
+        output.stdout = capped(&stdout_bytes, MAX_OUTPUT_BYTES);
+        output.stderr = capped(&stderr_bytes, MAX_OUTPUT_BYTES);
+        Ok(output)
+    }
+}
+
+impl Tool for RunCommand {
+    const NAME: &'static str = "run_command";
+    type Args = RunCommandArgs;
+    type Output = RunCommandOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "runs a command in the project directory and returns its exit code, stdout and stderr (each capped at {} KiB). Only allowlisted commands can run and the user approves each one. Allowed command prefixes: {}",
+                MAX_OUTPUT_BYTES / 1024,
+                self.policy.allowed().join(", ")
+            ),
+            parameters: serde_json::to_value(schema_for!(RunCommandArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.run(&args).await.to_tool_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(command: &str, timeout_secs: Option<u64>) -> RunCommandArgs {
+        RunCommandArgs {
+            command: command.to_string(),
+            cwd: None,
+            timeout_secs,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_command_in_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn answer() {}\n").unwrap();
+        let tool = RunCommand::new(
+            Workspace::new(dir.path()).unwrap(),
+            CommandPolicy::new(["ls", "seq", "sh", "sleep"], []),
+        );
+        let output = tool
+            .run(&RunCommandArgs {
+                cwd: Some("src".to_string()),
+                ..args("ls", None)
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.cwd, "src");
+        assert_eq!(output.exit_code, Some(0));
+        assert_eq!(output.stdout, "lib.rs\n");
+        // output past the cap is read and dropped, so the command isn't killed by a closed pipe
+        let output = tool.run(&args("seq 100000", None)).await.unwrap();
+        assert_eq!(output.exit_code, Some(0));
+        assert_eq!(output.signal, None);
+        assert!(output.stdout.starts_with("1\n2\n"));
+        assert!(output.stdout.ends_with("[output truncated at 16384 bytes]"));
+        let output = tool
+            .run(&args("sh -c 'echo started; sleep 5'", Some(1)))
+            .await
+            .unwrap();
+        assert!(output.timed_out);
+        assert_eq!(output.stdout, "started\n");
+        assert!(matches!(
+            tool.run(&args("cat src/lib.rs", None)).await,
+            Err(ShellError::NotAllowed { .. })
+        ));
+        assert!(matches!(
+            tool.run(&RunCommandArgs {
+                cwd: Some("..".to_string()),
+                ..args("ls", None)
+            })
+            .await,
+            Err(ShellError::Workspace(FsError::OutsideRoot(_)))
+        ));
+    }
+}
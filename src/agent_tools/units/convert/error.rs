@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum UnitError {
+    #[error("Unit expression is empty")]
+    Empty,
+    #[error("Unknown unit '{0}'")]
+    UnknownUnit(String),
+    #[error("Invalid exponent in '{0}'")]
+    InvalidExponent(String),
+    #[error("Cannot convert {from} ({from_dimension}) to {to} ({to_dimension})")]
+    Incompatible {
+        from: String,
+        from_dimension: String,
+        to: String,
+        to_dimension: String,
+    },
+    #[error("'{0}' has an offset (like °C or °F) and can only be converted on its own, not inside a compound unit")]
+    OffsetInCompound(String),
+    #[error("Value must be a finite number")]
+    NotFinite,
+}
@@ -0,0 +1,138 @@
+pub mod error;
+pub mod request;
+pub mod unit;
+
+use crate::agent_tools::ToToolResult;
+use error::UnitError;
+use request::ConvertUnitsArgs;
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::schema_for;
+use serde::Serialize;
+use unit::{describe, parse_unit, Unit};
+
+const SIGNIFICANT_DIGITS: usize = 12;
+
+#[derive(Debug, Serialize)]
+pub struct ConvertUnitsOutput {
+    pub value: f64,
+    pub unit: String,
+    pub result: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub reciprocal: bool,
+}
+
+fn round_significant(value: f64) -> f64 {
+    format!("{:.*e}", SIGNIFICANT_DIGITS - 1, value)
+        .parse()
+        .unwrap_or(value)
+}
+
+pub fn convert(value: f64, from: &str, to: &str) -> Result<(f64, bool), UnitError> {
+    if !value.is_finite() {
+        return Err(UnitError::NotFinite);
+    }
+    let from_unit = parse_unit(from)?;
+    let to_unit = parse_unit(to)?;
+    let inverse = to_unit.dimension.map(|d| -d);
+    let (converted, reciprocal) = if from_unit.dimension == to_unit.dimension {
+        let base = value * from_unit.factor + from_unit.offset;
+        ((base - to_unit.offset) / to_unit.factor, false)
+    } else if from_unit.dimension == inverse && !has_offset(&from_unit, &to_unit) {
+        (1.0 / (value * from_unit.factor * to_unit.factor), true)
+    } else {
+        return Err(UnitError::Incompatible {
+            from: from.to_string(),
+            from_dimension: describe(&from_unit.dimension),
+            to: to.to_string(),
+            to_dimension: describe(&to_unit.dimension),
+        });
+    };
+    if !converted.is_finite() {
+        return Err(UnitError::NotFinite);
+    }
+    Ok((round_significant(converted), reciprocal))
+}
+
+fn has_offset(from: &Unit, to: &Unit) -> bool {
+    from.offset != 0.0 || to.offset != 0.0
+}
+
+pub struct ConvertUnits;
+
+impl Tool for ConvertUnits {
+    const NAME: &'static str = "convert_units";
+    type Args = ConvertUnitsArgs;
+    type Output = ConvertUnitsOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "converts a value between units, checking that both units measure the same dimension. Supports length, mass, time, temperature, data sizes (bits and bytes with SI or binary prefixes like kB and MiB), energy, power, pressure, speed, volume and compound units built with *, / and ^ (e.g. ns/op, MB/s, kg*m/s^2). Converting between reciprocal dimensions, like ns/op to op/s, inverts the value and sets reciprocal = true".to_string(),
+            parameters: serde_json::to_value(schema_for!(ConvertUnitsArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (value, reciprocal) = convert(args.value, &args.from, &args.to).to_tool_result()?;
+        Ok(ConvertUnitsOutput {
+            value,
+            result: format!("{} {}", value, args.to),
+            unit: args.to,
+            reciprocal,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(convert(1.5, "GiB", "MiB").unwrap(), (1536.0, false));
+        assert_eq!(convert(1.0, "mi", "km").unwrap(), (1.609344, false));
+        assert_eq!(convert(100.0, "degC", "degF").unwrap(), (212.0, false));
+        assert_eq!(convert(-40.0, "°F", "°C").unwrap(), (-40.0, false));
+        assert_eq!(convert(0.0, "degC", "K").unwrap(), (273.15, false));
+        assert_eq!(convert(100.0, "km/h", "m/s").unwrap().0, 27.7777777778);
+        assert_eq!(convert(1.0, "kWh", "J").unwrap(), (3.6e6, false));
+        assert_eq!(convert(1.0, "m^3", "L").unwrap(), (1000.0, false));
+        assert_eq!(convert(8.0, "Mb/s", "MB/s").unwrap(), (1.0, false));
+        assert_eq!(convert(1.0, "N", "kg*m/s^2").unwrap(), (1.0, false));
+    }
+
+    #[test]
+    fn test_reciprocal() {
+        assert_eq!(convert(250.0, "ns/op", "op/s").unwrap(), (4e6, true));
+        assert_eq!(convert(2.0, "GHz", "ns").unwrap(), (0.5, true));
+    }
+
+    #[test]
+    fn test_convert_errors() {
+        assert!(matches!(
+            convert(1.0, "kg", "m"),
+            Err(UnitError::Incompatible { from_dimension, to_dimension, .. })
+                if from_dimension == "mass" && to_dimension == "length"
+        ));
+        assert!(matches!(
+            convert(1.0, "parsec", "m"),
+            Err(UnitError::UnknownUnit(unit)) if unit == "parsec"
+        ));
+        assert!(matches!(
+            convert(1.0, "degC/s", "K/s"),
+            Err(UnitError::OffsetInCompound(_))
+        ));
+        assert!(matches!(
+            convert(1.0, "m^x", "m"),
+            Err(UnitError::InvalidExponent(_))
+        ));
+        assert!(matches!(
+            convert(f64::NAN, "m", "m"),
+            Err(UnitError::NotFinite)
+        ));
+    }
+}
@@ -0,0 +1,16 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ConvertUnitsArgs {
+    #[schemars(description = "The numeric value to convert")]
+    pub value: f64,
+    #[schemars(
+        description = "Unit to convert from. Accepts SI and binary prefixes and compound units, e.g. 'MiB', 'ns/op', 'km/h', 'kg*m/s^2', 'degF'"
+    )]
+    pub from: String,
+    #[schemars(
+        description = "Unit to convert to, with the same dimension as `from` (or its reciprocal, e.g. 'ns/op' to 'op/s')"
+    )]
+    pub to: String,
+}
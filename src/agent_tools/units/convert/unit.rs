@@ -0,0 +1,377 @@
+use crate::agent_tools::units::convert::error::UnitError;
+
+const DIMENSION_NAMES: [&str; 5] = ["length", "mass", "time", "temperature", "information"];
+
+pub type Dimension = [i32; 5];
+
+const NONE: Dimension = [0, 0, 0, 0, 0];
+const LENGTH: Dimension = [1, 0, 0, 0, 0];
+const AREA: Dimension = [2, 0, 0, 0, 0];
+const VOLUME: Dimension = [3, 0, 0, 0, 0];
+const MASS: Dimension = [0, 1, 0, 0, 0];
+const TIME: Dimension = [0, 0, 1, 0, 0];
+const FREQUENCY: Dimension = [0, 0, -1, 0, 0];
+const SPEED: Dimension = [1, 0, -1, 0, 0];
+const FORCE: Dimension = [1, 1, -2, 0, 0];
+const ENERGY: Dimension = [2, 1, -2, 0, 0];
+const POWER: Dimension = [2, 1, -3, 0, 0];
+const PRESSURE: Dimension = [-1, 1, -2, 0, 0];
+const TEMPERATURE: Dimension = [0, 0, 0, 1, 0];
+const INFORMATION: Dimension = [0, 0, 0, 0, 1];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Prefixes {
+    None,
+    Si,
+    SiAndBinary,
+}
+
+struct UnitDef {
+    names: &'static [&'static str],
+    factor: f64,
+    offset: f64,
+    dimension: Dimension,
+    prefixes: Prefixes,
+}
+
+const fn unit(
+    names: &'static [&'static str],
+    factor: f64,
+    dimension: Dimension,
+    prefixes: Prefixes,
+) -> UnitDef {
+    UnitDef {
+        names,
+        factor,
+        offset: 0.0,
+        dimension,
+        prefixes,
+    }
+}
+
+// factors convert to SI base units: metre, kilogram, second, kelvin, bit
+const UNITS: &[UnitDef] = &[
+    unit(
+        &["m", "meter", "meters", "metre", "metres"],
+        1.0,
+        LENGTH,
+        Prefixes::Si,
+    ),
+    unit(&["in", "inch", "inches"], 0.0254, LENGTH, Prefixes::None),
+    unit(&["ft", "foot", "feet"], 0.3048, LENGTH, Prefixes::None),
+    unit(&["yd", "yard", "yards"], 0.9144, LENGTH, Prefixes::None),
+    unit(&["mi", "mile", "miles"], 1609.344, LENGTH, Prefixes::None),
+    unit(&["nmi"], 1852.0, LENGTH, Prefixes::None),
+    unit(&["au"], 1.495_978_707e11, LENGTH, Prefixes::None),
+    unit(
+        &["ly", "lightyear", "lightyears"],
+        9.460_730_472_580_8e15,
+        LENGTH,
+        Prefixes::None,
+    ),
+    unit(&["ha", "hectare", "hectares"], 1e4, AREA, Prefixes::None),
+    unit(&["acre", "acres"], 4_046.856_422_4, AREA, Prefixes::None),
+    unit(
+        &["L", "l", "liter", "liters", "litre", "litres"],
+        1e-3,
+        VOLUME,
+        Prefixes::Si,
+    ),
+    unit(
+        &["gal", "gallon", "gallons"],
+        3.785_411_784e-3,
+        VOLUME,
+        Prefixes::None,
+    ),
+    unit(&["g", "gram", "grams"], 1e-3, MASS, Prefixes::Si),
+    unit(&["t", "tonne", "tonnes"], 1e3, MASS, Prefixes::None),
+    unit(
+        &["lb", "lbs", "pound", "pounds"],
+        0.453_592_37,
+        MASS,
+        Prefixes::None,
+    ),
+    unit(
+        &["oz", "ounce", "ounces"],
+        0.028_349_523_125,
+        MASS,
+        Prefixes::None,
+    ),
+    unit(&["st", "stone"], 6.350_293_18, MASS, Prefixes::None),
+    unit(
+        &["s", "sec", "secs", "second", "seconds"],
+        1.0,
+        TIME,
+        Prefixes::Si,
+    ),
+    unit(
+        &["min", "mins", "minute", "minutes"],
+        60.0,
+        TIME,
+        Prefixes::None,
+    ),
+    unit(
+        &["h", "hr", "hrs", "hour", "hours"],
+        3600.0,
+        TIME,
+        Prefixes::None,
+    ),
+    unit(&["d", "day", "days"], 86_400.0, TIME, Prefixes::None),
+    unit(&["wk", "week", "weeks"], 604_800.0, TIME, Prefixes::None),
+    unit(
+        &["mo", "month", "months"],
+        2_629_746.0,
+        TIME,
+        Prefixes::None,
+    ),
+    unit(&["yr", "year", "years"], 31_556_952.0, TIME, Prefixes::None),
+    unit(&["Hz", "hertz"], 1.0, FREQUENCY, Prefixes::Si),
+    unit(&["mph"], 0.447_04, SPEED, Prefixes::None),
+    unit(
+        &["kn", "knot", "knots"],
+        1852.0 / 3600.0,
+        SPEED,
+        Prefixes::None,
+    ),
+    unit(&["N", "newton", "newtons"], 1.0, FORCE, Prefixes::Si),
+    unit(&["J", "joule", "joules"], 1.0, ENERGY, Prefixes::Si),
+    unit(&["cal", "calorie", "calories"], 4.184, ENERGY, Prefixes::Si),
+    unit(&["Wh"], 3600.0, ENERGY, Prefixes::Si),
+    unit(&["eV"], 1.602_176_634e-19, ENERGY, Prefixes::Si),
+    unit(&["W", "watt", "watts"], 1.0, POWER, Prefixes::Si),
+    unit(
+        &["hp", "horsepower"],
+        745.699_871_582_270_2,
+        POWER,
+        Prefixes::None,
+    ),
+    unit(&["Pa", "pascal", "pascals"], 1.0, PRESSURE, Prefixes::Si),
+    unit(&["bar"], 1e5, PRESSURE, Prefixes::Si),
+    unit(&["atm"], 101_325.0, PRESSURE, Prefixes::None),
+    unit(&["psi"], 6_894.757_293_168, PRESSURE, Prefixes::None),
+    unit(&["K", "kelvin"], 1.0, TEMPERATURE, Prefixes::Si),
+    UnitDef {
+        names: &["degC", "°C", "C", "celsius"],
+        factor: 1.0,
+        offset: 273.15,
+        dimension: TEMPERATURE,
+        prefixes: Prefixes::None,
+    },
+    UnitDef {
+        names: &["degF", "°F", "F", "fahrenheit"],
+        factor: 5.0 / 9.0,
+        offset: 459.67 * 5.0 / 9.0,
+        dimension: TEMPERATURE,
+        prefixes: Prefixes::None,
+    },
+    unit(&["degR", "rankine"], 5.0 / 9.0, TEMPERATURE, Prefixes::None),
+    unit(
+        &["bit", "bits", "b"],
+        1.0,
+        INFORMATION,
+        Prefixes::SiAndBinary,
+    ),
+    unit(
+        &["B", "byte", "bytes"],
+        8.0,
+        INFORMATION,
+        Prefixes::SiAndBinary,
+    ),
+    unit(
+        &[
+            "op",
+            "ops",
+            "operation",
+            "operations",
+            "iter",
+            "iters",
+            "iteration",
+            "iterations",
+        ],
+        1.0,
+        NONE,
+        Prefixes::None,
+    ),
+    unit(&["%", "percent"], 0.01, NONE, Prefixes::None),
+];
+
+const SI_PREFIXES: &[(&str, f64)] = &[
+    ("da", 1e1),
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("µ", 1e-6),
+    ("μ", 1e-6),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+];
+
+const BINARY_PREFIXES: &[(&str, f64)] = &[
+    ("Ki", 1024.0),
+    ("Mi", 1_048_576.0),
+    ("Gi", 1_073_741_824.0),
+    ("Ti", 1_099_511_627_776.0),
+    ("Pi", 1_125_899_906_842_624.0),
+    ("Ei", 1_152_921_504_606_846_976.0),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub factor: f64,
+    pub offset: f64,
+    pub dimension: Dimension,
+}
+
+impl Unit {
+    fn dimensionless() -> Self {
+        Self {
+            factor: 1.0,
+            offset: 0.0,
+            dimension: NONE,
+        }
+    }
+    fn powi(&self, exponent: i32) -> Self {
+        Self {
+            factor: self.factor.powi(exponent),
+            offset: self.offset,
+            dimension: self.dimension.map(|d| d * exponent),
+        }
+    }
+    fn mul(&self, other: &Unit) -> Self {
+        let mut dimension = self.dimension;
+        for (d, o) in dimension.iter_mut().zip(other.dimension) {
+            *d += o;
+        }
+        Self {
+            factor: self.factor * other.factor,
+            offset: 0.0,
+            dimension,
+        }
+    }
+}
+
+pub fn describe(dimension: &Dimension) -> String {
+    let (numerator, denominator): (Vec<_>, Vec<_>) = DIMENSION_NAMES
+        .iter()
+        .zip(dimension)
+        .filter(|(_, power)| **power != 0)
+        .partition(|(_, power)| **power > 0);
+    let format = |parts: Vec<(&&str, &i32)>| {
+        parts
+            .iter()
+            .map(|(name, power)| match power.abs() {
+                1 => name.to_string(),
+                power => format!("{}^{}", name, power),
+            })
+            .collect::<Vec<_>>()
+            .join(" * ")
+    };
+    match (numerator.is_empty(), denominator.is_empty()) {
+        (true, true) => "dimensionless".to_string(),
+        (false, true) => format(numerator),
+        (true, false) => format!("1 / {}", format(denominator)),
+        (false, false) => format!("{} / {}", format(numerator), format(denominator)),
+    }
+}
+
+fn from_def(def: &UnitDef, scale: f64) -> Unit {
+    Unit {
+        factor: def.factor * scale,
+        offset: def.offset,
+        dimension: def.dimension,
+    }
+}
+
+fn lookup(name: &str) -> Result<Unit, UnitError> {
+    if let Some(def) = UNITS.iter().find(|def| def.names.contains(&name)) {
+        return Ok(from_def(def, 1.0));
+    }
+    for def in UNITS.iter().filter(|def| def.prefixes != Prefixes::None) {
+        let binary = match def.prefixes {
+            Prefixes::SiAndBinary => BINARY_PREFIXES,
+            _ => &[],
+        };
+        for (prefix, scale) in binary.iter().chain(SI_PREFIXES) {
+            if let Some(rest) = name.strip_prefix(prefix)
+                && def.names.contains(&rest)
+            {
+                return Ok(from_def(def, *scale));
+            }
+        }
+    }
+    let lowercase = name.to_lowercase();
+    UNITS
+        .iter()
+        .find(|def| def.names.len() > 1 && def.names[1..].contains(&lowercase.as_str()))
+        .map(|def| from_def(def, 1.0))
+        .ok_or_else(|| UnitError::UnknownUnit(name.to_string()))
+}
+
+fn parse_term(term: &str) -> Result<Unit, UnitError> {
+    let (name, exponent) = if let Some(name) = term.strip_suffix('²') {
+        (name, 2)
+    } else if let Some(name) = term.strip_suffix('³') {
+        (name, 3)
+    } else if let Some((name, exponent)) = term.split_once('^') {
+        let exponent = exponent
+            .trim()
+            .parse::<i32>()
+            .map_err(|_| UnitError::InvalidExponent(term.to_string()))?;
+        (name, exponent)
+    } else {
+        (term, 1)
+    };
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(UnitError::Empty);
+    }
+    let unit = if name == "1" {
+        Unit::dimensionless()
+    } else {
+        lookup(name)?
+    };
+    if unit.offset != 0.0 && exponent != 1 {
+        return Err(UnitError::OffsetInCompound(name.to_string()));
+    }
+    Ok(unit.powi(exponent))
+}
+
+pub fn parse_unit(expression: &str) -> Result<Unit, UnitError> {
+    let expression = expression.trim();
+    if expression.is_empty() {
+        return Err(UnitError::Empty);
+    }
+    let mut terms = Vec::new();
+    let mut rest = expression;
+    let mut divide = false;
+    loop {
+        let end = rest.find(['*', '/', '·']).unwrap_or(rest.len());
+        let term = parse_term(&rest[..end])?;
+        terms.push(if divide { term.powi(-1) } else { term });
+        let Some(op) = rest[end..].chars().next() else {
+            break;
+        };
+        divide = op == '/';
+        rest = &rest[end + op.len_utf8()..];
+    }
+    if terms.len() > 1 && terms.iter().any(|term| term.offset != 0.0) {
+        return Err(UnitError::OffsetInCompound(expression.to_string()));
+    }
+    if terms.len() == 1 {
+        return Ok(terms.remove(0));
+    }
+    Ok(terms
+        .iter()
+        .fold(Unit::dimensionless(), |unit, term| unit.mul(term)))
+}
@@ -0,0 +1,8 @@
+use crate::agent_tools::units::convert::ConvertUnits;
+use rig::tool::ToolDyn;
+
+pub mod convert;
+
+pub fn units_tools() -> Vec<Box<dyn ToolDyn>> {
+    vec![Box::new(ConvertUnits)]
+}
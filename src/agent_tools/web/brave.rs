@@ -0,0 +1,174 @@
+use crate::{
+    agent_tools::web::{
+        cache::Cached,
+        crawl::{request::CrawlArgs, response::CrawlResponse},
+        error::WebError,
+        extract::{request::ExtractArgs, response::ExtractResponse},
+        html::strip_tags,
+        local::LocalFetcher,
+        map::{request::MapArgs, response::MapResponse},
+        provider::{filter_domains, send, ProviderKind, WebResult, WebSearchProvider},
+        rate_limit::{RateLimit, RateLimiter},
+        search::{
+            request::{SearchArgs, TimeRange, Topic},
+            response::{SearchResponse, SearchResult},
+        },
+    },
+    cassette::HttpTransport,
+};
+use reqwest::header::ACCEPT;
+use serde::Deserialize;
+use url::Url;
+
+const PROVIDER: &str = "Brave";
+const WEB_PATH: &str = "/res/v1/web/search";
+const NEWS_PATH: &str = "/res/v1/news/search";
+const DEFAULT_MAX_RESULTS: u8 = 5;
+const MAX_COUNT: u8 = 20;
+
+// web results sit under `web`, news results at the top level
+#[derive(Debug, Deserialize)]
+struct BraveResponse {
+    web: Option<BraveResults>,
+    #[serde(default)]
+    results: Vec<BraveResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveResults {
+    #[serde(default)]
+    results: Vec<BraveResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveResult {
+    url: String,
+    #[serde(default)]
+    title: String,
+    description: Option<String>,
+    #[serde(default)]
+    extra_snippets: Vec<String>,
+}
+
+// the Brave Search API for search, pages are fetched directly for extract, crawl and map
+pub struct BraveClient {
+    transport: HttpTransport,
+    limiter: RateLimiter,
+    api_key: String,
+    base_url: String,
+    fetcher: LocalFetcher,
+}
+
+impl BraveClient {
+    pub fn new(
+        transport: HttpTransport,
+        api_key: impl Into<String>,
+        base_url: impl Into<String>,
+        fetcher: LocalFetcher,
+    ) -> Self {
+        Self {
+            transport,
+            limiter: RateLimiter::new(ProviderKind::Brave.rate_limit()),
+            api_key: api_key.into(),
+            base_url: base_url.into(),
+            fetcher,
+        }
+    }
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.limiter = RateLimiter::new(limit);
+        self
+    }
+    async fn search_brave(&self, args: &SearchArgs) -> Result<Cached<SearchResponse>, WebError> {
+        let endpoint = match args.topic {
+            Some(Topic::News) => NEWS_PATH,
+            _ => WEB_PATH,
+        };
+        let max_results = args.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+        let mut url = Url::parse(&format!("{}{}", self.base_url, endpoint))?;
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("q", &args.query)
+                .append_pair("count", &max_results.clamp(1, MAX_COUNT).to_string());
+            let freshness = match args.time_range {
+                Some(TimeRange::Day) => Some("pd"),
+                Some(TimeRange::Week) => Some("pw"),
+                Some(TimeRange::Month) => Some("pm"),
+                Some(TimeRange::Year) => Some("py"),
+                _ => None,
+            };
+            if let Some(freshness) = freshness {
+                query.append_pair("freshness", freshness);
+            }
+            if let Some(country) = &args.country {
+                query.append_pair("country", country);
+            }
+        }
+        let request = self
+            .transport
+            .client()
+            .get(url)
+            .header(ACCEPT, "application/json")
+            .header("X-Subscription-Token", &self.api_key);
+        let response = send(&self.transport, &self.limiter, PROVIDER, endpoint, request).await?;
+        let response: BraveResponse =
+            serde_json::from_value(response).map_err(|source| WebError::Schema {
+                provider: PROVIDER,
+                endpoint,
+                source,
+            })?;
+        let mut results = response.web.map(|web| web.results).unwrap_or_default();
+        results.extend(response.results);
+        let mut results = filter_domains(
+            results,
+            |result| &result.url,
+            args.include_domains.as_deref(),
+            args.exclude_domains.as_deref(),
+        );
+        results.truncate(max_results.into());
+        Ok(Cached::fresh(SearchResponse {
+            query: args.query.clone(),
+            answer: None,
+            images: Vec::new(),
+            results: results
+                .into_iter()
+                .map(|result| SearchResult {
+                    title: strip_tags(&result.title),
+                    url: result.url,
+                    content: result
+                        .description
+                        .iter()
+                        .chain(&result.extra_snippets)
+                        .map(|snippet| strip_tags(snippet))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    score: None,
+                    raw_content: None,
+                    favicon: None,
+                })
+                .collect(),
+            response_time: None,
+            auto_parameters: None,
+            usage: None,
+            request_id: None,
+        }))
+    }
+}
+
+impl WebSearchProvider for BraveClient {
+    fn name(&self) -> &'static str {
+        "brave"
+    }
+    fn search<'a>(&'a self, args: &'a SearchArgs) -> WebResult<'a, SearchResponse> {
+        Box::pin(self.search_brave(args))
+    }
+    fn extract<'a>(&'a self, args: &'a ExtractArgs) -> WebResult<'a, ExtractResponse> {
+        self.fetcher.extract(args)
+    }
+    fn crawl<'a>(&'a self, args: &'a CrawlArgs) -> WebResult<'a, CrawlResponse> {
+        self.fetcher.crawl(args)
+    }
+    fn map<'a>(&'a self, args: &'a MapArgs) -> WebResult<'a, MapResponse> {
+        self.fetcher.map(args)
+    }
+}
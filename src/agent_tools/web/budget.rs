@@ -0,0 +1,193 @@
+use serde::Serialize;
+
+// a rough but stable estimate, good enough to keep tool output inside a context budget
+pub const DEFAULT_TOKEN_BUDGET: usize = 4000;
+pub const CHARS_PER_TOKEN: usize = 4;
+const MIN_CONTENT_CHARS: usize = 300;
+const PAGE_OVERHEAD_CHARS: usize = 48;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Page {
+    // the id the agent cites this page by, like S3
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    pub content: String,
+}
+
+impl Page {
+    fn overhead(&self) -> usize {
+        PAGE_OVERHEAD_CHARS + self.url.len() + self.title.as_ref().map_or(0, String::len)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    tokens: usize,
+}
+
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    let Some((end, _)) = text.char_indices().nth(max_chars) else {
+        return text.to_string();
+    };
+    // prefer to cut between words
+    let end = text[..end]
+        .rfind(char::is_whitespace)
+        .filter(|space| *space > end / 2)
+        .unwrap_or(end);
+    let rest = text[end..].chars().count();
+    format!(
+        "{} … [{} more characters truncated]",
+        text[..end].trim_end(),
+        rest
+    )
+}
+
+// stable, so pages with equal hits keep the order Tavily returned them in
+pub fn rank_by_terms(pages: &mut [Page], query: &str) {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| term.len() > 2)
+        .map(str::to_lowercase)
+        .collect();
+    if terms.is_empty() {
+        return;
+    }
+    pages.sort_by_cached_key(|page| {
+        let text = format!(
+            "{} {}",
+            page.title.as_deref().unwrap_or_default(),
+            page.content
+        )
+        .to_lowercase();
+        let hits: usize = terms
+            .iter()
+            .map(|term| text.matches(term.as_str()).count())
+            .sum();
+        std::cmp::Reverse(hits)
+    });
+}
+
+impl Budget {
+    pub fn new(tokens: usize) -> Self {
+        Self { tokens }
+    }
+    fn chars(&self) -> usize {
+        self.tokens * CHARS_PER_TOKEN
+    }
+    pub fn omitted(&self, count: usize, what: &str) -> Option<String> {
+        (count > 0).then(|| {
+            format!(
+                "{} more {} omitted to stay within the {}-token budget",
+                count, what, self.tokens
+            )
+        })
+    }
+    // keeps as many pages as fit with a useful amount of content each, in ranked order, then
+    // shares the budget between them so short pages hand their unused share to long ones
+    pub fn fit_pages(&self, mut pages: Vec<Page>) -> (Vec<Page>, usize) {
+        let mut used = 0;
+        let kept = pages
+            .iter()
+            .take_while(|page| {
+                used += page.overhead() + page.content.chars().count().min(MIN_CONTENT_CHARS);
+                used <= self.chars()
+            })
+            .count()
+            .max(1)
+            .min(pages.len());
+        let omitted = pages.len() - kept;
+        pages.truncate(kept);
+        let overhead: usize = pages.iter().map(Page::overhead).sum();
+        let mut remaining = self.chars().saturating_sub(overhead);
+        let mut by_length: Vec<usize> = (0..pages.len()).collect();
+        by_length.sort_by_key(|i| pages[*i].content.chars().count());
+        for (position, i) in by_length.into_iter().enumerate() {
+            let share = remaining / (kept - position);
+            let length = pages[i].content.chars().count();
+            let allowed = length.min(share.max(MIN_CONTENT_CHARS.min(length)));
+            pages[i].content = truncate(&pages[i].content, allowed);
+            remaining = remaining.saturating_sub(allowed);
+        }
+        (pages, omitted)
+    }
+    pub fn fit_urls(&self, mut urls: Vec<String>) -> (Vec<String>, usize) {
+        let mut used = 0;
+        let kept = urls
+            .iter()
+            .take_while(|url| {
+                used += url.len() + 3;
+                used <= self.chars()
+            })
+            .count();
+        let omitted = urls.len() - kept;
+        urls.truncate(kept);
+        (urls, omitted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(url: &str, content: &str) -> Page {
+        Page {
+            source: None,
+            title: None,
+            url: url.to_string(),
+            score: None,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_truncate_between_words() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(
+            truncate("the quick brown fox", 12),
+            "the quick … [10 more characters truncated]"
+        );
+    }
+
+    #[test]
+    fn test_fit_pages_shares_budget_and_omits_the_rest() {
+        let long = "word ".repeat(2000);
+        let pages = vec![
+            page("https://a", "tiny"),
+            page("https://b", &long),
+            page("https://c", &long),
+            page("https://d", &long),
+        ];
+        let (kept, omitted) = Budget::new(250).fit_pages(pages);
+        assert_eq!(omitted, 1);
+        assert_eq!(kept.len(), 3);
+        assert_eq!(kept[0].content, "tiny");
+        let total: usize = kept
+            .iter()
+            .map(|page| page.overhead() + page.content.len())
+            .sum();
+        assert!(total <= 250 * CHARS_PER_TOKEN + 2 * 40, "{}", total);
+        assert!(kept[1].content.ends_with("characters truncated]"));
+        assert_eq!(
+            Budget::new(250).omitted(omitted, "page(s)").unwrap(),
+            "1 more page(s) omitted to stay within the 250-token budget"
+        );
+    }
+
+    #[test]
+    fn test_rank_by_terms_is_stable() {
+        let mut pages = vec![
+            page("https://a", "nothing here"),
+            page("https://b", "async traits in rust"),
+            page("https://c", "more nothing"),
+        ];
+        rank_by_terms(&mut pages, "async traits");
+        let urls: Vec<&str> = pages.iter().map(|page| page.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://b", "https://a", "https://c"]);
+    }
+}
@@ -0,0 +1,327 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub const CACHE_DIR_NAME: &str = "cache";
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+
+// arguments that change how a request runs but not what it returns
+const IGNORED_ARGS: &[&str] = &["timeout"];
+// free text where case and spacing do not change the answer
+const TEXT_ARGS: &[&str] = &["query", "instructions"];
+
+// how long a response stays fresh, news moves fast while page contents rarely change
+pub fn ttl(endpoint: &str, args: &Value) -> Duration {
+    let secs = match endpoint.trim_start_matches('/') {
+        "search" if args["topic"] == "news" || args["time_range"] == "day" => 30 * MINUTE,
+        "search" => DAY,
+        "extract" => 7 * DAY,
+        _ => 3 * DAY,
+    };
+    Duration::from_secs(secs)
+}
+
+fn normalize_value(key: Option<&str>, value: &Value) -> Option<Value> {
+    match value {
+        Value::Null => None,
+        Value::String(text) if key.is_some_and(|key| TEXT_ARGS.contains(&key)) => Some(
+            text.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+                .into(),
+        ),
+        Value::String(text) => Some(text.trim().into()),
+        // lists of urls, domains and paths are sets
+        Value::Array(items) if items.iter().all(Value::is_string) => {
+            let mut items: Vec<String> = items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|item| item.trim().to_string())
+                .collect();
+            items.sort();
+            items.dedup();
+            Some(items.into())
+        }
+        Value::Array(items) => Some(
+            items
+                .iter()
+                .filter_map(|item| normalize_value(None, item))
+                .collect(),
+        ),
+        Value::Object(map) => Some(Value::Object(
+            map.iter()
+                .filter(|(key, _)| !IGNORED_ARGS.contains(&key.as_str()))
+                .filter_map(|(key, value)| {
+                    normalize_value(Some(key), value).map(|value| (key.clone(), value))
+                })
+                .collect::<Map<_, _>>(),
+        )),
+        value => Some(value.clone()),
+    }
+}
+
+pub fn normalize(args: &Value) -> Value {
+    normalize_value(None, args).unwrap_or(Value::Null)
+}
+
+// serde_json keeps object keys sorted, so equal arguments always hash the same
+pub fn key(endpoint: &str, args: &Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(endpoint.as_bytes());
+    hasher.update([0]);
+    hasher.update(normalize(args).to_string().as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    endpoint: String,
+    args: Value,
+    stored_at: u64,
+    response: Value,
+}
+
+#[derive(Debug, Default)]
+pub struct EndpointStats {
+    pub entries: usize,
+    pub expired: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    pub endpoints: BTreeMap<String, EndpointStats>,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    pub fn entries(&self) -> usize {
+        self.endpoints.values().map(|stats| stats.entries).sum()
+    }
+    pub fn bytes(&self) -> u64 {
+        self.endpoints.values().map(|stats| stats.bytes).sum()
+    }
+}
+
+// content-addressed responses under <dir>/<endpoint>/<sha256>.json, failures only cost a cache miss
+pub struct ResponseCache {
+    dir: PathBuf,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    fn path(&self, endpoint: &str, args: &Value) -> PathBuf {
+        self.dir
+            .join(endpoint.trim_start_matches('/'))
+            .join(format!("{}.json", key(endpoint, args)))
+    }
+    // a fresh response and how long ago it was fetched
+    pub fn get(&self, endpoint: &str, args: &Value) -> Option<(Value, Duration)> {
+        let path = self.path(endpoint, args);
+        let fresh = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Entry>(&bytes).ok())
+            .and_then(|entry| {
+                let age = Duration::from_secs(now().saturating_sub(entry.stored_at));
+                if age < ttl(endpoint, args) {
+                    Some((entry.response, age))
+                } else {
+                    let _ = std::fs::remove_file(&path);
+                    None
+                }
+            });
+        let counter = if fresh.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        fresh
+    }
+    pub fn put(&self, endpoint: &str, args: &Value, response: &Value) -> std::io::Result<()> {
+        let path = self.path(endpoint, args);
+        let dir = path.parent().unwrap_or(&self.dir);
+        std::fs::create_dir_all(dir)?;
+        let entry = Entry {
+            endpoint: endpoint.to_string(),
+            args: normalize(args),
+            stored_at: now(),
+            response: response.clone(),
+        };
+        // write then rename so a concurrent reader never sees half an entry
+        let mut file = tempfile::NamedTempFile::new_in(dir)?;
+        serde_json::to_writer(&mut file, &entry)?;
+        file.persist(&path).map_err(|e| e.error)?;
+        Ok(())
+    }
+    fn files(&self) -> impl Iterator<Item = (String, PathBuf)> {
+        std::fs::read_dir(&self.dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .flat_map(|dir| {
+                let endpoint = format!("/{}", dir.file_name().to_string_lossy());
+                std::fs::read_dir(dir.path())
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .map(move |path| (endpoint.clone(), path))
+            })
+    }
+    pub fn stats(&self) -> CacheStats {
+        let mut stats = CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            ..CacheStats::default()
+        };
+        for (endpoint, path) in self.files() {
+            let endpoint_stats = stats.endpoints.entry(endpoint.clone()).or_default();
+            let Ok(bytes) = std::fs::read(&path) else {
+                continue;
+            };
+            endpoint_stats.entries += 1;
+            endpoint_stats.bytes += bytes.len() as u64;
+            let expired = serde_json::from_slice::<Entry>(&bytes).map_or(true, |entry| {
+                now().saturating_sub(entry.stored_at) >= ttl(&endpoint, &entry.args).as_secs()
+            });
+            if expired {
+                endpoint_stats.expired += 1;
+            }
+        }
+        stats
+    }
+    // removes every stored response and returns how many there were
+    pub fn clear(&self) -> std::io::Result<usize> {
+        let mut removed = 0;
+        for (_, path) in self.files() {
+            std::fs::remove_file(path)?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+}
+
+// a response and, when it came from the local cache, how long ago it was fetched
+pub struct Cached<T> {
+    pub value: T,
+    pub age: Option<Duration>,
+}
+
+impl<T> Cached<T> {
+    pub fn fresh(value: T) -> Self {
+        Self { value, age: None }
+    }
+    pub fn note(&self) -> Option<String> {
+        self.age.map(|age| {
+            format!(
+                "Served from the local cache (fetched {}), no credits used",
+                describe_age(age)
+            )
+        })
+    }
+}
+
+pub fn describe_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..MINUTE => "just now".to_string(),
+        MINUTE..HOUR => format!("{}m ago", secs / MINUTE),
+        HOUR..DAY => format!("{}h {}m ago", secs / HOUR, secs % HOUR / MINUTE),
+        _ => format!("{}d {}h ago", secs / DAY, secs % DAY / HOUR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_equivalent_args_share_a_key() {
+        let a = json!({ "query": "  Rust   Async ", "include_domains": ["b.com", "a.com", "a.com"], "timeout": 5 });
+        let b = json!({ "query": "rust async", "include_domains": ["a.com", "b.com"], "country": null });
+        assert_eq!(key("/search", &a), key("/search", &b));
+        assert_ne!(key("/search", &a), key("/extract", &a));
+        assert_ne!(
+            key("/search", &a),
+            key(
+                "/search",
+                &json!({ "query": "rust async", "topic": "news" })
+            )
+        );
+    }
+
+    #[test]
+    fn test_expired_entries_are_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path());
+        let args = json!({ "query": "rust", "topic": "news" });
+        assert!(cache.get("/search", &args).is_none());
+        cache
+            .put("/search", &args, &json!({ "results": [] }))
+            .unwrap();
+        let (response, age) = cache.get("/search", &args).unwrap();
+        assert_eq!(response, json!({ "results": [] }));
+        assert!(age < Duration::from_secs(5));
+
+        // backdate the entry past the news ttl
+        let path = cache.path("/search", &args);
+        let mut entry: Entry = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        entry.stored_at -= 31 * MINUTE;
+        std::fs::write(&path, serde_json::to_vec(&entry).unwrap()).unwrap();
+        let stats = cache.stats();
+        assert_eq!(
+            (stats.entries(), stats.endpoints["/search"].expired),
+            (1, 1)
+        );
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert!(cache.get("/search", &args).is_none());
+        assert!(!path.exists());
+
+        cache
+            .put(
+                "/extract",
+                &json!({ "urls": ["https://a.com"] }),
+                &json!({}),
+            )
+            .unwrap();
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert_eq!(cache.stats().entries(), 0);
+    }
+}
@@ -1,37 +1,43 @@
 pub mod request;
-#[allow(dead_code)]
 pub mod response;
 
-use crate::agent_tools::{SomeError, ToToolError, ToToolResult};
-
-use super::tavily::{TavilyClient, BASE_URL};
+use crate::agent_tools::{
+    web::{budget::Budget, provider::WebSearchProvider, sources::Sources, validate::Validate},
+    ToToolResult,
+};
 use request::CrawlArgs;
-use reqwest::StatusCode;
+use response::CrawlOutput;
 use rig::{
     completion::ToolDefinition,
     tool::{Tool, ToolError},
 };
 use schemars::schema_for;
-use serde_json::Value;
 use std::sync::Arc;
-use url::Url;
-
-const CRAWL_PATH: &str = "/crawl";
 
 pub struct Crawl {
-    client: Arc<TavilyClient>,
+    provider: Arc<dyn WebSearchProvider>,
+    sources: Arc<Sources>,
+    token_budget: usize,
 }
 
 impl Crawl {
-    pub fn new(client: Arc<TavilyClient>) -> Self {
-        Self { client }
+    pub fn new(
+        provider: Arc<dyn WebSearchProvider>,
+        sources: Arc<Sources>,
+        token_budget: usize,
+    ) -> Self {
+        Self {
+            provider,
+            sources,
+            token_budget,
+        }
     }
 }
 
 impl Tool for Crawl {
     const NAME: &'static str = "crawl_website";
     type Args = CrawlArgs;
-    type Output = Value;
+    type Output = CrawlOutput;
     type Error = ToolError;
 
     async fn definition(&self, _prompt: String) -> ToolDefinition {
@@ -43,19 +49,20 @@ impl Tool for Crawl {
     }
 
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
-        let url = Url::parse(BASE_URL)
-            .to_tool_result()?
-            .join(CRAWL_PATH)
-            .to_tool_result()?;
-        let json = serde_json::to_value(args).to_tool_result()?;
-        let response = self.client.post(url, json).await.to_tool_result()?;
-        let status = response.status();
-        let body = response.json::<Value>().await.to_tool_result()?;
-        match status {
-            StatusCode::OK => Ok(body),
-            status => {
-                Err(SomeError(format!("Crawl failed with {}: {:?}", status, body)).to_tool_err())
-            }
-        }
+        args.validate().to_tool_result()?;
+        let budget = Budget::new(self.token_budget);
+        self.provider
+            .crawl(&args)
+            .await
+            .map(|cached| {
+                let cache = cached.note();
+                let mut output = CrawlOutput {
+                    cache,
+                    ..cached.value.render(budget, args.instructions.as_deref())
+                };
+                self.sources.cite_pages(&mut output.results);
+                output
+            })
+            .to_tool_result()
     }
 }
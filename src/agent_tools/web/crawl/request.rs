@@ -1,6 +1,20 @@
+use crate::agent_tools::web::{
+    error::WebError,
+    validate::{at_least, range, Validate},
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+const MIN_CHUNKS: u8 = 1;
+const MAX_CHUNKS: u8 = 5;
+pub const MIN_DEPTH: u8 = 1;
+pub const MAX_DEPTH: u8 = 5;
+pub const MIN_BREADTH: u16 = 1;
+pub const MAX_BREADTH: u16 = 500;
+pub const MIN_LIMIT: u32 = 1;
+pub const MIN_TIMEOUT: f32 = 10.0;
+pub const MAX_TIMEOUT: f32 = 150.0;
+
 #[derive(Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[schemars(description = "Depth of content extraction.")]
@@ -19,7 +33,7 @@ pub enum CrawlFormat {
     Text,
 }
 
-#[derive(Serialize, Deserialize, JsonSchema)]
+#[derive(Default, Serialize, Deserialize, JsonSchema)]
 pub struct CrawlArgs {
     #[schemars(description = "The root URL to begin the crawl")]
     pub url: String,
@@ -31,19 +45,31 @@ pub struct CrawlArgs {
     pub instructions: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Max relevant chunks per source (1-5). Default: 3")]
+    #[schemars(
+        description = "Max relevant chunks per source (1-5). Default: 3",
+        range(min = MIN_CHUNKS, max = MAX_CHUNKS)
+    )]
     pub chunks_per_source: Option<u8>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "How far from the base URL the crawler can explore (1-5). Default: 1")]
+    #[schemars(
+        description = "How far from the base URL the crawler can explore (1-5). Default: 1",
+        range(min = MIN_DEPTH, max = MAX_DEPTH)
+    )]
     pub max_depth: Option<u8>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Links followed per page level (1-500). Default: 20")]
+    #[schemars(
+        description = "Links followed per page level (1-500). Default: 20",
+        range(min = MIN_BREADTH, max = MAX_BREADTH)
+    )]
     pub max_breadth: Option<u16>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Total links the crawler will process. Default: 50")]
+    #[schemars(
+        description = "Total links the crawler will process (at least 1). Default: 50",
+        range(min = MIN_LIMIT)
+    )]
     pub limit: Option<u32>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -83,10 +109,24 @@ pub struct CrawlArgs {
     pub include_favicon: Option<bool>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Operation timeout in seconds (10-150). Default: 150")]
+    #[schemars(
+        description = "Operation timeout in seconds (10-150). Default: 150",
+        range(min = MIN_TIMEOUT, max = MAX_TIMEOUT)
+    )]
     pub timeout: Option<f32>,
+}
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Include credit usage information in response")]
-    pub include_usage: Option<bool>,
+impl Validate for CrawlArgs {
+    fn validate(&self) -> Result<(), WebError> {
+        range(
+            "chunks_per_source",
+            self.chunks_per_source,
+            MIN_CHUNKS,
+            MAX_CHUNKS,
+        )?;
+        range("max_depth", self.max_depth, MIN_DEPTH, MAX_DEPTH)?;
+        range("max_breadth", self.max_breadth, MIN_BREADTH, MAX_BREADTH)?;
+        at_least("limit", self.limit, MIN_LIMIT)?;
+        range("timeout", self.timeout, MIN_TIMEOUT, MAX_TIMEOUT)
+    }
 }
@@ -1,12 +1,13 @@
+use crate::agent_tools::web::budget::{rank_by_terms, Budget, Page};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CrawlResponse {
     pub base_url: String,
     pub results: Vec<CrawlResult>,
-    pub response_time: f64,
+    pub response_time: Option<f64>,
     pub usage: Option<Usage>,
-    pub request_id: String,
+    pub request_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -20,3 +21,39 @@ pub struct CrawlResult {
 pub struct Usage {
     pub credits: u32,
 }
+
+#[derive(Debug, Serialize)]
+pub struct CrawlOutput {
+    pub base_url: String,
+    pub results: Vec<Page>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub omitted: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<String>,
+}
+
+impl CrawlResponse {
+    pub fn render(self, budget: Budget, instructions: Option<&str>) -> CrawlOutput {
+        let mut pages: Vec<Page> = self
+            .results
+            .into_iter()
+            .map(|result| Page {
+                source: None,
+                title: None,
+                url: result.url,
+                score: None,
+                content: result.raw_content,
+            })
+            .collect();
+        if let Some(instructions) = instructions {
+            rank_by_terms(&mut pages, instructions);
+        }
+        let (results, omitted) = budget.fit_pages(pages);
+        CrawlOutput {
+            base_url: self.base_url,
+            results,
+            omitted: budget.omitted(omitted, "page(s)"),
+            cache: None,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use serde::Deserialize;
+use std::{collections::BTreeMap, sync::Mutex};
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ToolCredits {
+    pub calls: u32,
+    pub cached: u32,
+    pub credits: f64,
+}
+
+// credits spent by each web tool this session, as reported in each response's usage
+#[derive(Debug, Default)]
+pub struct CreditLedger {
+    tools: Mutex<BTreeMap<&'static str, ToolCredits>>,
+}
+
+impl CreditLedger {
+    pub fn record(&self, tool: &'static str, credits: f64) {
+        let mut tools = self.tools.lock().unwrap();
+        let entry = tools.entry(tool).or_default();
+        entry.calls += 1;
+        entry.credits += credits;
+    }
+    // answered from the local cache, so nothing was spent
+    pub fn record_cached(&self, tool: &'static str) {
+        let mut tools = self.tools.lock().unwrap();
+        let entry = tools.entry(tool).or_default();
+        entry.calls += 1;
+        entry.cached += 1;
+    }
+    pub fn tools(&self) -> BTreeMap<&'static str, ToolCredits> {
+        self.tools.lock().unwrap().clone()
+    }
+    pub fn total(&self) -> f64 {
+        self.tools
+            .lock()
+            .unwrap()
+            .values()
+            .map(|tool| tool.credits)
+            .sum()
+    }
+}
+
+// GET /usage, limits are null on plans without one
+#[derive(Debug, Deserialize)]
+pub struct AccountUsage {
+    pub key: Option<KeyUsage>,
+    pub account: Option<PlanUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KeyUsage {
+    #[serde(default)]
+    pub usage: f64,
+    pub limit: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PlanUsage {
+    pub current_plan: Option<String>,
+    #[serde(default)]
+    pub plan_usage: f64,
+    pub plan_limit: Option<f64>,
+    #[serde(default)]
+    pub paygo_usage: f64,
+    pub paygo_limit: Option<f64>,
+}
+
+fn remaining(used: f64, limit: Option<f64>) -> Option<f64> {
+    limit.map(|limit| (limit - used).max(0.0))
+}
+
+impl KeyUsage {
+    pub fn remaining(&self) -> Option<f64> {
+        remaining(self.usage, self.limit)
+    }
+}
+
+impl PlanUsage {
+    pub fn remaining(&self) -> Option<f64> {
+        remaining(self.plan_usage, self.plan_limit)
+    }
+    pub fn paygo_remaining(&self) -> Option<f64> {
+        remaining(self.paygo_usage, self.paygo_limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_ledger_totals_per_tool() {
+        let ledger = CreditLedger::default();
+        ledger.record("search_web", 1.0);
+        ledger.record("search_web", 2.0);
+        ledger.record_cached("search_web");
+        ledger.record("map_website", 0.5);
+        let tools = ledger.tools();
+        assert_eq!(
+            tools["search_web"],
+            ToolCredits {
+                calls: 3,
+                cached: 1,
+                credits: 3.0
+            }
+        );
+        assert_eq!(ledger.total(), 3.5);
+    }
+
+    #[test]
+    fn test_account_usage_tolerates_missing_limits() {
+        let usage: AccountUsage = serde_json::from_value(json!({
+            "key": { "usage": 150, "limit": null },
+            "account": { "current_plan": "Researcher", "plan_usage": 500, "plan_limit": 1000 }
+        }))
+        .unwrap();
+        assert_eq!(usage.key.unwrap().remaining(), None);
+        let account = usage.account.unwrap();
+        assert_eq!(account.remaining(), Some(500.0));
+        assert_eq!(account.paygo_remaining(), None);
+    }
+}
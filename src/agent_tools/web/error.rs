@@ -0,0 +1,85 @@
+use crate::cassette::TransportError;
+use reqwest::StatusCode;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WebError {
+    #[error("Invalid URL: {0}")]
+    Url(#[from] url::ParseError),
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    #[error("Could not encode the {provider} {endpoint} request: {source}")]
+    Request {
+        provider: &'static str,
+        endpoint: &'static str,
+        source: serde_json::Error,
+    },
+    #[error("{provider} {endpoint} failed with {status}: {body}")]
+    Status {
+        provider: &'static str,
+        endpoint: &'static str,
+        status: StatusCode,
+        body: String,
+    },
+    #[error("{provider} {endpoint} returned an unexpected response: {source}")]
+    Schema {
+        provider: &'static str,
+        endpoint: &'static str,
+        source: serde_json::Error,
+    },
+    #[error("Could not read the {provider} {endpoint} response: {source}")]
+    Body {
+        provider: &'static str,
+        endpoint: &'static str,
+        source: reqwest::Error,
+    },
+    #[error("Invalid {field}: {reason}")]
+    Argument { field: &'static str, reason: String },
+    #[error("Unknown web provider '{0}', expected tavily, searxng, brave or local")]
+    UnknownProvider(String),
+    #[error("The {provider} web provider needs {setting} to be set")]
+    MissingSetting {
+        provider: &'static str,
+        setting: &'static str,
+    },
+    #[error("The {provider} web provider cannot {operation}")]
+    Unsupported {
+        provider: &'static str,
+        operation: &'static str,
+    },
+    #[error("Fetching {url} failed with {status}")]
+    Fetch { url: String, status: StatusCode },
+    #[error("{0} is a loopback, private or link-local address, fetching it needs MARVIN_WEB_ALLOW_PRIVATE_HOSTS=true")]
+    PrivateHost(String),
+    #[error("{url} redirected more than {limit} times")]
+    Redirects { url: String, limit: usize },
+    #[error("Could not read {url}: {source}")]
+    Read { url: String, source: reqwest::Error },
+    #[error("{url} is {content_type}, not a text page")]
+    ContentType { url: String, content_type: String },
+    #[error("{url} is larger than the {limit} byte limit")]
+    TooLarge { url: String, limit: usize },
+    #[error("Could not read the PDF at {url}: {message}")]
+    Pdf { url: String, message: String },
+    #[error("robots.txt disallows fetching {0}")]
+    Robots(String),
+    #[error("Could not access the snapshot at {path}: {source}")]
+    Snapshot {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("The snapshot file {path} is unreadable: {source}")]
+    SnapshotFile {
+        path: String,
+        source: serde_json::Error,
+    },
+    #[error("There are no site snapshots yet, the user can take one with /snapshot <url>")]
+    NoSnapshots,
+    #[error("No snapshot matches '{site}', the snapshots are: {known}")]
+    UnknownSnapshot { site: String, known: String },
+    #[error("Invalid pattern '{pattern}': {source}")]
+    Pattern {
+        pattern: String,
+        source: regex::Error,
+    },
+}
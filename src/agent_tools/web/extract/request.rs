@@ -1,6 +1,16 @@
+use crate::agent_tools::web::{
+    error::WebError,
+    validate::{max_items, not_empty, range, Validate},
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+pub const MAX_URLS: usize = 20;
+const MIN_CHUNKS: u8 = 1;
+const MAX_CHUNKS: u8 = 5;
+pub const MIN_TIMEOUT: f32 = 1.0;
+pub const MAX_TIMEOUT: f32 = 60.0;
+
 #[derive(Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[schemars(description = "Depth of extraction. 'advanced' provides more thorough extraction.")]
@@ -21,7 +31,10 @@ pub enum ExtractFormat {
 
 #[derive(Serialize, Deserialize, JsonSchema)]
 pub struct ExtractArgs {
-    #[schemars(description = "List of URLs to extract content from")]
+    #[schemars(
+        description = "List of URLs to extract content from (max 20)",
+        length(min = 1, max = MAX_URLS)
+    )]
     pub urls: Vec<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,7 +42,10 @@ pub struct ExtractArgs {
     pub query: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Number of content chunks per source (max 500 chars each). Default: 3")]
+    #[schemars(
+        description = "Number of content chunks per source (1-5, max 500 chars each). Default: 3",
+        range(min = MIN_CHUNKS, max = MAX_CHUNKS)
+    )]
     pub chunks_per_source: Option<u8>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -49,10 +65,23 @@ pub struct ExtractArgs {
     pub format: Option<ExtractFormat>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Maximum seconds to wait for extraction (1.0-60.0)")]
+    #[schemars(
+        description = "Maximum seconds to wait for extraction (1.0-60.0)",
+        range(min = MIN_TIMEOUT, max = MAX_TIMEOUT)
+    )]
     pub timeout: Option<f32>,
+}
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Include credit usage information in response")]
-    pub include_usage: Option<bool>,
+impl Validate for ExtractArgs {
+    fn validate(&self) -> Result<(), WebError> {
+        not_empty("urls", &self.urls)?;
+        max_items("urls", Some(&self.urls), MAX_URLS)?;
+        range(
+            "chunks_per_source",
+            self.chunks_per_source,
+            MIN_CHUNKS,
+            MAX_CHUNKS,
+        )?;
+        range("timeout", self.timeout, MIN_TIMEOUT, MAX_TIMEOUT)
+    }
 }
@@ -1,12 +1,14 @@
+use crate::agent_tools::web::budget::{rank_by_terms, Budget, Page};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExtractResponse {
     pub results: Vec<ExtractResult>,
+    #[serde(default)]
     pub failed_results: Vec<FailedResult>,
-    pub response_time: f64,
+    pub response_time: Option<f64>,
     pub usage: Option<Usage>,
-    pub request_id: String,
+    pub request_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,3 +29,40 @@ pub struct FailedResult {
 pub struct Usage {
     pub credits: u32,
 }
+
+#[derive(Debug, Serialize)]
+pub struct ExtractOutput {
+    pub results: Vec<Page>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed_results: Vec<FailedResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub omitted: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<String>,
+}
+
+impl ExtractResponse {
+    pub fn render(self, budget: Budget, query: Option<&str>) -> ExtractOutput {
+        let mut pages: Vec<Page> = self
+            .results
+            .into_iter()
+            .map(|result| Page {
+                source: None,
+                title: None,
+                url: result.url,
+                score: None,
+                content: result.raw_content,
+            })
+            .collect();
+        if let Some(query) = query {
+            rank_by_terms(&mut pages, query);
+        }
+        let (results, omitted) = budget.fit_pages(pages);
+        ExtractOutput {
+            results,
+            failed_results: self.failed_results,
+            omitted: budget.omitted(omitted, "page(s)"),
+            cache: None,
+        }
+    }
+}
@@ -0,0 +1,97 @@
+pub mod request;
+
+use crate::agent_tools::{
+    web::{
+        budget::Budget,
+        error::WebError,
+        extract::response::{ExtractOutput, ExtractResponse, ExtractResult, FailedResult},
+        local::{fetch_timeout, parse_url, LocalFetcher, MAX_CONCURRENT_FETCHES},
+        sources::Sources,
+        validate::Validate,
+    },
+    ToToolResult,
+};
+use futures::{stream, StreamExt};
+use request::FetchArgs;
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::schema_for;
+use std::{sync::Arc, time::Duration};
+
+// fetches pages directly whatever the web provider, so it needs no API key and spends no credits
+pub struct FetchUrl {
+    fetcher: LocalFetcher,
+    sources: Arc<Sources>,
+    token_budget: usize,
+}
+
+impl FetchUrl {
+    pub fn new(fetcher: LocalFetcher, sources: Arc<Sources>, token_budget: usize) -> Self {
+        Self {
+            fetcher,
+            sources,
+            token_budget,
+        }
+    }
+    async fn read(&self, url: &str, timeout: Duration) -> Result<ExtractResult, WebError> {
+        let url = parse_url(url)?;
+        Ok(ExtractResult {
+            raw_content: self.fetcher.read(&url, timeout).await?,
+            url: url.to_string(),
+            images: None,
+            favicon: None,
+        })
+    }
+}
+
+impl Tool for FetchUrl {
+    const NAME: &'static str = "fetch_url";
+    type Args = FetchArgs;
+    type Output = ExtractOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Fetch one or more known URLs directly and return their main content as Markdown, with links listed as numbered references. Handles HTML, plain text, JSON and PDF. Respects robots.txt and costs no search credits, so prefer it over extract_url when the URL is known.".to_string(),
+            parameters: serde_json::to_value(schema_for!(FetchArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        args.validate().to_tool_result()?;
+        let timeout = fetch_timeout(args.timeout);
+        let fetched: Vec<(String, Result<ExtractResult, WebError>)> =
+            stream::iter(args.urls.clone())
+                .map(|url| async move {
+                    let result = self.read(&url, timeout).await;
+                    (url, result)
+                })
+                .buffered(MAX_CONCURRENT_FETCHES)
+                .collect()
+                .await;
+        let mut results = Vec::new();
+        let mut failed_results = Vec::new();
+        for (url, result) in fetched {
+            match result {
+                Ok(result) => results.push(result),
+                Err(e) => failed_results.push(FailedResult {
+                    url,
+                    error: e.to_string(),
+                }),
+            }
+        }
+        let response = ExtractResponse {
+            results,
+            failed_results,
+            response_time: None,
+            usage: None,
+            request_id: None,
+        };
+        let mut output = response.render(Budget::new(self.token_budget), args.query.as_deref());
+        self.sources.cite_pages(&mut output.results);
+        Ok(output)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::agent_tools::web::{
+    error::WebError,
+    extract::request::{MAX_TIMEOUT, MAX_URLS, MIN_TIMEOUT},
+    validate::{max_items, not_empty, range, Validate},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct FetchArgs {
+    #[schemars(
+        description = "List of URLs to fetch (max 20)",
+        length(min = 1, max = MAX_URLS)
+    )]
+    pub urls: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Optional query to rank the fetched pages by relevance")]
+    pub query: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Maximum seconds to wait for each page (1.0-60.0)",
+        range(min = MIN_TIMEOUT, max = MAX_TIMEOUT)
+    )]
+    pub timeout: Option<f32>,
+}
+
+impl Validate for FetchArgs {
+    fn validate(&self) -> Result<(), WebError> {
+        not_empty("urls", &self.urls)?;
+        max_items("urls", Some(&self.urls), MAX_URLS)?;
+        range("timeout", self.timeout, MIN_TIMEOUT, MAX_TIMEOUT)
+    }
+}
@@ -0,0 +1,110 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::{
+    error::Error,
+    net::{IpAddr, SocketAddr},
+};
+use thiserror::Error;
+use url::{Host, Url};
+
+#[derive(Debug, Error)]
+#[error("{0} resolves to a private address")]
+pub struct PrivateAddress(String);
+
+// loopback, private, link-local and other addresses that only mean something on this machine or its network
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            // 100.64.0.0/10, carrier-grade NAT
+            let shared = ip.octets()[0] == 100 && ip.octets()[1] & 0xc0 == 64;
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || shared)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+// hosts written as an address are checked here, names when they are resolved
+pub fn is_private_literal(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Ipv4(ip)) => !is_public(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => !is_public(IpAddr::V6(ip)),
+        _ => false,
+    }
+}
+
+// whether a request failed because its host only resolves to private addresses
+pub fn is_private_address(error: &(dyn Error + 'static)) -> bool {
+    let mut source = Some(error);
+    while let Some(error) = source {
+        if error.is::<PrivateAddress>() {
+            return true;
+        }
+        source = error.source();
+    }
+    false
+}
+
+// drops private addresses from what a name resolves to, after the lookup, so a name that
+// resolves differently the second time can't point a fetch at this machine either
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(PrivateAddress(host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_private_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in ["93.184.216.34", "2606:2800:220:1::", "100.128.0.1"] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+        assert!(is_private_literal(
+            &Url::parse("http://[::1]:8080/").unwrap()
+        ));
+        assert!(!is_private_literal(
+            &Url::parse("https://docs.rs/").unwrap()
+        ));
+    }
+}
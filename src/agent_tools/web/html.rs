@@ -0,0 +1,423 @@
+use scraper::{ElementRef, Html, Node, Selector};
+use url::Url;
+
+// elements that never hold readable text
+const SKIPPED: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "svg", "canvas", "iframe", "object",
+    "select", "button",
+];
+const BLOCKS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "header",
+    "footer",
+    "nav",
+    "aside",
+    "figure",
+    "figcaption",
+    "form",
+    "fieldset",
+    "details",
+    "summary",
+    "dl",
+    "dt",
+    "dd",
+    "address",
+    "table",
+];
+
+#[derive(Debug)]
+pub struct Document {
+    pub title: Option<String>,
+    pub markdown: String,
+    pub links: Vec<Url>,
+}
+
+// how links are written, inline after their text or as numbered references listed at the end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Links {
+    Inline,
+    References,
+}
+
+// http(s) links resolved against the page, without fragments
+pub fn resolve_link(base: &Url, href: &str) -> Option<Url> {
+    let mut url = base.join(href.trim()).ok()?;
+    url.set_fragment(None);
+    matches!(url.scheme(), "http" | "https").then_some(url)
+}
+
+struct Writer<'a> {
+    base: &'a Url,
+    out: String,
+    // line breaks owed before the next text, 2 for a paragraph and 1 for a line
+    pending: usize,
+    prefixes: Vec<&'static str>,
+    pre: bool,
+    links: Vec<Url>,
+    style: Links,
+    skip: fn(ElementRef) -> bool,
+}
+
+impl Writer<'_> {
+    fn prefix(&self) -> String {
+        self.prefixes.concat()
+    }
+    fn brk(&mut self, lines: usize) {
+        if !self.out.is_empty() {
+            self.pending = self.pending.max(lines);
+        }
+    }
+    fn at_line_start(&self) -> bool {
+        self.pending > 0 || self.out.is_empty() || self.out.ends_with('\n')
+    }
+    fn push(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if self.pending > 0 || self.out.is_empty() {
+            if !self.out.is_empty() {
+                // blank lines inside a blockquote keep their marker, but not the one opening it
+                let prefix = self.prefix();
+                let marker = prefix.trim_end();
+                let last_line = self.out.rsplit('\n').next().unwrap_or_default();
+                let blank = if last_line.starts_with(marker) {
+                    format!("\n{}", marker)
+                } else {
+                    "\n".to_string()
+                };
+                for _ in 1..self.pending {
+                    self.out.push_str(&blank);
+                }
+                self.out.push('\n');
+            }
+            self.out.push_str(&self.prefix());
+            self.pending = 0;
+        }
+        self.out.push_str(text);
+    }
+    fn text(&mut self, text: &str) {
+        if self.pre {
+            let continued = format!("\n{}", self.prefix());
+            self.push(&text.replace('\n', &continued));
+            return;
+        }
+        let mut collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.starts_with(char::is_whitespace) && !collapsed.is_empty() {
+            collapsed.insert(0, ' ');
+        }
+        if text.ends_with(char::is_whitespace) && !collapsed.is_empty() {
+            collapsed.push(' ');
+        }
+        if collapsed.trim().is_empty() {
+            if !text.is_empty() && !self.at_line_start() && !self.out.ends_with(' ') {
+                self.out.push(' ');
+            }
+            return;
+        }
+        if self.at_line_start() || self.out.ends_with(' ') {
+            collapsed = collapsed.trim_start().to_string();
+        }
+        self.push(&collapsed);
+    }
+    fn children(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.element(child);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    // wraps inline content in markers, dropping them when the content is empty
+    fn wrapped(&mut self, element: ElementRef, open: &str, close: &str) {
+        let before = self.out.len();
+        let pending = self.pending;
+        self.push(open);
+        let start = self.out.len();
+        self.children(element);
+        if self.out[start..].trim().is_empty() {
+            self.out.truncate(before);
+            self.pending = pending;
+        } else {
+            let trailing = self.out.len() - self.out.trim_end().len();
+            self.out.truncate(self.out.len() - trailing);
+            self.out.push_str(close);
+            if trailing > 0 {
+                self.out.push(' ');
+            }
+        }
+    }
+    fn element(&mut self, element: ElementRef) {
+        let name = element.value().name();
+        if SKIPPED.contains(&name)
+            || element.value().attr("hidden").is_some()
+            || (self.skip)(element)
+        {
+            return;
+        }
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse().unwrap_or(1);
+                self.brk(2);
+                self.push(&format!("{} ", "#".repeat(level)));
+                self.children(element);
+                self.brk(2);
+            }
+            "br" => {
+                if !self.out.is_empty() {
+                    self.pending = self.pending.max(1);
+                }
+            }
+            "hr" => {
+                self.brk(2);
+                self.push("---");
+                self.brk(2);
+            }
+            "pre" => {
+                self.brk(2);
+                self.push("```");
+                self.brk(1);
+                self.pre = true;
+                self.children(element);
+                self.pre = false;
+                self.brk(1);
+                self.push("```");
+                self.brk(2);
+            }
+            "code" if !self.pre => self.wrapped(element, "`", "`"),
+            "strong" | "b" => self.wrapped(element, "**", "**"),
+            "em" | "i" => self.wrapped(element, "*", "*"),
+            "a" => match element
+                .value()
+                .attr("href")
+                .and_then(|href| resolve_link(self.base, href))
+            {
+                Some(url) => {
+                    let number = match self.links.iter().position(|link| *link == url) {
+                        Some(index) => index + 1,
+                        None => {
+                            self.links.push(url.clone());
+                            self.links.len()
+                        }
+                    };
+                    let close = match self.style {
+                        Links::Inline => format!("]({})", url),
+                        Links::References => format!("][{}]", number),
+                    };
+                    self.wrapped(element, "[", &close);
+                }
+                None => self.children(element),
+            },
+            "img" => {
+                let alt = element.value().attr("alt").unwrap_or_default().trim();
+                if let (false, Some(url)) = (
+                    alt.is_empty(),
+                    element
+                        .value()
+                        .attr("src")
+                        .and_then(|src| resolve_link(self.base, src)),
+                ) {
+                    self.push(&format!("![{}]({})", alt, url));
+                }
+            }
+            "blockquote" => {
+                self.brk(2);
+                self.prefixes.push("> ");
+                self.children(element);
+                self.prefixes.pop();
+                self.brk(2);
+            }
+            "ul" | "ol" => {
+                let nested = self.prefixes.last() == Some(&"   ");
+                self.brk(if nested { 1 } else { 2 });
+                let mut number = element
+                    .value()
+                    .attr("start")
+                    .and_then(|start| start.parse().ok())
+                    .unwrap_or(1);
+                for item in element.child_elements() {
+                    if item.value().name() != "li" {
+                        self.element(item);
+                        continue;
+                    }
+                    self.brk(1);
+                    if name == "ol" {
+                        self.push(&format!("{}. ", number));
+                        number += 1;
+                    } else {
+                        self.push("- ");
+                    }
+                    self.prefixes.push("   ");
+                    self.children(item);
+                    self.prefixes.pop();
+                }
+                self.brk(if nested { 1 } else { 2 });
+            }
+            "tr" => {
+                self.brk(1);
+                self.push("|");
+                let cells: Vec<ElementRef> = element
+                    .child_elements()
+                    .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                    .collect();
+                for cell in &cells {
+                    self.push(" ");
+                    self.children(*cell);
+                    let trimmed = self.out.trim_end().len();
+                    self.out.truncate(trimmed);
+                    self.out.push_str(" |");
+                }
+                if !cells.is_empty() && cells.iter().all(|cell| cell.value().name() == "th") {
+                    self.brk(1);
+                    self.push(&format!("|{}", " --- |".repeat(cells.len())));
+                }
+                self.brk(1);
+            }
+            name if BLOCKS.contains(&name) => {
+                self.brk(2);
+                self.children(element);
+                self.brk(2);
+            }
+            "li" => {
+                self.brk(1);
+                self.children(element);
+                self.brk(1);
+            }
+            _ => self.children(element),
+        }
+    }
+}
+
+pub fn title(html: &Html) -> Option<String> {
+    let selector = Selector::parse("title").ok()?;
+    html.select(&selector)
+        .next()
+        .map(|title| title.text().collect::<String>().trim().to_string())
+        .filter(|title| !title.is_empty())
+}
+
+// what relative links resolve against, a page's <base href> if it has one
+pub fn base_url(html: &Html, url: &Url) -> Url {
+    let selector = Selector::parse("base[href]").unwrap();
+    html.select(&selector)
+        .next()
+        .and_then(|base| url.join(base.value().attr("href")?.trim()).ok())
+        .unwrap_or_else(|| url.clone())
+}
+
+// converts an element's content to Markdown, leaving out elements `skip` rejects and collecting the links it contains
+pub fn render(
+    element: ElementRef,
+    base: &Url,
+    style: Links,
+    skip: fn(ElementRef) -> bool,
+) -> (String, Vec<Url>) {
+    let mut writer = Writer {
+        base,
+        out: String::new(),
+        pending: 0,
+        prefixes: Vec::new(),
+        pre: false,
+        links: Vec::new(),
+        style,
+        skip,
+    };
+    writer.element(element);
+    let mut markdown = writer
+        .out
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    if style == Links::References && !writer.links.is_empty() {
+        markdown.push('\n');
+        for (i, link) in writer.links.iter().enumerate() {
+            markdown.push_str(&format!("\n[{}]: {}", i + 1, link));
+        }
+    }
+    (markdown, writer.links)
+}
+
+// plain text of a snippet that may carry markup such as <strong>
+pub fn strip_tags(fragment: &str) -> String {
+    Html::parse_fragment(fragment)
+        .root_element()
+        .text()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn to_markdown(html: &str, url: &Url) -> Document {
+    let html = Html::parse_document(html);
+    let base = base_url(&html, url);
+    let (markdown, links) = render(html.root_element(), &base, Links::Inline, |_| false);
+    Document {
+        title: title(&html),
+        markdown,
+        links,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_markdown() {
+        let base = Url::parse("https://example.com/docs/").unwrap();
+        let document = to_markdown(
+            r#"<html><head><title> Guide </title><style>p { color: red }</style></head>
+            <body>
+              <h1>Getting   started</h1>
+              <p>Install with <code>cargo add</code>, then read the <a href="api.html#top">API <b>docs</b></a>.</p>
+              <ul><li>one</li><li>two<ol><li>nested</li></ol></li></ul>
+              <blockquote><p>quoted</p><p>twice</p></blockquote>
+              <pre>fn main() {
+    println!("hi");
+}</pre>
+              <table><tr><th>Name</th><th>Value</th></tr><tr><td>a</td><td>1</td></tr></table>
+              <script>alert("no")</script>
+            </body></html>"#,
+            &base,
+        );
+        assert_eq!(document.title.as_deref(), Some("Guide"));
+        assert_eq!(
+            document.markdown,
+            "# Getting started\n\n\
+             Install with `cargo add`, then read the [API **docs**](https://example.com/docs/api.html).\n\n\
+             - one\n\
+             - two\n   1. nested\n\n\
+             > quoted\n>\n> twice\n\n\
+             ```\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+             | Name | Value |\n| --- | --- |\n| a | 1 |"
+        );
+        assert_eq!(
+            strip_tags("The <strong>Rust</strong>  book &amp; more"),
+            "The Rust book & more"
+        );
+        assert_eq!(
+            document.links,
+            vec![Url::parse("https://example.com/docs/api.html").unwrap()]
+        );
+        let document = to_markdown(
+            r#"<head><base href="/v2/"></head><body><a href="api.html">API</a></body>"#,
+            &base,
+        );
+        assert_eq!(
+            document.links,
+            vec![Url::parse("https://example.com/v2/api.html").unwrap()]
+        );
+    }
+}
@@ -0,0 +1,551 @@
+use crate::{
+    agent_tools::web::{
+        cache::Cached,
+        crawl::{
+            request::CrawlArgs,
+            response::{CrawlResponse, CrawlResult},
+        },
+        error::WebError,
+        extract::{
+            request::ExtractArgs,
+            response::{ExtractResponse, ExtractResult, FailedResult},
+        },
+        hosts::{self, PublicResolver},
+        html::to_markdown,
+        map::{request::MapArgs, response::MapResponse},
+        provider::{WebResult, WebSearchProvider},
+        readability::readable,
+        robots::RobotsCache,
+        search::{request::SearchArgs, response::SearchResponse},
+    },
+    cassette::{HttpTransport, TransportError},
+    chat::config::Config,
+};
+use futures::{stream, StreamExt};
+use regex::Regex;
+use reqwest::{
+    header::{ACCEPT, CONTENT_TYPE, LOCATION, USER_AGENT},
+    redirect::Policy,
+    Client, Response,
+};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use url::Url;
+
+pub const AGENT: &str = concat!("marvin/", env!("CARGO_PKG_VERSION"));
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(20);
+const MAX_PAGE_BYTES: usize = 2 * 1024 * 1024;
+const MAX_PDF_BYTES: usize = 16 * 1024 * 1024;
+pub const MAX_CONCURRENT_FETCHES: usize = 4;
+const DEFAULT_MAX_DEPTH: u8 = 1;
+const DEFAULT_MAX_BREADTH: u16 = 20;
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_REDIRECTS: usize = 10;
+
+#[derive(Debug)]
+pub struct Download {
+    // where the page was found after redirects, what its relative links resolve against
+    pub url: Url,
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+    pub truncated: bool,
+}
+
+impl Download {
+    fn is_html(&self) -> bool {
+        self.content_type.contains("html")
+    }
+    fn is_pdf(&self) -> bool {
+        self.content_type.contains("application/pdf")
+    }
+    fn is_text(&self) -> bool {
+        self.content_type.starts_with("text/")
+            || self.content_type.contains("json")
+            || self.content_type.contains("xml")
+    }
+}
+
+#[derive(Debug)]
+pub struct FetchedPage {
+    pub url: Url,
+    pub content: String,
+    pub links: Vec<Url>,
+}
+
+// fetches pages directly and converts HTML to Markdown, no search engine involved
+#[derive(Clone)]
+pub struct LocalFetcher {
+    transport: HttpTransport,
+    // shared by every clone, so each site's robots.txt is fetched once per session
+    robots: Arc<RobotsCache>,
+    // whether localhost and the local network may be fetched, off unless configured
+    allow_private_hosts: bool,
+}
+
+// bare hosts like docs.rs are taken to mean https
+pub fn parse_url(url: &str) -> Result<Url, WebError> {
+    let url = url.trim();
+    if url.contains("://") {
+        Ok(Url::parse(url)?)
+    } else {
+        Ok(Url::parse(&format!("https://{}", url))?)
+    }
+}
+
+// pages whose body has no heading of its own are titled from <title>
+fn titled(title: Option<String>, markdown: String) -> String {
+    match title {
+        Some(title) if !markdown.starts_with('#') => format!("# {}\n\n{}", title, markdown),
+        _ => markdown,
+    }
+}
+
+// pdf parsing is CPU bound and can panic on malformed files, so it runs on the blocking pool
+async fn pdf_text(url: &Url, bytes: Vec<u8>) -> Result<String, WebError> {
+    let text = tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes))
+        .await
+        .map_err(|e| WebError::Pdf {
+            url: url.to_string(),
+            message: e.to_string(),
+        })?
+        .map_err(|e| WebError::Pdf {
+            url: url.to_string(),
+            message: e.to_string(),
+        })?;
+    Ok(text.trim().to_string())
+}
+
+async fn read_body(
+    url: &Url,
+    mut response: Response,
+    max_bytes: usize,
+) -> Result<Download, WebError> {
+    let status = response.status();
+    if !status.is_success() {
+        return Err(WebError::Fetch {
+            url: url.to_string(),
+            status,
+        });
+    }
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("text/html")
+        .to_lowercase();
+    let mut bytes = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk().await.map_err(|source| WebError::Read {
+        url: url.to_string(),
+        source,
+    })? {
+        if bytes.len() + chunk.len() > max_bytes {
+            bytes.extend_from_slice(&chunk[..max_bytes - bytes.len()]);
+            truncated = true;
+            break;
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(Download {
+        url: response.url().clone(),
+        content_type,
+        bytes,
+        truncated,
+    })
+}
+
+pub fn fetch_timeout(secs: Option<f32>) -> Duration {
+    secs.and_then(|secs| Duration::try_from_secs_f32(secs).ok())
+        .filter(|timeout| !timeout.is_zero())
+        .unwrap_or(DEFAULT_TIMEOUT)
+}
+
+fn patterns(patterns: Option<&Vec<String>>) -> Result<Vec<Regex>, WebError> {
+    patterns
+        .into_iter()
+        .flatten()
+        .map(|pattern| {
+            Regex::new(pattern).map_err(|source| WebError::Pattern {
+                pattern: pattern.clone(),
+                source,
+            })
+        })
+        .collect()
+}
+
+// which links a crawl or map follows, shared by both since their arguments match
+struct Scope {
+    start: Url,
+    max_depth: u8,
+    max_breadth: usize,
+    limit: usize,
+    select_paths: Vec<Regex>,
+    exclude_paths: Vec<Regex>,
+    select_domains: Vec<Regex>,
+    exclude_domains: Vec<Regex>,
+    allow_external: bool,
+    timeout: Duration,
+}
+
+impl Scope {
+    fn allows(&self, url: &Url) -> bool {
+        let host = url.host_str().unwrap_or_default();
+        let path = url.path();
+        (self.allow_external || url.host_str() == self.start.host_str())
+            && (self.select_domains.is_empty()
+                || self.select_domains.iter().any(|re| re.is_match(host)))
+            && !self.exclude_domains.iter().any(|re| re.is_match(host))
+            && (self.select_paths.is_empty()
+                || self.select_paths.iter().any(|re| re.is_match(path)))
+            && !self.exclude_paths.iter().any(|re| re.is_match(path))
+    }
+}
+
+impl TryFrom<&CrawlArgs> for Scope {
+    type Error = WebError;
+
+    fn try_from(args: &CrawlArgs) -> Result<Self, Self::Error> {
+        Ok(Self {
+            start: parse_url(&args.url)?,
+            max_depth: args.max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+            max_breadth: args.max_breadth.unwrap_or(DEFAULT_MAX_BREADTH).into(),
+            limit: args.limit.unwrap_or(DEFAULT_LIMIT) as usize,
+            select_paths: patterns(args.select_paths.as_ref())?,
+            exclude_paths: patterns(args.exclude_paths.as_ref())?,
+            select_domains: patterns(args.select_domains.as_ref())?,
+            exclude_domains: patterns(args.exclude_domains.as_ref())?,
+            allow_external: args.allow_external.unwrap_or(false),
+            timeout: fetch_timeout(args.timeout),
+        })
+    }
+}
+
+impl TryFrom<&MapArgs> for Scope {
+    type Error = WebError;
+
+    fn try_from(args: &MapArgs) -> Result<Self, Self::Error> {
+        Ok(Self {
+            start: parse_url(&args.url)?,
+            max_depth: args.max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+            max_breadth: args.max_breadth.unwrap_or(DEFAULT_MAX_BREADTH).into(),
+            limit: args.limit.unwrap_or(DEFAULT_LIMIT) as usize,
+            select_paths: patterns(args.select_paths.as_ref())?,
+            exclude_paths: patterns(args.exclude_paths.as_ref())?,
+            select_domains: patterns(args.select_domains.as_ref())?,
+            exclude_domains: patterns(args.exclude_domains.as_ref())?,
+            allow_external: args.allow_external.unwrap_or(false),
+            timeout: fetch_timeout(args.timeout),
+        })
+    }
+}
+
+impl LocalFetcher {
+    pub fn new(
+        transport: HttpTransport,
+        robots: Arc<RobotsCache>,
+        allow_private_hosts: bool,
+    ) -> Self {
+        // redirects are followed in `get`, names are only resolved to public addresses
+        let mut client = Client::builder().redirect(Policy::none());
+        if !allow_private_hosts {
+            client = client.dns_resolver(Arc::new(PublicResolver));
+        }
+        let client = client
+            .build()
+            .expect("the default TLS backend is available");
+        Self {
+            transport: transport.with_client(client),
+            robots,
+            allow_private_hosts,
+        }
+    }
+    async fn send(&self, url: &Url, timeout: Duration) -> Result<Response, WebError> {
+        let request = self
+            .transport
+            .client()
+            .get(url.clone())
+            .header(USER_AGENT, AGENT)
+            .header(
+                ACCEPT,
+                "text/html,application/xhtml+xml,text/plain;q=0.9,*/*;q=0.5",
+            )
+            .timeout(timeout)
+            .build()
+            .map_err(TransportError::from)?;
+        match self.transport.execute(request).await {
+            Ok(response) => Ok(response),
+            Err(TransportError::Http(e)) if hosts::is_private_address(&e) => Err(
+                WebError::PrivateHost(url.host_str().unwrap_or_default().to_string()),
+            ),
+            Err(e) => Err(e.into()),
+        }
+    }
+    // follows redirects itself, so with `robots` every URL is checked before it is requested
+    async fn get(
+        &self,
+        url: &Url,
+        timeout: Duration,
+        robots: Option<&RobotsCache>,
+    ) -> Result<Response, WebError> {
+        let mut next = url.clone();
+        for _ in 0..=MAX_REDIRECTS {
+            if !self.allow_private_hosts && hosts::is_private_literal(&next) {
+                return Err(WebError::PrivateHost(
+                    next.host_str().unwrap_or_default().to_string(),
+                ));
+            }
+            // boxed, fetching robots.txt comes back through here without a cache
+            if let Some(robots) = robots {
+                Box::pin(robots.check(self, &next)).await?;
+            }
+            let response = self.send(&next, timeout).await?;
+            let status = response.status();
+            if !status.is_redirection() {
+                return Ok(response);
+            }
+            next = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .and_then(|location| next.join(location).ok())
+                .ok_or_else(|| WebError::Fetch {
+                    url: next.to_string(),
+                    status,
+                })?;
+        }
+        Err(WebError::Redirects {
+            url: url.to_string(),
+            limit: MAX_REDIRECTS,
+        })
+    }
+    // downloads at most `max_bytes` of a page, saying whether the rest was cut off
+    pub async fn download(
+        &self,
+        url: &Url,
+        timeout: Duration,
+        max_bytes: usize,
+    ) -> Result<Download, WebError> {
+        let response = self.get(url, timeout, None).await?;
+        read_body(url, response, max_bytes).await
+    }
+    // like `download`, but only for pages robots.txt allows, wherever redirects lead
+    async fn download_page(
+        &self,
+        url: &Url,
+        timeout: Duration,
+        max_bytes: usize,
+    ) -> Result<Download, WebError> {
+        let response = self.get(url, timeout, Some(&self.robots)).await?;
+        read_body(url, response, max_bytes).await
+    }
+    pub async fn fetch(&self, url: &Url, timeout: Duration) -> Result<FetchedPage, WebError> {
+        let download = self.download_page(url, timeout, MAX_PAGE_BYTES).await?;
+        let text = String::from_utf8_lossy(&download.bytes).into_owned();
+        if download.is_html() {
+            let document = to_markdown(&text, &download.url);
+            Ok(FetchedPage {
+                url: download.url,
+                content: titled(document.title, document.markdown),
+                links: document.links,
+            })
+        } else if download.is_text() {
+            Ok(FetchedPage {
+                url: download.url,
+                content: text,
+                links: Vec::new(),
+            })
+        } else {
+            Err(WebError::ContentType {
+                url: url.to_string(),
+                content_type: download.content_type,
+            })
+        }
+    }
+    // a page reduced to its main content for reading, with links as numbered references.
+    // PDFs have their text extracted and JSON is pretty printed
+    pub async fn read(&self, url: &Url, timeout: Duration) -> Result<String, WebError> {
+        let pdf = url.path().to_lowercase().ends_with(".pdf");
+        let max_bytes = if pdf { MAX_PDF_BYTES } else { MAX_PAGE_BYTES };
+        let download = self.download_page(url, timeout, max_bytes).await?;
+        if download.is_pdf() || (pdf && download.content_type.contains("octet-stream")) {
+            if download.truncated {
+                return Err(WebError::TooLarge {
+                    url: url.to_string(),
+                    limit: MAX_PDF_BYTES,
+                });
+            }
+            return pdf_text(url, download.bytes).await;
+        }
+        let text = String::from_utf8_lossy(&download.bytes).into_owned();
+        let mut content = if download.is_html() {
+            let document = readable(&text, &download.url);
+            titled(document.title, document.markdown)
+        } else if download.content_type.contains("json") {
+            serde_json::from_str::<serde_json::Value>(&text)
+                .ok()
+                .and_then(|json| serde_json::to_string_pretty(&json).ok())
+                .unwrap_or(text)
+        } else if download.is_text() {
+            text
+        } else {
+            return Err(WebError::ContentType {
+                url: url.to_string(),
+                content_type: download.content_type,
+            });
+        };
+        if download.truncated {
+            content.push_str(&format!(
+                "\n\n[Truncated, only the first {} KiB were downloaded]",
+                MAX_PAGE_BYTES / 1024
+            ));
+        }
+        Ok(content)
+    }
+    // breadth first from the start page; pages at the last level are only fetched when their content is wanted
+    async fn walk(
+        &self,
+        scope: &Scope,
+        fetch_leaves: bool,
+    ) -> Result<(Vec<FetchedPage>, Vec<Url>), WebError> {
+        let mut seen = HashSet::from([scope.start.clone()]);
+        let mut discovered = vec![scope.start.clone()];
+        let mut level = vec![scope.start.clone()];
+        let mut pages = Vec::new();
+        for depth in 0..=scope.max_depth {
+            if level.is_empty() || (depth == scope.max_depth && !fetch_leaves) {
+                break;
+            }
+            level.truncate(scope.limit.saturating_sub(pages.len()));
+            let fetched: Vec<Result<FetchedPage, WebError>> = stream::iter(level)
+                .map(|url| async move { self.fetch(&url, scope.timeout).await })
+                .buffered(MAX_CONCURRENT_FETCHES)
+                .collect()
+                .await;
+            let mut next = Vec::new();
+            for result in fetched {
+                let page = match result {
+                    Ok(page) => page,
+                    Err(e) if depth == 0 => return Err(e),
+                    Err(_) => continue,
+                };
+                // a page reached through a redirect isn't fetched again under its own URL
+                seen.insert(page.url.clone());
+                if depth < scope.max_depth {
+                    let mut followed = 0;
+                    for link in &page.links {
+                        if followed == scope.max_breadth {
+                            break;
+                        }
+                        if scope.allows(link) && seen.insert(link.clone()) {
+                            followed += 1;
+                            next.push(link.clone());
+                            discovered.push(link.clone());
+                        }
+                    }
+                }
+                pages.push(page);
+            }
+            level = next;
+        }
+        Ok((pages, discovered))
+    }
+    async fn extract_pages(&self, args: &ExtractArgs) -> Result<Cached<ExtractResponse>, WebError> {
+        let timeout = fetch_timeout(args.timeout);
+        let fetched: Vec<(String, Result<FetchedPage, WebError>)> = stream::iter(args.urls.clone())
+            .map(|url| async move {
+                let page = match parse_url(&url) {
+                    Ok(parsed) => self.fetch(&parsed, timeout).await,
+                    Err(e) => Err(e),
+                };
+                (url, page)
+            })
+            .buffered(MAX_CONCURRENT_FETCHES)
+            .collect()
+            .await;
+        let mut results = Vec::new();
+        let mut failed_results = Vec::new();
+        for (url, page) in fetched {
+            match page {
+                Ok(page) => results.push(ExtractResult {
+                    url: page.url.to_string(),
+                    raw_content: page.content,
+                    images: None,
+                    favicon: None,
+                }),
+                Err(e) => failed_results.push(FailedResult {
+                    url,
+                    error: e.to_string(),
+                }),
+            }
+        }
+        Ok(Cached::fresh(ExtractResponse {
+            results,
+            failed_results,
+            response_time: None,
+            usage: None,
+            request_id: None,
+        }))
+    }
+    async fn crawl_site(&self, args: &CrawlArgs) -> Result<Cached<CrawlResponse>, WebError> {
+        let scope = Scope::try_from(args)?;
+        let (pages, _) = self.walk(&scope, true).await?;
+        Ok(Cached::fresh(CrawlResponse {
+            base_url: scope.start.to_string(),
+            results: pages
+                .into_iter()
+                .map(|page| CrawlResult {
+                    url: page.url.to_string(),
+                    raw_content: page.content,
+                    favicon: None,
+                })
+                .collect(),
+            response_time: None,
+            usage: None,
+            request_id: None,
+        }))
+    }
+    async fn map_site(&self, args: &MapArgs) -> Result<Cached<MapResponse>, WebError> {
+        let scope = Scope::try_from(args)?;
+        let (_, mut discovered) = self.walk(&scope, false).await?;
+        discovered.truncate(scope.limit);
+        Ok(Cached::fresh(MapResponse {
+            base_url: scope.start.to_string(),
+            results: discovered.iter().map(Url::to_string).collect(),
+            response_time: None,
+            usage: None,
+            request_id: None,
+        }))
+    }
+}
+
+impl WebSearchProvider for LocalFetcher {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+    fn search<'a>(&'a self, _args: &'a SearchArgs) -> WebResult<'a, SearchResponse> {
+        Box::pin(async {
+            Err(WebError::Unsupported {
+                provider: "local",
+                operation: "search the web, set MARVIN_WEB_PROVIDER to tavily, searxng or brave",
+            })
+        })
+    }
+    fn extract<'a>(&'a self, args: &'a ExtractArgs) -> WebResult<'a, ExtractResponse> {
+        Box::pin(self.extract_pages(args))
+    }
+    fn crawl<'a>(&'a self, args: &'a CrawlArgs) -> WebResult<'a, CrawlResponse> {
+        Box::pin(self.crawl_site(args))
+    }
+    fn map<'a>(&'a self, args: &'a MapArgs) -> WebResult<'a, MapResponse> {
+        Box::pin(self.map_site(args))
+    }
+}
+
+impl From<&Config> for LocalFetcher {
+    fn from(value: &Config) -> Self {
+        LocalFetcher::new(
+            value.transport().clone(),
+            value.robots().clone(),
+            value.allow_private_hosts(),
+        )
+    }
+}
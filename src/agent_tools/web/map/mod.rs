@@ -1,37 +1,37 @@
 pub mod request;
-#[allow(dead_code)]
 pub mod response;
 
-use crate::agent_tools::{SomeError, ToToolError, ToToolResult};
-
-use super::tavily::{TavilyClient, BASE_URL};
+use crate::agent_tools::{
+    web::{budget::Budget, provider::WebSearchProvider, validate::Validate},
+    ToToolResult,
+};
 use request::MapArgs;
-use reqwest::StatusCode;
+use response::MapOutput;
 use rig::{
     completion::ToolDefinition,
     tool::{Tool, ToolError},
 };
 use schemars::schema_for;
-use serde_json::Value;
 use std::sync::Arc;
-use url::Url;
-
-const MAP_PATH: &str = "/map";
 
 pub struct Map {
-    client: Arc<TavilyClient>,
+    provider: Arc<dyn WebSearchProvider>,
+    token_budget: usize,
 }
 
 impl Map {
-    pub fn new(client: Arc<TavilyClient>) -> Self {
-        Self { client }
+    pub fn new(provider: Arc<dyn WebSearchProvider>, token_budget: usize) -> Self {
+        Self {
+            provider,
+            token_budget,
+        }
     }
 }
 
 impl Tool for Map {
     const NAME: &'static str = "map_website";
     type Args = MapArgs;
-    type Output = Value;
+    type Output = MapOutput;
     type Error = ToolError;
 
     async fn definition(&self, _prompt: String) -> ToolDefinition {
@@ -43,19 +43,18 @@ impl Tool for Map {
     }
 
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
-        let url = Url::parse(BASE_URL)
-            .to_tool_result()?
-            .join(MAP_PATH)
-            .to_tool_result()?;
-        let json = serde_json::to_value(args).to_tool_result()?;
-        let response = self.client.post(url, json).await.to_tool_result()?;
-        let status = response.status();
-        let body = response.json::<Value>().await.to_tool_result()?;
-        match status {
-            StatusCode::OK => Ok(body),
-            status => {
-                Err(SomeError(format!("Crawl failed with {}: {:?}", status, body)).to_tool_err())
-            }
-        }
+        args.validate().to_tool_result()?;
+        let budget = Budget::new(self.token_budget);
+        self.provider
+            .map(&args)
+            .await
+            .map(|cached| {
+                let cache = cached.note();
+                MapOutput {
+                    cache,
+                    ..cached.value.render(budget)
+                }
+            })
+            .to_tool_result()
     }
 }
@@ -1,3 +1,10 @@
+use crate::agent_tools::web::{
+    crawl::request::{
+        MAX_BREADTH, MAX_DEPTH, MAX_TIMEOUT, MIN_BREADTH, MIN_DEPTH, MIN_LIMIT, MIN_TIMEOUT,
+    },
+    error::WebError,
+    validate::{at_least, range, Validate},
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -14,16 +21,23 @@ pub struct MapArgs {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[schemars(
-        description = "Max depth of mapping - how far from base URL crawler can explore. Default: 1"
+        description = "Max depth of mapping - how far from base URL crawler can explore (1-5). Default: 1",
+        range(min = MIN_DEPTH, max = MAX_DEPTH)
     )]
     pub max_depth: Option<u8>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Max links to follow per page level. Default: 20")]
+    #[schemars(
+        description = "Max links to follow per page level (1-500). Default: 20",
+        range(min = MIN_BREADTH, max = MAX_BREADTH)
+    )]
     pub max_breadth: Option<u16>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Total links the crawler will process before stopping. Default: 50")]
+    #[schemars(
+        description = "Total links the crawler will process before stopping (at least 1). Default: 50",
+        range(min = MIN_LIMIT)
+    )]
     pub limit: Option<u32>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -47,10 +61,18 @@ pub struct MapArgs {
     pub allow_external: Option<bool>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Timeout in seconds for the map operation (10-150). Default: 150")]
+    #[schemars(
+        description = "Timeout in seconds for the map operation (10-150). Default: 150",
+        range(min = MIN_TIMEOUT, max = MAX_TIMEOUT)
+    )]
     pub timeout: Option<f32>,
+}
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Include credit usage information in response")]
-    pub include_usage: Option<bool>,
+impl Validate for MapArgs {
+    fn validate(&self) -> Result<(), WebError> {
+        range("max_depth", self.max_depth, MIN_DEPTH, MAX_DEPTH)?;
+        range("max_breadth", self.max_breadth, MIN_BREADTH, MAX_BREADTH)?;
+        at_least("limit", self.limit, MIN_LIMIT)?;
+        range("timeout", self.timeout, MIN_TIMEOUT, MAX_TIMEOUT)
+    }
 }
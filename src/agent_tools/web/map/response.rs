@@ -1,15 +1,38 @@
+use crate::agent_tools::web::budget::Budget;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MapResponse {
     pub base_url: String,
     pub results: Vec<String>,
-    pub response_time: f64,
+    pub response_time: Option<f64>,
     pub usage: Option<Usage>,
-    pub request_id: String,
+    pub request_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Usage {
     pub credits: u32,
 }
+
+#[derive(Debug, Serialize)]
+pub struct MapOutput {
+    pub base_url: String,
+    pub urls: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub omitted: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<String>,
+}
+
+impl MapResponse {
+    pub fn render(self, budget: Budget) -> MapOutput {
+        let (urls, omitted) = budget.fit_urls(self.results);
+        MapOutput {
+            base_url: self.base_url,
+            urls,
+            omitted: budget.omitted(omitted, "URL(s)"),
+            cache: None,
+        }
+    }
+}
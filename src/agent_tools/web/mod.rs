@@ -1,28 +1,55 @@
 use crawl::Crawl;
 use extract::Extract;
+use fetch::FetchUrl;
+use local::LocalFetcher;
 use map::Map;
+use provider::WebSearchProvider;
 use rig::tool::ToolDyn;
 use search::Search;
+use snapshot::{store::SnapshotStore, SearchSnapshots};
+use sources::Sources;
 use std::sync::Arc;
-use tavily::TavilyClient;
 
+pub mod brave;
+pub mod budget;
+pub mod cache;
 pub mod crawl;
+pub mod credits;
+pub mod error;
 pub mod extract;
+pub mod fetch;
+pub mod hosts;
+pub mod html;
+pub mod local;
 pub mod map;
+pub mod provider;
+pub mod rate_limit;
+pub mod readability;
+pub mod robots;
 pub mod search;
+pub mod searxng;
+pub mod snapshot;
+pub mod sources;
 pub mod tavily;
+pub mod validate;
 
-pub trait WebTools {
-    fn web_tools(&self) -> Vec<Box<dyn ToolDyn>>;
-}
-
-impl WebTools for Arc<TavilyClient> {
-    fn web_tools(&self) -> Vec<Box<dyn ToolDyn>> {
-        vec![
-            Box::new(Search::new(self.clone())),
-            Box::new(Extract::new(self.clone())),
-            Box::new(Crawl::new(self.clone())),
-            Box::new(Map::new(self.clone())),
-        ]
-    }
+pub fn web_tools(
+    provider: Arc<dyn WebSearchProvider>,
+    fetcher: LocalFetcher,
+    snapshots: SnapshotStore,
+    sources: Arc<Sources>,
+    token_budget: usize,
+) -> Vec<Box<dyn ToolDyn>> {
+    vec![
+        Box::new(Search::new(provider.clone(), sources.clone(), token_budget)),
+        Box::new(Extract::new(
+            provider.clone(),
+            sources.clone(),
+            token_budget,
+        )),
+        Box::new(Crawl::new(provider.clone(), sources.clone(), token_budget)),
+        Box::new(Map::new(provider, token_budget)),
+        Box::new(FetchUrl::new(fetcher, sources.clone(), token_budget)),
+        Box::new(SearchSnapshots::new(snapshots, sources, token_budget)),
+    ]
 }
@@ -0,0 +1,233 @@
+use crate::{
+    agent_tools::web::{
+        brave::BraveClient,
+        cache::{Cached, ResponseCache},
+        crawl::{request::CrawlArgs, response::CrawlResponse},
+        credits::{AccountUsage, CreditLedger},
+        error::WebError,
+        extract::{request::ExtractArgs, response::ExtractResponse},
+        local::LocalFetcher,
+        map::{request::MapArgs, response::MapResponse},
+        rate_limit::{RateLimit, RateLimiter},
+        search::{request::SearchArgs, response::SearchResponse},
+        searxng::SearxngClient,
+        tavily::TavilyClient,
+    },
+    cassette::{HttpTransport, TransportError},
+    chat::config::Config,
+};
+use futures::future::BoxFuture;
+use reqwest::{RequestBuilder, StatusCode};
+use serde_json::Value;
+use std::{fmt, str::FromStr, sync::Arc};
+
+pub type WebResult<'a, T> = BoxFuture<'a, Result<Cached<T>, WebError>>;
+
+// a backend for the web tools, responses share Tavily's shapes so every backend renders and budgets alike
+pub trait WebSearchProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn search<'a>(&'a self, args: &'a SearchArgs) -> WebResult<'a, SearchResponse>;
+    fn extract<'a>(&'a self, args: &'a ExtractArgs) -> WebResult<'a, ExtractResponse>;
+    fn crawl<'a>(&'a self, args: &'a CrawlArgs) -> WebResult<'a, CrawlResponse>;
+    fn map<'a>(&'a self, args: &'a MapArgs) -> WebResult<'a, MapResponse>;
+    fn cache(&self) -> Option<&ResponseCache> {
+        None
+    }
+    fn credits(&self) -> Option<&CreditLedger> {
+        None
+    }
+    fn account_usage(&self) -> BoxFuture<'_, Result<AccountUsage, WebError>> {
+        let provider = self.name();
+        Box::pin(async move {
+            Err(WebError::Unsupported {
+                provider,
+                operation: "report account usage",
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProviderKind {
+    #[default]
+    Tavily,
+    Searxng,
+    Brave,
+    Local,
+}
+
+impl FromStr for ProviderKind {
+    type Err = WebError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "tavily" => Ok(Self::Tavily),
+            "searxng" => Ok(Self::Searxng),
+            "brave" => Ok(Self::Brave),
+            "local" => Ok(Self::Local),
+            other => Err(WebError::UnknownProvider(other.to_string())),
+        }
+    }
+}
+
+impl ProviderKind {
+    // what each API allows on its entry-level plan, Brave's free plan starts one request a second
+    pub fn rate_limit(self) -> RateLimit {
+        match self {
+            Self::Tavily => RateLimit {
+                max_concurrent: 4,
+                per_second: None,
+            },
+            Self::Searxng => RateLimit {
+                max_concurrent: 2,
+                per_second: None,
+            },
+            Self::Brave => RateLimit {
+                max_concurrent: 1,
+                per_second: Some(1.0),
+            },
+            Self::Local => RateLimit::UNLIMITED,
+        }
+    }
+}
+
+impl fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Tavily => "tavily",
+            Self::Searxng => "searxng",
+            Self::Brave => "brave",
+            Self::Local => "local",
+        })
+    }
+}
+
+pub fn web_provider(config: &Config) -> Result<Arc<dyn WebSearchProvider>, WebError> {
+    Ok(match config.web_provider() {
+        ProviderKind::Tavily => Arc::new(TavilyClient::from(config)),
+        ProviderKind::Searxng => {
+            let url = config.searxng_url().ok_or(WebError::MissingSetting {
+                provider: "searxng",
+                setting: "SEARXNG_URL",
+            })?;
+            Arc::new(
+                SearxngClient::new(config.transport().clone(), url, LocalFetcher::from(config))
+                    .with_rate_limit(config.web_rate_limit()),
+            )
+        }
+        ProviderKind::Brave => {
+            let api_key = config.brave_api_key().ok_or(WebError::MissingSetting {
+                provider: "brave",
+                setting: "BRAVE_API_KEY",
+            })?;
+            Arc::new(
+                BraveClient::new(
+                    config.transport().clone(),
+                    api_key,
+                    config.brave_base_url(),
+                    LocalFetcher::from(config),
+                )
+                .with_rate_limit(config.web_rate_limit()),
+            )
+        }
+        ProviderKind::Local => Arc::new(LocalFetcher::from(config)),
+    })
+}
+
+// sends a request to a search API and returns the JSON body of a successful response
+pub async fn send(
+    transport: &HttpTransport,
+    limiter: &RateLimiter,
+    provider: &'static str,
+    endpoint: &'static str,
+    request: RequestBuilder,
+) -> Result<Value, WebError> {
+    let request = request.build().map_err(TransportError::from)?;
+    let _permit = limiter.acquire().await;
+    let response = transport.execute(request).await?;
+    let status = response.status();
+    let body = response.text().await.map_err(|source| WebError::Body {
+        provider,
+        endpoint,
+        source,
+    })?;
+    if status != StatusCode::OK {
+        return Err(WebError::Status {
+            provider,
+            endpoint,
+            status,
+            body,
+        });
+    }
+    serde_json::from_str(&body).map_err(|source| WebError::Schema {
+        provider,
+        endpoint,
+        source,
+    })
+}
+
+// keeps results whose host is, or is a subdomain of, an included domain and none of the excluded ones
+pub fn filter_domains<T>(
+    results: Vec<T>,
+    url: impl Fn(&T) -> &str,
+    include: Option<&[String]>,
+    exclude: Option<&[String]>,
+) -> Vec<T> {
+    let matches = |host: &str, domains: &[String]| {
+        domains.iter().any(|domain| {
+            let domain = domain.trim().trim_start_matches("www.");
+            host == domain || host.ends_with(&format!(".{}", domain))
+        })
+    };
+    results
+        .into_iter()
+        .filter(|result| {
+            let host = url::Url::parse(url(result))
+                .ok()
+                .and_then(|url| {
+                    url.host_str()
+                        .map(|host| host.trim_start_matches("www.").to_string())
+                })
+                .unwrap_or_default();
+            include.is_none_or(|domains| domains.is_empty() || matches(&host, domains))
+                && exclude.is_none_or(|domains| !matches(&host, domains))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_domains() {
+        let urls = vec![
+            "https://www.rust-lang.org/learn",
+            "https://blog.rust-lang.org/2024",
+            "https://example.com",
+            "https://notrust-lang.org",
+        ];
+        let include = vec!["rust-lang.org".to_string()];
+        let exclude = vec!["blog.rust-lang.org".to_string()];
+        assert_eq!(
+            filter_domains(urls.clone(), |url| url, Some(&include), None),
+            &urls[..2]
+        );
+        assert_eq!(
+            filter_domains(urls.clone(), |url| url, Some(&include), Some(&exclude)),
+            &urls[..1]
+        );
+        assert_eq!(
+            filter_domains(urls.clone(), |url| url, None, Some(&exclude)).len(),
+            3
+        );
+        assert!(matches!(
+            "duckduckgo".parse::<ProviderKind>(),
+            Err(WebError::UnknownProvider(_))
+        ));
+        assert_eq!(
+            "SearXNG".parse::<ProviderKind>().unwrap(),
+            ProviderKind::Searxng
+        );
+    }
+}
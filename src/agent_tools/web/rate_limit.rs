@@ -0,0 +1,91 @@
+use std::{sync::Mutex, time::Duration};
+use tokio::{
+    sync::{Semaphore, SemaphorePermit},
+    time::Instant,
+};
+
+// how hard a provider's API may be hit, requests in flight at once and requests started per second
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub max_concurrent: usize,
+    pub per_second: Option<f64>,
+}
+
+impl RateLimit {
+    pub const UNLIMITED: Self = Self {
+        max_concurrent: Semaphore::MAX_PERMITS,
+        per_second: None,
+    };
+}
+
+// paces the requests a client sends, shared by every tool call running at the same time
+#[derive(Debug)]
+pub struct RateLimiter {
+    slots: Semaphore,
+    interval: Duration,
+    // when the next request may start
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        let interval = limit
+            .per_second
+            .filter(|per_second| *per_second > 0.0)
+            .map_or(Duration::ZERO, |per_second| {
+                Duration::from_secs_f64(1.0 / per_second)
+            });
+        Self {
+            slots: Semaphore::new(limit.max_concurrent.clamp(1, Semaphore::MAX_PERMITS)),
+            interval,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+    // waits for a free slot and then for the request's turn, the request holds the permit while it runs
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        let permit = self
+            .slots
+            .acquire()
+            .await
+            .expect("the semaphore is never closed");
+        let start = {
+            let mut next = self.next.lock().unwrap();
+            let start = (*next).max(Instant::now());
+            *next = start + self.interval;
+            start
+        };
+        tokio::time::sleep_until(start).await;
+        permit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requests_are_spaced_and_capped() {
+        let limiter = RateLimiter::new(RateLimit {
+            max_concurrent: 2,
+            per_second: Some(20.0),
+        });
+        let started = Instant::now();
+        let first = limiter.acquire().await;
+        let second = limiter.acquire().await;
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        // both slots are taken until a request finishes
+        assert!(
+            tokio::time::timeout(Duration::from_millis(150), limiter.acquire())
+                .await
+                .is_err()
+        );
+        drop(first);
+        let _third = limiter.acquire().await;
+        drop(second);
+
+        let unlimited = RateLimiter::new(RateLimit::UNLIMITED);
+        let started = Instant::now();
+        let _held: Vec<_> = futures::future::join_all((0..10).map(|_| unlimited.acquire())).await;
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+}
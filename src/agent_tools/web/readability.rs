@@ -0,0 +1,159 @@
+use crate::agent_tools::web::html::{base_url, render, title, Document, Links};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use std::{collections::HashMap, sync::LazyLock};
+use url::Url;
+
+// containers a page marks as its main content, used when there is exactly one of them
+const MAIN_SELECTORS: &[&str] = &["article", "main", "[role=main]"];
+const MIN_MAIN_CHARS: usize = 200;
+// blocks whose text scores their parent and grandparent as the content container
+const SCORED_BLOCKS: &str = "p, pre, td, blockquote";
+const MIN_BLOCK_CHARS: usize = 25;
+const BOILERPLATE_TAGS: &[&str] = &["nav", "aside", "footer", "form", "dialog", "menu"];
+const NEVER_BOILERPLATE: &[&str] = &["html", "body", "main", "article"];
+
+static UNLIKELY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(nav|navbar|menu|sidebar|header|masthead|footer|comments?|share|social|cookies?|banner|promo|ads?|advert|breadcrumbs?|related|subscribe|newsletter|popup|modal|skip)\b").unwrap()
+});
+static LIKELY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(article|content|main|post|entry|body|text)\b").unwrap());
+
+// navigation, sidebars, footers and the like that surround a page's content
+fn is_boilerplate(element: ElementRef) -> bool {
+    let value = element.value();
+    if NEVER_BOILERPLATE.contains(&value.name()) {
+        return false;
+    }
+    if BOILERPLATE_TAGS.contains(&value.name())
+        || matches!(
+            value.attr("role"),
+            Some("navigation" | "complementary" | "contentinfo" | "banner")
+        )
+        || value.attr("aria-hidden") == Some("true")
+    {
+        return true;
+    }
+    let names = format!(
+        "{} {}",
+        value.attr("class").unwrap_or_default(),
+        value.id().unwrap_or_default()
+    );
+    UNLIKELY.is_match(&names) && !LIKELY.is_match(&names)
+}
+
+fn text_len(element: ElementRef) -> usize {
+    element.text().map(|text| text.trim().chars().count()).sum()
+}
+
+// share of an element's text that sits inside links
+fn link_density(element: ElementRef) -> f64 {
+    let total = text_len(element);
+    if total == 0 {
+        return 1.0;
+    }
+    let selector = Selector::parse("a").unwrap();
+    let linked: usize = element.select(&selector).map(text_len).sum();
+    linked as f64 / total as f64
+}
+
+fn in_boilerplate(element: ElementRef) -> bool {
+    is_boilerplate(element)
+        || element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(is_boilerplate)
+}
+
+// the element holding the page's main content, found the way Readability does:
+// paragraphs score the containers they sit in, discounted by how much of a container is links
+fn main_content(html: &Html) -> ElementRef<'_> {
+    for selector in MAIN_SELECTORS {
+        let selector = Selector::parse(selector).unwrap();
+        let mut matches = html.select(&selector);
+        if let (Some(main), None) = (matches.next(), matches.next())
+            && text_len(main) >= MIN_MAIN_CHARS
+            && !in_boilerplate(main)
+        {
+            return main;
+        }
+    }
+    let mut scores = HashMap::new();
+    let blocks = Selector::parse(SCORED_BLOCKS).unwrap();
+    for block in html.select(&blocks) {
+        let text: String = block.text().collect();
+        let len = text.trim().chars().count();
+        if len < MIN_BLOCK_CHARS || in_boilerplate(block) {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (len as f64 / 100.0).min(3.0);
+        let mut containers = block.ancestors().filter_map(ElementRef::wrap);
+        if let Some(parent) = containers.next() {
+            *scores.entry(parent.id()).or_insert(0.0) += score;
+            if let Some(grandparent) = containers.next() {
+                *scores.entry(grandparent.id()).or_insert(0.0) += score / 2.0;
+            }
+        }
+    }
+    scores
+        .into_iter()
+        .filter_map(|(id, score)| {
+            let element = ElementRef::wrap(html.tree.get(id)?)?;
+            Some((element, score * (1.0 - link_density(element))))
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(element, _)| element)
+        .or_else(|| html.select(&Selector::parse("body").unwrap()).next())
+        .unwrap_or_else(|| html.root_element())
+}
+
+// the main content of a page as Markdown, with links as numbered references listed at the end
+pub fn readable(html: &str, url: &Url) -> Document {
+    let html = Html::parse_document(html);
+    let base = base_url(&html, url);
+    let (markdown, links) = render(
+        main_content(&html),
+        &base,
+        Links::References,
+        is_boilerplate,
+    );
+    Document {
+        title: title(&html),
+        markdown,
+        links,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readable_keeps_main_content() {
+        let base = Url::parse("https://example.com/blog/post").unwrap();
+        let document = readable(
+            r#"<html><head><title>Post</title></head><body>
+              <nav><a href="/">Home</a> <a href="/blog">Blog</a></nav>
+              <div class="layout">
+                <div class="sidebar"><p>Subscribe to our newsletter, it is great, really great.</p></div>
+                <div id="post-body">
+                  <h2>Borrowing</h2>
+                  <p>References let you use a value without taking ownership of it, see the <a href="/book/ch04">book</a>.</p>
+                  <p>Mutable references are exclusive, so only one can exist at a time, which the <a href="/book/ch04">chapter</a> explains.</p>
+                  <div class="share-buttons"><a href="https://social.example">Share</a></div>
+                </div>
+              </div>
+              <footer><p>Copyright 2024, Example Inc, all rights reserved.</p></footer>
+            </body></html>"#,
+            &base,
+        );
+        assert_eq!(document.title.as_deref(), Some("Post"));
+        assert_eq!(
+            document.markdown,
+            "## Borrowing\n\n\
+             References let you use a value without taking ownership of it, see the [book][1].\n\n\
+             Mutable references are exclusive, so only one can exist at a time, which the [chapter][1] explains.\n\n\
+             [1]: https://example.com/book/ch04"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use crate::agent_tools::web::{
+    error::WebError,
+    local::{LocalFetcher, AGENT},
+};
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use url::Url;
+
+const ROBOTS_TIMEOUT: Duration = Duration::from_secs(10);
+// Google's limit, rules past it are ignored
+const MAX_ROBOTS_BYTES: usize = 500 * 1024;
+
+#[derive(Debug)]
+struct Rule {
+    allow: bool,
+    // length of the pattern as written, longer patterns are more specific
+    length: usize,
+    pattern: Regex,
+}
+
+// the robots.txt rules that apply to one user agent
+#[derive(Debug, Default)]
+pub struct Robots {
+    rules: Vec<Rule>,
+}
+
+// `*` matches any run of characters and a trailing `$` anchors the pattern to the end of the path
+fn rule(allow: bool, pattern: &str) -> Option<Rule> {
+    let (body, anchored) = match pattern.strip_suffix('$') {
+        Some(body) => (body, true),
+        None => (pattern, false),
+    };
+    let escaped: Vec<String> = body.split('*').map(regex::escape).collect();
+    let regex = format!("^{}{}", escaped.join(".*"), if anchored { "$" } else { "" });
+    Some(Rule {
+        allow,
+        length: pattern.len(),
+        pattern: Regex::new(&regex).ok()?,
+    })
+}
+
+impl Robots {
+    // what a server error says about a site's robots.txt: nothing may be fetched for now
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: rule(false, "/").into_iter().collect(),
+        }
+    }
+    // rules from the groups naming `agent`, or from the `*` groups when none do
+    pub fn parse(text: &str, agent: &str) -> Self {
+        let mut groups: Vec<(Vec<String>, Vec<Rule>)> = Vec::new();
+        let mut in_rules = false;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match field.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    if in_rules || groups.is_empty() {
+                        groups.push((Vec::new(), Vec::new()));
+                        in_rules = false;
+                    }
+                    if let Some((agents, _)) = groups.last_mut() {
+                        agents.push(value.to_lowercase());
+                    }
+                }
+                field @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    if value.is_empty() {
+                        continue;
+                    }
+                    if let (Some((_, rules)), Some(rule)) =
+                        (groups.last_mut(), rule(field == "allow", value))
+                    {
+                        rules.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let agent = agent.to_lowercase();
+        let named = groups.iter().any(|(agents, _)| agents.contains(&agent));
+        Self {
+            rules: groups
+                .into_iter()
+                .filter(|(agents, _)| {
+                    agents
+                        .iter()
+                        .any(|name| if named { *name == agent } else { name == "*" })
+                })
+                .flat_map(|(_, rules)| rules)
+                .collect(),
+        }
+    }
+    // the longest matching rule decides, with allow winning a tie
+    pub fn allows(&self, url: &Url) -> bool {
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        self.rules
+            .iter()
+            .filter(|rule| rule.pattern.is_match(&path))
+            .max_by_key(|rule| (rule.length, rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+// robots.txt per origin, fetched the first time a page there is requested
+#[derive(Default)]
+pub struct RobotsCache {
+    origins: Mutex<HashMap<String, Arc<Robots>>>,
+}
+
+impl RobotsCache {
+    pub async fn check(&self, fetcher: &LocalFetcher, url: &Url) -> Result<(), WebError> {
+        let origin = url.origin().ascii_serialization();
+        let cached = self.origins.lock().unwrap().get(&origin).cloned();
+        let robots = match cached {
+            Some(robots) => robots,
+            None => {
+                let robots = Arc::new(Self::fetch(fetcher, url).await);
+                self.origins.lock().unwrap().insert(origin, robots.clone());
+                robots
+            }
+        };
+        if robots.allows(url) {
+            Ok(())
+        } else {
+            Err(WebError::Robots(url.to_string()))
+        }
+    }
+    // a missing robots.txt allows everything, one the server fails to serve allows nothing
+    async fn fetch(fetcher: &LocalFetcher, url: &Url) -> Robots {
+        let Ok(robots_url) = url.join("/robots.txt") else {
+            return Robots::default();
+        };
+        let agent = AGENT.split('/').next().unwrap_or(AGENT);
+        match fetcher
+            .download(&robots_url, ROBOTS_TIMEOUT, MAX_ROBOTS_BYTES)
+            .await
+        {
+            Ok(download) => Robots::parse(&String::from_utf8_lossy(&download.bytes), agent),
+            Err(WebError::Fetch { status, .. }) if status.is_server_error() => {
+                Robots::disallow_all()
+            }
+            Err(_) => Robots::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_robots_rules() {
+        let robots = Robots::parse(
+            "User-agent: *\n\
+             Disallow: /\n\
+             \n\
+             User-agent: Googlebot\n\
+             User-agent: marvin # us\n\
+             Disallow: /private/\n\
+             Allow: /private/docs/\n\
+             Disallow: /*.pdf$\n\
+             Disallow:\n",
+            "marvin",
+        );
+        let allows = |path: &str| {
+            robots.allows(&Url::parse(&format!("https://example.com{}", path)).unwrap())
+        };
+        assert!(allows("/"));
+        assert!(!allows("/private/keys"));
+        assert!(allows("/private/docs/guide"));
+        assert!(!allows("/papers/rust.pdf"));
+        assert!(allows("/papers/rust.pdf?download=1"));
+        let others = Robots::parse("User-agent: *\nDisallow: /search?q=\n", "marvin");
+        assert!(!others.allows(&Url::parse("https://example.com/search?q=rust").unwrap()));
+        assert!(others.allows(&Url::parse("https://example.com/searching").unwrap()));
+    }
+}
@@ -1,6 +1,16 @@
+use crate::agent_tools::web::{
+    error::WebError,
+    validate::{date, max_items, range, Validate, DATE_PATTERN},
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+const MIN_CHUNKS: u8 = 1;
+const MAX_CHUNKS: u8 = 3;
+const MAX_RESULTS: u8 = 20;
+const MAX_INCLUDE_DOMAINS: usize = 300;
+const MAX_EXCLUDE_DOMAINS: usize = 150;
+
 #[derive(Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[schemars(
@@ -57,11 +67,17 @@ pub struct SearchArgs {
     pub search_depth: Option<SearchDepth>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Max relevant snippets per source (1-3). Only for 'advanced' depth")]
+    #[schemars(
+        description = "Max relevant snippets per source (1-3). Only for 'advanced' depth",
+        range(min = MIN_CHUNKS, max = MAX_CHUNKS)
+    )]
     pub chunks_per_source: Option<u8>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Maximum search results to return (0-20). Default: 5")]
+    #[schemars(
+        description = "Maximum search results to return (0-20). Default: 5",
+        range(min = 0, max = MAX_RESULTS)
+    )]
     pub max_results: Option<u8>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -69,11 +85,17 @@ pub struct SearchArgs {
     pub time_range: Option<TimeRange>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Return results published after this date (YYYY-MM-DD format)")]
+    #[schemars(
+        description = "Return results published after this date (YYYY-MM-DD format)",
+        regex(pattern = DATE_PATTERN)
+    )]
     pub start_date: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Return results published before this date (YYYY-MM-DD format)")]
+    #[schemars(
+        description = "Return results published before this date (YYYY-MM-DD format)",
+        regex(pattern = DATE_PATTERN)
+    )]
     pub end_date: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -99,18 +121,53 @@ pub struct SearchArgs {
     pub include_favicon: Option<bool>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "List of domains to restrict search to (max 300)")]
+    #[schemars(
+        description = "List of domains to restrict search to (max 300)",
+        length(max = MAX_INCLUDE_DOMAINS)
+    )]
     pub include_domains: Option<Vec<String>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "List of domains to exclude from search (max 150)")]
+    #[schemars(
+        description = "List of domains to exclude from search (max 150)",
+        length(max = MAX_EXCLUDE_DOMAINS)
+    )]
     pub exclude_domains: Option<Vec<String>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[schemars(description = "Boost results from a specific country (ISO country code)")]
     pub country: Option<String>,
+}
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[schemars(description = "Include credit usage information in response")]
-    pub include_usage: Option<bool>,
+impl Validate for SearchArgs {
+    fn validate(&self) -> Result<(), WebError> {
+        range(
+            "chunks_per_source",
+            self.chunks_per_source,
+            MIN_CHUNKS,
+            MAX_CHUNKS,
+        )?;
+        range("max_results", self.max_results, 0, MAX_RESULTS)?;
+        max_items(
+            "include_domains",
+            self.include_domains.as_deref(),
+            MAX_INCLUDE_DOMAINS,
+        )?;
+        max_items(
+            "exclude_domains",
+            self.exclude_domains.as_deref(),
+            MAX_EXCLUDE_DOMAINS,
+        )?;
+        let start = date("start_date", self.start_date.as_deref())?;
+        let end = date("end_date", self.end_date.as_deref())?;
+        if let (Some(start), Some(end)) = (start, end)
+            && start > end
+        {
+            return Err(WebError::Argument {
+                field: "start_date",
+                reason: format!("must not be after end_date ({} > {})", start, end),
+            });
+        }
+        Ok(())
+    }
 }
@@ -1,27 +1,43 @@
+use crate::agent_tools::web::budget::{Budget, Page};
 use serde::{Deserialize, Serialize};
 
+const MAX_IMAGES: usize = 5;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchResponse {
     pub query: String,
     pub answer: Option<String>,
-    pub images: Vec<String>,
+    #[serde(default)]
+    pub images: Vec<SearchImage>,
     pub results: Vec<SearchResult>,
-    pub response_time: String,
+    pub response_time: Option<serde_json::Value>,
     pub auto_parameters: Option<AutoParameters>,
     pub usage: Option<Usage>,
-    pub request_id: String,
+    pub request_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchResult {
     pub title: String,
     pub url: String,
+    #[serde(default)]
     pub content: String,
-    pub score: f64,
+    pub score: Option<f64>,
     pub raw_content: Option<String>,
     pub favicon: Option<String>,
 }
 
+// images are plain URLs unless image descriptions were requested
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SearchImage {
+    Url(String),
+    Described {
+        url: String,
+        description: Option<String>,
+    },
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AutoParameters {
     pub topic: String,
@@ -32,3 +48,43 @@ pub struct AutoParameters {
 pub struct Usage {
     pub credits: u32,
 }
+
+#[derive(Debug, Serialize)]
+pub struct SearchOutput {
+    pub query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    pub results: Vec<Page>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<SearchImage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub omitted: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<String>,
+}
+
+impl SearchResponse {
+    pub fn render(self, budget: Budget) -> SearchOutput {
+        let mut results = self.results;
+        results.sort_by(|a, b| b.score.unwrap_or(0.0).total_cmp(&a.score.unwrap_or(0.0)));
+        let pages = results
+            .into_iter()
+            .map(|result| Page {
+                source: None,
+                title: Some(result.title),
+                url: result.url,
+                score: result.score,
+                content: result.raw_content.unwrap_or(result.content),
+            })
+            .collect();
+        let (results, omitted) = budget.fit_pages(pages);
+        SearchOutput {
+            query: self.query,
+            answer: self.answer,
+            results,
+            images: self.images.into_iter().take(MAX_IMAGES).collect(),
+            omitted: budget.omitted(omitted, "result(s)"),
+            cache: None,
+        }
+    }
+}
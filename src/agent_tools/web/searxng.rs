@@ -0,0 +1,157 @@
+use crate::{
+    agent_tools::web::{
+        cache::Cached,
+        crawl::{request::CrawlArgs, response::CrawlResponse},
+        error::WebError,
+        extract::{request::ExtractArgs, response::ExtractResponse},
+        local::LocalFetcher,
+        map::{request::MapArgs, response::MapResponse},
+        provider::{filter_domains, send, ProviderKind, WebResult, WebSearchProvider},
+        rate_limit::{RateLimit, RateLimiter},
+        search::{
+            request::{SearchArgs, TimeRange, Topic},
+            response::{SearchResponse, SearchResult},
+        },
+    },
+    cassette::HttpTransport,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use url::Url;
+
+const PROVIDER: &str = "SearXNG";
+const SEARCH_PATH: &str = "/search";
+const DEFAULT_MAX_RESULTS: usize = 5;
+
+#[derive(Debug, Deserialize)]
+struct SearxngResponse {
+    #[serde(default)]
+    results: Vec<SearxngResult>,
+    // plain strings in older versions, objects with an answer field in newer ones
+    #[serde(default)]
+    answers: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearxngResult {
+    url: String,
+    #[serde(default)]
+    title: String,
+    content: Option<String>,
+    score: Option<f64>,
+}
+
+// a self-hosted SearXNG instance for search, pages are fetched directly for extract, crawl and map
+pub struct SearxngClient {
+    transport: HttpTransport,
+    limiter: RateLimiter,
+    base_url: String,
+    fetcher: LocalFetcher,
+}
+
+impl SearxngClient {
+    pub fn new(
+        transport: HttpTransport,
+        base_url: impl Into<String>,
+        fetcher: LocalFetcher,
+    ) -> Self {
+        Self {
+            transport,
+            limiter: RateLimiter::new(ProviderKind::Searxng.rate_limit()),
+            base_url: base_url.into(),
+            fetcher,
+        }
+    }
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.limiter = RateLimiter::new(limit);
+        self
+    }
+    async fn search_instance(&self, args: &SearchArgs) -> Result<Cached<SearchResponse>, WebError> {
+        let mut url = Url::parse(&format!("{}{}", self.base_url, SEARCH_PATH))?;
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("q", &args.query)
+                .append_pair("format", "json");
+            if let Some(Topic::News) = args.topic {
+                query.append_pair("categories", "news");
+            }
+            let time_range = match args.time_range {
+                Some(TimeRange::Day) => Some("day"),
+                Some(TimeRange::Week) => Some("week"),
+                Some(TimeRange::Month) => Some("month"),
+                Some(TimeRange::Year) => Some("year"),
+                _ => None,
+            };
+            if let Some(time_range) = time_range {
+                query.append_pair("time_range", time_range);
+            }
+        }
+        let request = self.transport.client().get(url);
+        let response = send(
+            &self.transport,
+            &self.limiter,
+            PROVIDER,
+            SEARCH_PATH,
+            request,
+        )
+        .await?;
+        let response: SearxngResponse =
+            serde_json::from_value(response).map_err(|source| WebError::Schema {
+                provider: PROVIDER,
+                endpoint: SEARCH_PATH,
+                source,
+            })?;
+        let mut results = filter_domains(
+            response.results,
+            |result| &result.url,
+            args.include_domains.as_deref(),
+            args.exclude_domains.as_deref(),
+        );
+        results.truncate(args.max_results.map_or(DEFAULT_MAX_RESULTS, usize::from));
+        let answer = response
+            .answers
+            .iter()
+            .find_map(|answer| answer.as_str().or(answer["answer"].as_str()))
+            .filter(|_| args.include_answer == Some(true))
+            .map(str::to_string);
+        Ok(Cached::fresh(SearchResponse {
+            query: args.query.clone(),
+            answer,
+            images: Vec::new(),
+            results: results
+                .into_iter()
+                .map(|result| SearchResult {
+                    title: result.title,
+                    url: result.url,
+                    content: result.content.unwrap_or_default(),
+                    score: result.score,
+                    raw_content: None,
+                    favicon: None,
+                })
+                .collect(),
+            response_time: None,
+            auto_parameters: None,
+            usage: None,
+            request_id: None,
+        }))
+    }
+}
+
+impl WebSearchProvider for SearxngClient {
+    fn name(&self) -> &'static str {
+        "searxng"
+    }
+    fn search<'a>(&'a self, args: &'a SearchArgs) -> WebResult<'a, SearchResponse> {
+        Box::pin(self.search_instance(args))
+    }
+    fn extract<'a>(&'a self, args: &'a ExtractArgs) -> WebResult<'a, ExtractResponse> {
+        self.fetcher.extract(args)
+    }
+    fn crawl<'a>(&'a self, args: &'a CrawlArgs) -> WebResult<'a, CrawlResponse> {
+        self.fetcher.crawl(args)
+    }
+    fn map<'a>(&'a self, args: &'a MapArgs) -> WebResult<'a, MapResponse> {
+        self.fetcher.map(args)
+    }
+}
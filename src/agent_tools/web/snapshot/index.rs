@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// BM25's usual constants, term frequency saturation and length normalization
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+const MAX_CHUNK_CHARS: usize = 1500;
+
+// a stretch of one page between headings, cut at a paragraph break when it grows long
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chunk {
+    pub page: usize,
+    pub heading: Option<String>,
+    // byte range in the page's Markdown
+    pub start: usize,
+    pub end: usize,
+    pub terms: usize,
+}
+
+// an inverted index over a snapshot's chunks, scored with BM25
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Index {
+    pub chunks: Vec<Chunk>,
+    postings: HashMap<String, Vec<(usize, u32)>>,
+}
+
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|term| term.chars().count() > 1)
+        .map(str::to_lowercase)
+}
+
+fn heading(line: &str) -> Option<&str> {
+    let text = line.strip_prefix('#')?.trim_start_matches('#');
+    text.starts_with(' ').then(|| text.trim())
+}
+
+// byte ranges of a page's chunks with the heading each falls under
+fn split(markdown: &str) -> Vec<(Option<String>, usize, usize)> {
+    let mut chunks = Vec::new();
+    let mut current: Option<String> = None;
+    let mut start = 0;
+    let mut offset = 0;
+    let mut close = |heading: &Option<String>, start: usize, end: usize| {
+        if !markdown[start..end].trim().is_empty() {
+            chunks.push((heading.clone(), start, end));
+        }
+    };
+    for line in markdown.split_inclusive('\n') {
+        if let Some(text) = heading(line) {
+            close(&current, start, offset);
+            current = Some(text.to_string());
+            start = offset;
+        } else if line.trim().is_empty() && offset - start > MAX_CHUNK_CHARS {
+            close(&current, start, offset);
+            start = offset;
+        }
+        offset += line.len();
+    }
+    close(&current, start, markdown.len());
+    chunks
+}
+
+impl Index {
+    pub fn add_page(&mut self, page: usize, markdown: &str) {
+        for (heading, start, end) in split(markdown) {
+            let chunk = self.chunks.len();
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            for term in terms(&markdown[start..end]) {
+                *counts.entry(term).or_default() += 1;
+            }
+            for (term, count) in counts.iter() {
+                self.postings
+                    .entry(term.clone())
+                    .or_default()
+                    .push((chunk, *count));
+            }
+            self.chunks.push(Chunk {
+                page,
+                heading,
+                start,
+                end,
+                terms: counts.values().sum::<u32>() as usize,
+            });
+        }
+    }
+    // the best scoring chunks for a query, best first
+    pub fn search(&self, query: &str, limit: usize) -> Vec<(&Chunk, f64)> {
+        let count = self.chunks.len() as f64;
+        let average =
+            self.chunks.iter().map(|chunk| chunk.terms).sum::<usize>() as f64 / count.max(1.0);
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        let mut query_terms: Vec<String> = terms(query).collect();
+        query_terms.sort();
+        query_terms.dedup();
+        for term in query_terms {
+            let Some(postings) = self.postings.get(&term) else {
+                continue;
+            };
+            let frequency = postings.len() as f64;
+            let idf = ((count - frequency + 0.5) / (frequency + 0.5) + 1.0).ln();
+            for (chunk, occurrences) in postings {
+                let occurrences = *occurrences as f64;
+                let length = self.chunks[*chunk].terms as f64 / average.max(1.0);
+                *scores.entry(*chunk).or_default() +=
+                    idf * occurrences * (K1 + 1.0) / (occurrences + K1 * (1.0 - B + B * length));
+            }
+        }
+        let mut ranked: Vec<(usize, f64)> = scores.into_iter().collect();
+        ranked.sort_by(|(a_chunk, a), (b_chunk, b)| b.total_cmp(a).then(a_chunk.cmp(b_chunk)));
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|(chunk, score)| (&self.chunks[chunk], score))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_follow_headings_and_rank_with_bm25() {
+        let intro = "# Async Book\n\nIntro to async.\n\n## Pinning\n\nPin keeps a future in place so self references stay valid.\n";
+        let executors = format!(
+            "# Executors\n\n{}\n\nA waker tells the executor to poll a pinned future again.\n",
+            "Executors poll futures. ".repeat(70)
+        );
+        let mut index = Index::default();
+        index.add_page(0, intro);
+        index.add_page(1, &executors);
+        let headings: Vec<(usize, Option<&str>)> = index
+            .chunks
+            .iter()
+            .map(|chunk| (chunk.page, chunk.heading.as_deref()))
+            .collect();
+        assert_eq!(
+            headings,
+            vec![
+                (0, Some("Async Book")),
+                (0, Some("Pinning")),
+                (1, Some("Executors")),
+                (1, Some("Executors")),
+            ]
+        );
+        let pinning = &index.chunks[1];
+        assert_eq!(
+            &intro[pinning.start..pinning.end],
+            "## Pinning\n\nPin keeps a future in place so self references stay valid.\n"
+        );
+        let found = index.search("pin a future", 3);
+        assert_eq!(found[0].0, pinning);
+        assert!(found.iter().all(|(_, score)| *score > 0.0));
+        assert!(index.search("tokio", 3).is_empty());
+        let json = serde_json::to_string(&index).unwrap();
+        let restored: Index = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.search("waker", 1)[0].0, &index.chunks[3]);
+    }
+}
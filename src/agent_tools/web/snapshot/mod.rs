@@ -0,0 +1,69 @@
+pub mod index;
+pub mod request;
+pub mod response;
+pub mod store;
+
+use crate::agent_tools::{
+    web::{budget::Budget, error::WebError, sources::Sources, validate::Validate},
+    ToToolResult,
+};
+use request::{SearchSnapshotsArgs, DEFAULT_RESULTS};
+use response::SnapshotOutput;
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolError},
+};
+use schemars::schema_for;
+use std::sync::Arc;
+use store::SnapshotStore;
+
+// answers from sites the user saved with /snapshot, without touching the network
+pub struct SearchSnapshots {
+    store: SnapshotStore,
+    sources: Arc<Sources>,
+    token_budget: usize,
+}
+
+impl SearchSnapshots {
+    pub fn new(store: SnapshotStore, sources: Arc<Sources>, token_budget: usize) -> Self {
+        Self {
+            store,
+            sources,
+            token_budget,
+        }
+    }
+}
+
+impl Tool for SearchSnapshots {
+    const NAME: &'static str = "search_snapshots";
+    type Args = SearchSnapshotsArgs;
+    type Output = SnapshotOutput;
+    type Error = ToolError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Search documentation sites the user saved locally with /snapshot, offline and without credits. Returns the best-matching passages with the URL of the page each comes from, plus the snapshots searched. Prefer it over the web tools for questions about a site that has a snapshot.".to_string(),
+            parameters: serde_json::to_value(schema_for!(SearchSnapshotsArgs)).unwrap(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        args.validate().to_tool_result()?;
+        let store = self.store.clone();
+        let limit = args.max_results.unwrap_or(DEFAULT_RESULTS) as usize;
+        let (searched, hits) = tokio::task::spawn_blocking(move || {
+            store.search(&args.query, args.site.as_deref(), limit)
+        })
+        .await
+        .map_err(|e| WebError::Snapshot {
+            path: self.store.dir().display().to_string(),
+            source: std::io::Error::other(e),
+        })
+        .and_then(|result| result)
+        .to_tool_result()?;
+        let mut output = SnapshotOutput::render(&searched, hits, Budget::new(self.token_budget));
+        self.sources.cite_pages(&mut output.results);
+        Ok(output)
+    }
+}
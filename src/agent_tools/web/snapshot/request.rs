@@ -0,0 +1,41 @@
+use crate::agent_tools::web::{
+    error::WebError,
+    validate::{range, Validate},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const MIN_RESULTS: u8 = 1;
+const MAX_RESULTS: u8 = 10;
+pub const DEFAULT_RESULTS: u8 = 5;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct SearchSnapshotsArgs {
+    #[schemars(description = "What to look for in the saved pages")]
+    pub query: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Only search snapshots whose name or start URL contains this, e.g. 'async-book' or 'docs.rs'. Default: every snapshot"
+    )]
+    pub site: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Maximum number of passages to return (1-10). Default: 5",
+        range(min = MIN_RESULTS, max = MAX_RESULTS)
+    )]
+    pub max_results: Option<u8>,
+}
+
+impl Validate for SearchSnapshotsArgs {
+    fn validate(&self) -> Result<(), WebError> {
+        if self.query.trim().is_empty() {
+            return Err(WebError::Argument {
+                field: "query",
+                reason: "must not be empty".to_string(),
+            });
+        }
+        range("max_results", self.max_results, MIN_RESULTS, MAX_RESULTS)
+    }
+}
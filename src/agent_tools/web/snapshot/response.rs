@@ -0,0 +1,43 @@
+use crate::agent_tools::web::{
+    budget::{Budget, Page},
+    snapshot::store::{Hit, Snapshot},
+};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct SnapshotOutput {
+    // the snapshots searched, with the URL and time each was taken
+    pub searched: Vec<String>,
+    pub results: Vec<Page>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub omitted: Option<String>,
+}
+
+impl SnapshotOutput {
+    pub fn render(searched: &[Snapshot], hits: Vec<Hit>, budget: Budget) -> Self {
+        let pages = hits
+            .into_iter()
+            .map(|hit| Page {
+                source: None,
+                title: hit.title,
+                url: hit.url,
+                score: Some((hit.score * 100.0).round() / 100.0),
+                content: hit.content,
+            })
+            .collect();
+        let (results, omitted) = budget.fit_pages(pages);
+        Self {
+            searched: searched
+                .iter()
+                .map(|snapshot| {
+                    format!(
+                        "{} ({}, taken {})",
+                        snapshot.name, snapshot.url, snapshot.taken_at
+                    )
+                })
+                .collect(),
+            results,
+            omitted: budget.omitted(omitted, "passage(s)"),
+        }
+    }
+}
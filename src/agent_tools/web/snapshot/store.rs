@@ -0,0 +1,300 @@
+use crate::agent_tools::web::{error::WebError, snapshot::index::Index};
+use chrono::Utc;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+pub const SNAPSHOTS_DIR_NAME: &str = "snapshots";
+const MANIFEST_FILE: &str = "snapshot.json";
+const INDEX_FILE: &str = "index.json";
+const PAGES_DIR: &str = "pages";
+const MAX_NAME_CHARS: usize = 80;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotPage {
+    // relative to the snapshot's pages directory
+    pub file: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub name: String,
+    pub url: String,
+    pub taken_at: String,
+    pub pages: Vec<SnapshotPage>,
+}
+
+// a passage of a snapshot page that matched a search
+#[derive(Debug, Clone)]
+pub struct Hit {
+    pub url: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub score: f64,
+}
+
+// crawled sites kept on disk as Markdown pages with an index, so they can be searched offline.
+// each snapshot is a directory holding snapshot.json, index.json and pages/*.md
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+fn io_error(path: &Path) -> impl FnOnce(std::io::Error) -> WebError + '_ {
+    move |source| WebError::Snapshot {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, WebError> {
+    let text = std::fs::read_to_string(path).map_err(io_error(path))?;
+    serde_json::from_str(&text).map_err(|source| WebError::SnapshotFile {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), WebError> {
+    let json = serde_json::to_string_pretty(value).map_err(|source| WebError::SnapshotFile {
+        path: path.display().to_string(),
+        source,
+    })?;
+    std::fs::write(path, json).map_err(io_error(path))
+}
+
+// lowercase words of `text` joined by dashes, cut between words
+fn slug(text: &str, max_chars: usize) -> String {
+    let mut slug = String::new();
+    for word in text
+        .split(|c: char| !c.is_alphanumeric() && c != '.' && c != '_')
+        .filter(|word| !word.is_empty())
+    {
+        if !slug.is_empty() && slug.len() + word.len() + 1 > max_chars {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_lowercase());
+    }
+    slug
+}
+
+// the page's first heading, crawled Markdown rarely carries its <title>
+fn title(markdown: &str) -> Option<String> {
+    markdown
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+}
+
+fn front_matter(url: &str, title: Option<&str>, taken_at: &str) -> String {
+    let mut front = format!("---\nurl: {}\n", url);
+    if let Some(title) = title {
+        front.push_str(&format!("title: {}\n", title));
+    }
+    front.push_str(&format!("taken_at: {}\n---\n\n", taken_at));
+    front
+}
+
+// a page's Markdown without the metadata written above it
+fn body(page: &str) -> &str {
+    page.strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n\n"))
+        .map_or(page, |(_, body)| body)
+}
+
+impl SnapshotStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    // the host and path of the start URL, so docs.rs/serde and docs.rs/tokio don't collide
+    pub fn name(url: &Url) -> String {
+        let name = slug(
+            &format!("{} {}", url.host_str().unwrap_or_default(), url.path()),
+            MAX_NAME_CHARS,
+        );
+        if name.is_empty() {
+            "site".to_string()
+        } else {
+            name
+        }
+    }
+    // replaces any earlier snapshot of the same site
+    pub fn save(&self, url: &Url, pages: Vec<(String, String)>) -> Result<Snapshot, WebError> {
+        let name = Self::name(url);
+        let dir = self.dir.join(&name);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(io_error(&dir))?;
+        }
+        let pages_dir = dir.join(PAGES_DIR);
+        std::fs::create_dir_all(&pages_dir).map_err(io_error(&pages_dir))?;
+        let taken_at = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let mut index = Index::default();
+        let mut saved = Vec::new();
+        for (i, (page_url, markdown)) in pages.into_iter().enumerate() {
+            let path_slug = Url::parse(&page_url)
+                .map(|url| slug(url.path(), 40))
+                .unwrap_or_default();
+            let file = match path_slug.as_str() {
+                "" => format!("{:03}.md", i + 1),
+                path_slug => format!("{:03}-{}.md", i + 1, path_slug),
+            };
+            let title = title(&markdown);
+            let path = pages_dir.join(&file);
+            std::fs::write(
+                &path,
+                front_matter(&page_url, title.as_deref(), &taken_at) + &markdown,
+            )
+            .map_err(io_error(&path))?;
+            index.add_page(saved.len(), &markdown);
+            saved.push(SnapshotPage {
+                file,
+                url: page_url,
+                title,
+            });
+        }
+        let snapshot = Snapshot {
+            name,
+            url: url.to_string(),
+            taken_at,
+            pages: saved,
+        };
+        write_json(&dir.join(INDEX_FILE), &index)?;
+        write_json(&dir.join(MANIFEST_FILE), &snapshot)?;
+        Ok(snapshot)
+    }
+    // every snapshot taken so far, by name
+    pub fn list(&self) -> Result<Vec<Snapshot>, WebError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_error(&self.dir)(e)),
+        };
+        let mut snapshots = Vec::new();
+        for entry in entries.flatten() {
+            let manifest = entry.path().join(MANIFEST_FILE);
+            if manifest.is_file() {
+                snapshots.push(read_json::<Snapshot>(&manifest)?);
+            }
+        }
+        snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(snapshots)
+    }
+    // the best passages for a query across the snapshots whose name or URL contains `site`
+    pub fn search(
+        &self,
+        query: &str,
+        site: Option<&str>,
+        limit: usize,
+    ) -> Result<(Vec<Snapshot>, Vec<Hit>), WebError> {
+        let snapshots = self.list()?;
+        if snapshots.is_empty() {
+            return Err(WebError::NoSnapshots);
+        }
+        let searched: Vec<Snapshot> = snapshots
+            .iter()
+            .filter(|snapshot| {
+                site.is_none_or(|site| snapshot.name.contains(site) || snapshot.url.contains(site))
+            })
+            .cloned()
+            .collect();
+        if searched.is_empty() {
+            return Err(WebError::UnknownSnapshot {
+                site: site.unwrap_or_default().to_string(),
+                known: snapshots
+                    .iter()
+                    .map(|snapshot| snapshot.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            });
+        }
+        let mut hits = Vec::new();
+        for snapshot in &searched {
+            let dir = self.dir.join(&snapshot.name);
+            let index: Index = read_json(&dir.join(INDEX_FILE))?;
+            for (chunk, score) in index.search(query, limit) {
+                let Some(page) = snapshot.pages.get(chunk.page) else {
+                    continue;
+                };
+                let path = dir.join(PAGES_DIR).join(&page.file);
+                let text = std::fs::read_to_string(&path).map_err(io_error(&path))?;
+                let content = body(&text)
+                    .get(chunk.start..chunk.end)
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+                hits.push(Hit {
+                    url: page.url.clone(),
+                    title: page.title.clone(),
+                    content,
+                    score,
+                });
+            }
+        }
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        Ok((searched, hits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_snapshot_is_searchable() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path());
+        let url = Url::parse("https://rust-lang.github.io/async-book/").unwrap();
+        assert_eq!(SnapshotStore::name(&url), "rust-lang.github.io-async-book");
+        store
+            .save(
+                &url,
+                vec![
+                    (
+                        "https://rust-lang.github.io/async-book/01_getting_started/".to_string(),
+                        "# Getting Started\n\nAsync Rust runs futures on an executor.\n"
+                            .to_string(),
+                    ),
+                    (
+                        "https://rust-lang.github.io/async-book/04_pinning/".to_string(),
+                        "# Pinning\n\nPin guarantees a value will not move.\n".to_string(),
+                    ),
+                ],
+            )
+            .unwrap();
+        let page = std::fs::read_to_string(
+            dir.path()
+                .join("rust-lang.github.io-async-book/pages/002-async-book-04_pinning.md"),
+        )
+        .unwrap();
+        assert!(page.starts_with(
+            "---\nurl: https://rust-lang.github.io/async-book/04_pinning/\ntitle: Pinning\ntaken_at: "
+        ));
+        let (searched, hits) = store.search("does pin move values", None, 5).unwrap();
+        assert_eq!(searched.len(), 1);
+        assert_eq!(hits[0].title.as_deref(), Some("Pinning"));
+        assert_eq!(
+            hits[0].content,
+            "# Pinning\n\nPin guarantees a value will not move."
+        );
+        assert!(matches!(
+            store.search("pin", Some("docs.rs"), 5),
+            Err(WebError::UnknownSnapshot { known, .. }) if known == "rust-lang.github.io-async-book"
+        ));
+        let empty = SnapshotStore::new(dir.path().join("none"));
+        assert!(matches!(
+            empty.search("pin", None, 5),
+            Err(WebError::NoSnapshots)
+        ));
+    }
+}
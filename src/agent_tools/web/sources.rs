@@ -0,0 +1,121 @@
+use crate::agent_tools::web::budget::Page;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Source {
+    pub id: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+// every page the web tools showed the agent this session, a URL keeps its id for the whole session
+#[derive(Debug, Default)]
+pub struct Sources {
+    sources: Mutex<Vec<Source>>,
+}
+
+// fragments and trailing slashes don't make a different source
+fn normalize(url: &str) -> &str {
+    let url = url.split('#').next().unwrap_or(url);
+    url.strip_suffix('/').unwrap_or(url)
+}
+
+fn number(id: &str) -> Option<u32> {
+    id.strip_prefix('S')?.parse().ok()
+}
+
+impl Sources {
+    pub fn cite(&self, url: &str, title: Option<&str>) -> String {
+        let mut sources = self.sources.lock().unwrap();
+        if let Some(source) = sources
+            .iter_mut()
+            .find(|source| normalize(&source.url) == normalize(url))
+        {
+            if source.title.is_none() {
+                source.title = title.map(str::to_string);
+            }
+            return source.id.clone();
+        }
+        let next = sources
+            .iter()
+            .filter_map(|source| number(&source.id))
+            .max()
+            .unwrap_or_default()
+            + 1;
+        let id = format!("S{}", next);
+        sources.push(Source {
+            id: id.clone(),
+            url: url.to_string(),
+            title: title.map(str::to_string),
+        });
+        id
+    }
+    pub fn cite_pages(&self, pages: &mut [Page]) {
+        for page in pages {
+            page.source = Some(self.cite(&page.url, page.title.as_deref()));
+        }
+    }
+    pub fn get(&self, id: &str) -> Option<Source> {
+        self.sources
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|source| source.id == id)
+            .cloned()
+    }
+    pub fn all(&self) -> Vec<Source> {
+        self.sources.lock().unwrap().clone()
+    }
+    // adds sources from a saved session, keeping their ids so its citations still resolve.
+    // one whose id is already taken by another URL is left out
+    pub fn restore(&self, restored: Vec<Source>) {
+        let mut sources = self.sources.lock().unwrap();
+        for source in restored {
+            if !sources.iter().any(|known| {
+                known.id == source.id || normalize(&known.url) == normalize(&source.url)
+            }) {
+                sources.push(source);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sources_keep_ids_per_url() {
+        let sources = Sources::default();
+        assert_eq!(sources.cite("https://doc.rust-lang.org/book/", None), "S1");
+        assert_eq!(
+            sources.cite("https://blog.rust-lang.org", Some("Blog")),
+            "S2"
+        );
+        assert_eq!(
+            sources.cite("https://doc.rust-lang.org/book#intro", Some("The Book")),
+            "S1"
+        );
+        assert_eq!(
+            sources.get("S1").unwrap().title.as_deref(),
+            Some("The Book")
+        );
+        sources.restore(vec![
+            Source {
+                id: "S2".to_string(),
+                url: "https://example.com".to_string(),
+                title: None,
+            },
+            Source {
+                id: "S7".to_string(),
+                url: "https://crates.io".to_string(),
+                title: None,
+            },
+        ]);
+        assert_eq!(sources.get("S2").unwrap().url, "https://blog.rust-lang.org");
+        assert_eq!(sources.cite("https://docs.rs", None), "S8");
+        assert_eq!(sources.all().len(), 4);
+    }
+}
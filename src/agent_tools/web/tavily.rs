@@ -1,34 +1,172 @@
-use crate::chat::config::Config;
-use reqwest::{Client, Response};
+use crate::{
+    agent_tools::web::{
+        cache::{Cached, ResponseCache},
+        crawl::{request::CrawlArgs, response::CrawlResponse, Crawl},
+        credits::{AccountUsage, CreditLedger},
+        error::WebError,
+        extract::{request::ExtractArgs, response::ExtractResponse, Extract},
+        map::{request::MapArgs, response::MapResponse, Map},
+        provider::{send, ProviderKind, WebResult, WebSearchProvider},
+        rate_limit::{RateLimit, RateLimiter},
+        search::{request::SearchArgs, response::SearchResponse, Search},
+    },
+    cassette::HttpTransport,
+    chat::config::Config,
+};
+use futures::future::BoxFuture;
+use reqwest::{Method, RequestBuilder};
+use rig::tool::Tool;
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
+use std::path::PathBuf;
 use url::Url;
 
+const PROVIDER: &str = "Tavily";
+const SEARCH_PATH: &str = "/search";
+const EXTRACT_PATH: &str = "/extract";
+const CRAWL_PATH: &str = "/crawl";
+const MAP_PATH: &str = "/map";
+const USAGE_PATH: &str = "/usage";
+
 pub struct TavilyClient {
-    client: Client,
+    transport: HttpTransport,
+    limiter: RateLimiter,
     api_key: String,
+    base_url: String,
+    cache: Option<ResponseCache>,
+    credits: CreditLedger,
 }
 
-pub(super) const BASE_URL: &str = "https://api.tavily.com";
-
 impl TavilyClient {
-    pub fn new(api_key: impl Into<String>) -> Self {
+    pub fn new(
+        transport: HttpTransport,
+        api_key: impl Into<String>,
+        base_url: impl Into<String>,
+    ) -> Self {
         Self {
-            client: Client::new(),
+            transport,
+            limiter: RateLimiter::new(ProviderKind::Tavily.rate_limit()),
             api_key: api_key.into(),
+            base_url: base_url.into(),
+            cache: None,
+            credits: CreditLedger::default(),
         }
     }
-    pub async fn post(&self, url: Url, json: Value) -> Result<Response, reqwest::Error> {
-        self.client
-            .post(url)
-            .bearer_auth(&self.api_key)
-            .json(&json)
-            .send()
-            .await
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.limiter = RateLimiter::new(limit);
+        self
+    }
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache = Some(ResponseCache::new(dir));
+        self
+    }
+    fn request(&self, method: Method, endpoint: &'static str) -> Result<RequestBuilder, WebError> {
+        let url = Url::parse(&self.base_url)?.join(endpoint)?;
+        Ok(self
+            .transport
+            .client()
+            .request(method, url)
+            .bearer_auth(&self.api_key))
+    }
+    // posts to a Tavily endpoint and deserializes a successful response into its typed form,
+    // answering from the local cache while an identical request is still fresh.
+    // usage is always requested so the credits each tool spends land in the ledger
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        tool: &'static str,
+        endpoint: &'static str,
+        args: &impl Serialize,
+    ) -> Result<Cached<T>, WebError> {
+        let json = serde_json::to_value(args).map_err(|source| WebError::Request {
+            provider: PROVIDER,
+            endpoint,
+            source,
+        })?;
+        if let Some((response, age)) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(endpoint, &json))
+            && let Ok(value) = serde_json::from_value(response)
+        {
+            self.credits.record_cached(tool);
+            return Ok(Cached {
+                value,
+                age: Some(age),
+            });
+        }
+        let mut body = json.clone();
+        body["include_usage"] = Value::Bool(true);
+        let request = self.request(Method::POST, endpoint)?.json(&body);
+        let response = send(&self.transport, &self.limiter, PROVIDER, endpoint, request).await?;
+        self.credits.record(
+            tool,
+            response["usage"]["credits"].as_f64().unwrap_or_default(),
+        );
+        let value = T::deserialize(&response).map_err(|source| WebError::Schema {
+            provider: PROVIDER,
+            endpoint,
+            source,
+        })?;
+        // only responses that parsed are worth keeping, and a failed write just means no cache hit next time
+        if let Some(cache) = &self.cache {
+            let _ = cache.put(endpoint, &json, &response);
+        }
+        Ok(Cached::fresh(value))
+    }
+    // the key's and account's credit usage and limits
+    pub async fn usage(&self) -> Result<AccountUsage, WebError> {
+        let request = self.request(Method::GET, USAGE_PATH)?;
+        let response = send(
+            &self.transport,
+            &self.limiter,
+            PROVIDER,
+            USAGE_PATH,
+            request,
+        )
+        .await?;
+        serde_json::from_value(response).map_err(|source| WebError::Schema {
+            provider: PROVIDER,
+            endpoint: USAGE_PATH,
+            source,
+        })
+    }
+}
+
+impl WebSearchProvider for TavilyClient {
+    fn name(&self) -> &'static str {
+        "tavily"
+    }
+    fn search<'a>(&'a self, args: &'a SearchArgs) -> WebResult<'a, SearchResponse> {
+        Box::pin(self.call(Search::NAME, SEARCH_PATH, args))
+    }
+    fn extract<'a>(&'a self, args: &'a ExtractArgs) -> WebResult<'a, ExtractResponse> {
+        Box::pin(self.call(Extract::NAME, EXTRACT_PATH, args))
+    }
+    fn crawl<'a>(&'a self, args: &'a CrawlArgs) -> WebResult<'a, CrawlResponse> {
+        Box::pin(self.call(Crawl::NAME, CRAWL_PATH, args))
+    }
+    fn map<'a>(&'a self, args: &'a MapArgs) -> WebResult<'a, MapResponse> {
+        Box::pin(self.call(Map::NAME, MAP_PATH, args))
+    }
+    fn cache(&self) -> Option<&ResponseCache> {
+        self.cache.as_ref()
+    }
+    fn credits(&self) -> Option<&CreditLedger> {
+        Some(&self.credits)
+    }
+    fn account_usage(&self) -> BoxFuture<'_, Result<AccountUsage, WebError>> {
+        Box::pin(self.usage())
     }
 }
 
 impl From<&Config> for TavilyClient {
     fn from(value: &Config) -> Self {
-        TavilyClient::new(value.tavily_api_key())
+        TavilyClient::new(
+            value.transport().clone(),
+            value.tavily_api_key(),
+            value.tavily_base_url(),
+        )
+        .with_cache_dir(value.cache_dir())
+        .with_rate_limit(value.web_rate_limit())
     }
 }
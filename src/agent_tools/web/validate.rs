@@ -0,0 +1,228 @@
+use crate::agent_tools::web::error::WebError;
+use chrono::NaiveDate;
+use std::fmt::Display;
+
+// the date format Tavily takes, as a JSON schema pattern
+pub const DATE_PATTERN: &str = r"^\d{4}-\d{2}-\d{2}$";
+
+// arguments checked before a request is sent, so the agent gets an error it can correct
+// instead of an opaque 400 from the API
+pub trait Validate {
+    fn validate(&self) -> Result<(), WebError>;
+}
+
+pub fn range<T: PartialOrd + Display + Copy>(
+    field: &'static str,
+    value: Option<T>,
+    min: T,
+    max: T,
+) -> Result<(), WebError> {
+    match value {
+        Some(value) if value < min || value > max => Err(WebError::Argument {
+            field,
+            reason: format!("must be between {} and {}, got {}", min, max, value),
+        }),
+        _ => Ok(()),
+    }
+}
+
+pub fn at_least<T: PartialOrd + Display + Copy>(
+    field: &'static str,
+    value: Option<T>,
+    min: T,
+) -> Result<(), WebError> {
+    match value {
+        Some(value) if value < min => Err(WebError::Argument {
+            field,
+            reason: format!("must be at least {}, got {}", min, value),
+        }),
+        _ => Ok(()),
+    }
+}
+
+pub fn max_items<T>(field: &'static str, items: Option<&[T]>, max: usize) -> Result<(), WebError> {
+    match items {
+        Some(items) if items.len() > max => Err(WebError::Argument {
+            field,
+            reason: format!("takes at most {} entries, got {}", max, items.len()),
+        }),
+        _ => Ok(()),
+    }
+}
+
+pub fn not_empty<T>(field: &'static str, items: &[T]) -> Result<(), WebError> {
+    if items.is_empty() {
+        return Err(WebError::Argument {
+            field,
+            reason: "needs at least one entry".to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn date(field: &'static str, value: Option<&str>) -> Result<Option<NaiveDate>, WebError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let valid = value.len() == 10 && value.as_bytes()[4] == b'-' && value.as_bytes()[7] == b'-';
+    valid
+        .then(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok())
+        .flatten()
+        .map(Some)
+        .ok_or_else(|| WebError::Argument {
+            field,
+            reason: format!("must be a date in YYYY-MM-DD format, got '{}'", value),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_tools::web::{
+        crawl::request::CrawlArgs, extract::request::ExtractArgs, fetch::request::FetchArgs,
+        map::request::MapArgs, search::request::SearchArgs,
+    };
+    use schemars::{schema_for, JsonSchema};
+    use serde::de::DeserializeOwned;
+    use serde_json::{json, Value};
+
+    fn error<T: Validate + DeserializeOwned>(args: Value) -> Option<String> {
+        serde_json::from_value::<T>(args)
+            .unwrap()
+            .validate()
+            .err()
+            .map(|e| e.to_string())
+    }
+
+    fn bounds<T: JsonSchema>(field: &str) -> (Value, Value) {
+        let schema = serde_json::to_value(schema_for!(T)).unwrap();
+        let property = &schema["properties"][field];
+        (property["minimum"].clone(), property["maximum"].clone())
+    }
+
+    #[test]
+    fn test_search_bounds() {
+        let search = |args: Value| error::<SearchArgs>(json!({ "query": "rust" }).merged(args));
+        assert_eq!(search(json!({ "chunks_per_source": 1 })), None);
+        assert_eq!(search(json!({ "chunks_per_source": 3 })), None);
+        assert_eq!(
+            search(json!({ "chunks_per_source": 0 })).unwrap(),
+            "Invalid chunks_per_source: must be between 1 and 3, got 0"
+        );
+        assert!(search(json!({ "chunks_per_source": 4 })).is_some());
+        assert_eq!(search(json!({ "max_results": 0 })), None);
+        assert_eq!(search(json!({ "max_results": 20 })), None);
+        assert_eq!(
+            search(json!({ "max_results": 21 })).unwrap(),
+            "Invalid max_results: must be between 0 and 20, got 21"
+        );
+        let domains = |count: usize| json!(vec!["example.com"; count]);
+        assert_eq!(search(json!({ "include_domains": domains(300) })), None);
+        assert_eq!(
+            search(json!({ "include_domains": domains(301) })).unwrap(),
+            "Invalid include_domains: takes at most 300 entries, got 301"
+        );
+        assert_eq!(search(json!({ "exclude_domains": domains(150) })), None);
+        assert!(search(json!({ "exclude_domains": domains(151) })).is_some());
+        assert_eq!(search(json!({ "start_date": "2024-02-29" })), None);
+        assert_eq!(
+            search(json!({ "start_date": "2023-02-29" })).unwrap(),
+            "Invalid start_date: must be a date in YYYY-MM-DD format, got '2023-02-29'"
+        );
+        assert!(search(json!({ "end_date": "2024/01/01" })).is_some());
+        assert!(search(json!({ "end_date": "2024-1-1" })).is_some());
+        assert_eq!(
+            search(json!({ "start_date": "2024-06-01", "end_date": "2024-01-01" })).unwrap(),
+            "Invalid start_date: must not be after end_date (2024-06-01 > 2024-01-01)"
+        );
+        assert_eq!(bounds::<SearchArgs>("max_results"), (json!(0), json!(20)));
+        assert_eq!(
+            bounds::<SearchArgs>("chunks_per_source"),
+            (json!(1), json!(3))
+        );
+        let schema = serde_json::to_value(schema_for!(SearchArgs)).unwrap();
+        assert_eq!(schema["properties"]["start_date"]["pattern"], DATE_PATTERN);
+        assert_eq!(schema["properties"]["include_domains"]["maxItems"], 300);
+    }
+
+    #[test]
+    fn test_extract_and_fetch_bounds() {
+        let extract = |args: Value| error::<ExtractArgs>(args);
+        let urls = |count: usize| json!(vec!["https://example.com"; count]);
+        assert_eq!(extract(json!({ "urls": urls(20) })), None);
+        assert_eq!(
+            extract(json!({ "urls": [] })).unwrap(),
+            "Invalid urls: needs at least one entry"
+        );
+        assert!(extract(json!({ "urls": urls(21) })).is_some());
+        assert_eq!(
+            extract(json!({ "urls": urls(1), "chunks_per_source": 5 })),
+            None
+        );
+        assert!(extract(json!({ "urls": urls(1), "chunks_per_source": 6 })).is_some());
+        assert_eq!(extract(json!({ "urls": urls(1), "timeout": 1.0 })), None);
+        assert_eq!(extract(json!({ "urls": urls(1), "timeout": 60.0 })), None);
+        assert_eq!(
+            extract(json!({ "urls": urls(1), "timeout": 0.5 })).unwrap(),
+            "Invalid timeout: must be between 1 and 60, got 0.5"
+        );
+        assert!(extract(json!({ "urls": urls(1), "timeout": 61.0 })).is_some());
+        assert_eq!(bounds::<ExtractArgs>("timeout"), (json!(1.0), json!(60.0)));
+        let fetch = |args: Value| error::<FetchArgs>(args);
+        assert_eq!(fetch(json!({ "urls": urls(20), "timeout": 60.0 })), None);
+        assert!(fetch(json!({ "urls": [] })).is_some());
+        assert!(fetch(json!({ "urls": urls(21) })).is_some());
+        assert!(fetch(json!({ "urls": urls(1), "timeout": 0.0 })).is_some());
+    }
+
+    #[test]
+    fn test_crawl_and_map_bounds() {
+        let url = json!({ "url": "https://example.com" });
+        let crawl = |args: Value| error::<CrawlArgs>(url.merged(args));
+        let map = |args: Value| error::<MapArgs>(url.merged(args));
+        for check in [&crawl as &dyn Fn(Value) -> Option<String>, &map] {
+            assert_eq!(
+                check(json!({ "max_depth": 1, "max_breadth": 1, "limit": 1 })),
+                None
+            );
+            assert_eq!(check(json!({ "max_depth": 5, "max_breadth": 500 })), None);
+            assert_eq!(
+                check(json!({ "max_depth": 0 })).unwrap(),
+                "Invalid max_depth: must be between 1 and 5, got 0"
+            );
+            assert!(check(json!({ "max_depth": 6 })).is_some());
+            assert!(check(json!({ "max_breadth": 0 })).is_some());
+            assert_eq!(
+                check(json!({ "max_breadth": 501 })).unwrap(),
+                "Invalid max_breadth: must be between 1 and 500, got 501"
+            );
+            assert_eq!(
+                check(json!({ "limit": 0 })).unwrap(),
+                "Invalid limit: must be at least 1, got 0"
+            );
+            assert_eq!(check(json!({ "timeout": 10.0 })), None);
+            assert_eq!(check(json!({ "timeout": 150.0 })), None);
+            assert!(check(json!({ "timeout": 9.5 })).is_some());
+            assert!(check(json!({ "timeout": 150.5 })).is_some());
+        }
+        assert_eq!(crawl(json!({ "chunks_per_source": 5 })), None);
+        assert!(crawl(json!({ "chunks_per_source": 0 })).is_some());
+        assert_eq!(bounds::<CrawlArgs>("max_depth"), (json!(1), json!(5)));
+        assert_eq!(bounds::<MapArgs>("max_breadth"), (json!(1), json!(500)));
+        assert_eq!(bounds::<MapArgs>("timeout"), (json!(10.0), json!(150.0)));
+    }
+
+    trait Merged {
+        fn merged(&self, other: Value) -> Value;
+    }
+
+    impl Merged for Value {
+        fn merged(&self, other: Value) -> Value {
+            let mut merged = self.clone();
+            if let (Some(merged), Value::Object(other)) = (merged.as_object_mut(), other) {
+                merged.extend(other);
+            }
+            merged
+        }
+    }
+}
@@ -2,7 +2,6 @@ use crate::{
     anthropic::{ModelInfo, ModelsResponse},
     chat::config::Config,
 };
-use reqwest::Client;
 
 pub trait GetAnthropicModels {
     fn get_models(&self) -> impl Future<Output = anyhow::Result<Vec<ModelInfo>>>;
@@ -10,12 +9,14 @@ pub trait GetAnthropicModels {
 
 impl GetAnthropicModels for Config {
     async fn get_models(&self) -> anyhow::Result<Vec<ModelInfo>> {
-        let response = Client::new()
-            .get("https://api.anthropic.com/v1/models")
+        let request = self
+            .transport()
+            .client()
+            .get(format!("{}/v1/models", self.anthropic_base_url()))
             .header("anthropic-version", "2023-06-01")
             .header("X-Api-Key", self.anthropic_api_key())
-            .send()
-            .await?;
+            .build()?;
+        let response = self.transport().execute(request).await?;
         let body = response.json::<serde_json::Value>().await?;
         let models_response: ModelsResponse = serde_json::from_value(body)?;
         Ok(models_response.data)
@@ -0,0 +1,134 @@
+use crate::cassette::HttpTransport;
+use bytes::Bytes;
+use reqwest::header::RETRY_AFTER;
+use rig::{
+    http_client::{
+        self, sse::BoxedStream, HttpClientExt, LazyBody, MultipartForm, Request, Response,
+        StreamingResponse,
+    },
+    wasm_compat::WasmCompatSend,
+};
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Default)]
+pub struct AnthropicHttpClient {
+    transport: HttpTransport,
+}
+
+impl AnthropicHttpClient {
+    pub fn new(transport: HttpTransport) -> Self {
+        Self { transport }
+    }
+}
+
+fn instance_error(e: impl std::error::Error + Send + Sync + 'static) -> http_client::Error {
+    http_client::Error::Instance(Box::new(e))
+}
+
+async fn check_status(response: reqwest::Response) -> http_client::Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    let body = response.text().await.map_err(instance_error)?;
+    let message = match (retry_after, serde_json::from_str::<Value>(&body)) {
+        (Some(secs), Ok(Value::Object(mut map))) => {
+            map.insert("retry_after".to_string(), secs.into());
+            Value::Object(map).to_string()
+        }
+        (Some(secs), _) => json!({ "retry_after": secs, "error": { "message": body } }).to_string(),
+        (None, _) => body,
+    };
+    Err(http_client::Error::InvalidStatusCodeWithMessage(
+        status, message,
+    ))
+}
+
+impl HttpClientExt for AnthropicHttpClient {
+    fn send<T, U>(
+        &self,
+        req: Request<T>,
+    ) -> impl Future<Output = http_client::Result<Response<LazyBody<U>>>> + WasmCompatSend + 'static
+    where
+        T: Into<Bytes>,
+        T: WasmCompatSend,
+        U: From<Bytes>,
+        U: WasmCompatSend + 'static,
+    {
+        let (parts, body) = req.into_parts();
+        let req = self
+            .transport
+            .client()
+            .request(parts.method, parts.uri.to_string())
+            .headers(parts.headers)
+            .body(body.into())
+            .build();
+        let transport = self.transport.clone();
+        async move {
+            let req = req.map_err(instance_error)?;
+            let response =
+                check_status(transport.execute(req).await.map_err(instance_error)?).await?;
+            let mut res = Response::builder().status(response.status());
+            if let Some(headers) = res.headers_mut() {
+                *headers = response.headers().clone();
+            }
+            let body: LazyBody<U> = Box::pin(async {
+                let bytes = response.bytes().await.map_err(instance_error)?;
+                Ok(U::from(bytes))
+            });
+            res.body(body).map_err(http_client::Error::Protocol)
+        }
+    }
+
+    fn send_multipart<U>(
+        &self,
+        req: Request<MultipartForm>,
+    ) -> impl Future<Output = http_client::Result<Response<LazyBody<U>>>> + WasmCompatSend + 'static
+    where
+        U: From<Bytes>,
+        U: WasmCompatSend + 'static,
+    {
+        self.transport.client().send_multipart(req)
+    }
+
+    fn send_streaming<T>(
+        &self,
+        req: Request<T>,
+    ) -> impl Future<Output = http_client::Result<StreamingResponse>> + WasmCompatSend
+    where
+        T: Into<Bytes>,
+    {
+        let (parts, body) = req.into_parts();
+        let req = self
+            .transport
+            .client()
+            .request(parts.method, parts.uri.to_string())
+            .headers(parts.headers)
+            .body(body.into())
+            .build();
+        let transport = self.transport.clone();
+        async move {
+            use futures::StreamExt;
+            let req = req.map_err(instance_error)?;
+            let response =
+                check_status(transport.execute(req).await.map_err(instance_error)?).await?;
+            let mut res = Response::builder()
+                .status(response.status())
+                .version(response.version());
+            if let Some(headers) = res.headers_mut() {
+                *headers = response.headers().clone();
+            }
+            let stream: BoxedStream = Box::pin(
+                response
+                    .bytes_stream()
+                    .map(|chunk| chunk.map_err(instance_error)),
+            );
+            res.body(stream).map_err(http_client::Error::Protocol)
+        }
+    }
+}
@@ -1,5 +1,9 @@
 pub mod get_models;
+pub mod http;
+pub mod pricing;
 
+use http::AnthropicHttpClient;
+use rig::providers::anthropic::{completion::CompletionModel, Client};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -18,3 +22,6 @@ pub struct ModelInfo {
     pub display_name: String,
     pub r#type: String,
 }
+
+pub type AnthropicClient = Client<AnthropicHttpClient>;
+pub type AnthropicModel = CompletionModel<AnthropicHttpClient>;
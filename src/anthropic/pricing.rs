@@ -0,0 +1,44 @@
+pub const CONTEXT_WINDOW: usize = 200_000;
+pub const DEFAULT_MAX_OUTPUT_TOKENS: u64 = 8192;
+
+// max output tokens, matched by model id prefix
+const MAX_OUTPUT_TOKENS: &[(&str, u64)] = &[
+    ("claude-opus-4", 32_000),
+    ("claude-sonnet-4", 64_000),
+    ("claude-haiku-4", 64_000),
+    ("claude-3-7-sonnet", 64_000),
+    ("claude-3-5", 8192),
+    ("claude-3", 4096),
+];
+
+// USD per million (input, output) tokens, matched by model id prefix
+const PRICES: &[(&str, f64, f64)] = &[
+    ("claude-opus-4-5", 5.0, 25.0),
+    ("claude-opus-4-6", 5.0, 25.0),
+    ("claude-opus", 15.0, 75.0),
+    ("claude-3-opus", 15.0, 75.0),
+    ("claude-sonnet", 3.0, 15.0),
+    ("claude-3-7-sonnet", 3.0, 15.0),
+    ("claude-3-5-sonnet", 3.0, 15.0),
+    ("claude-haiku", 1.0, 5.0),
+    ("claude-3-5-haiku", 0.8, 4.0),
+    ("claude-3-haiku", 0.25, 1.25),
+];
+
+pub fn cost_usd(model_id: &str, input_tokens: usize, output_tokens: usize) -> f64 {
+    PRICES
+        .iter()
+        .find(|(prefix, ..)| model_id.starts_with(prefix))
+        .map(|(_, input, output)| {
+            (input_tokens as f64 * input + output_tokens as f64 * output) / 1_000_000.0
+        })
+        .unwrap_or(0.0)
+}
+
+pub fn max_output_tokens(model_id: &str) -> u64 {
+    MAX_OUTPUT_TOKENS
+        .iter()
+        .find(|(prefix, _)| model_id.starts_with(prefix))
+        .map(|(_, max_tokens)| *max_tokens)
+        .unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS)
+}
@@ -0,0 +1,405 @@
+use bytes::Bytes;
+use futures::StreamExt;
+use reqwest::{header::HeaderMap, Body, Client, Request, Response, ResponseBuilderExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use thiserror::Error;
+use url::Url;
+
+const REDACTED: &str = "[REDACTED]";
+const REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "x-api-key",
+    "x-subscription-token",
+    "cookie",
+    "set-cookie",
+];
+const REDACTED_BODY_FIELDS: &[&str] = &["api_key"];
+const SKIPPED_RESPONSE_HEADERS: &[&str] =
+    &["content-length", "content-encoding", "transfer-encoding"];
+
+#[derive(Debug, Error)]
+pub enum TransportError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("Cassette error: {0}")]
+    Cassette(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    headers: BTreeMap<String, String>,
+    body: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug)]
+enum Cassette {
+    Record {
+        dir: PathBuf,
+        next: AtomicUsize,
+    },
+    Replay {
+        interactions: Mutex<Vec<Option<Interaction>>>,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HttpTransport {
+    client: Client,
+    cassette: Option<Arc<Cassette>>,
+}
+
+fn recorded_headers(headers: &HeaderMap, skipped: &[&str]) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter(|(name, _)| !skipped.contains(&name.as_str()))
+        .map(|(name, value)| {
+            let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).to_string()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+fn recorded_body(bytes: &[u8]) -> Value {
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(Value::Object(mut map)) => {
+            for field in REDACTED_BODY_FIELDS {
+                if let Some(value) = map.get_mut(*field) {
+                    *value = Value::String(REDACTED.to_string());
+                }
+            }
+            Value::Object(map)
+        }
+        Ok(value) => value,
+        Err(_) => Value::String(String::from_utf8_lossy(bytes).to_string()),
+    }
+}
+
+impl From<&Request> for RecordedRequest {
+    fn from(request: &Request) -> Self {
+        let url = request.url();
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        Self {
+            method: request.method().to_string(),
+            path,
+            headers: recorded_headers(request.headers(), &[]),
+            body: request.body().and_then(Body::as_bytes).map(recorded_body),
+        }
+    }
+}
+
+impl RecordedResponse {
+    // redirects are not recorded, so the response claims the URL that was requested
+    fn into_response(self, url: Url) -> Result<Response, TransportError> {
+        let mut builder = http::Response::builder().status(self.status).url(url);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let body = match self.body {
+            Value::String(body) => body,
+            body => body.to_string(),
+        };
+        builder
+            .body(body)
+            .map(Response::from)
+            .map_err(|e| TransportError::Cassette(e.to_string()))
+    }
+}
+
+impl Cassette {
+    fn take_match(&self, request: &RecordedRequest) -> Option<Interaction> {
+        let Self::Replay { interactions } = self else {
+            return None;
+        };
+        let mut interactions = interactions.lock().unwrap();
+        let same_route = |interaction: &Interaction| {
+            interaction.request.method == request.method && interaction.request.path == request.path
+        };
+        let index = interactions
+            .iter()
+            .position(|interaction| {
+                interaction.as_ref().is_some_and(|interaction| {
+                    same_route(interaction) && interaction.request.body == request.body
+                })
+            })
+            .or_else(|| {
+                interactions
+                    .iter()
+                    .position(|interaction| interaction.as_ref().is_some_and(same_route))
+            })?;
+        interactions[index].take()
+    }
+}
+
+struct Recording {
+    path: PathBuf,
+    request: RecordedRequest,
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl Recording {
+    fn write(&self) -> anyhow::Result<()> {
+        let interaction = Interaction {
+            request: self.request.clone(),
+            response: RecordedResponse {
+                status: self.status,
+                headers: self.headers.clone(),
+                body: recorded_body(&self.body),
+            },
+        };
+        let file = std::fs::File::create(&self.path)?;
+        serde_json::to_writer_pretty(file, &interaction)?;
+        Ok(())
+    }
+}
+
+// streaming callers may stop reading before the body ends, so the pair is written once the body is dropped
+impl Drop for Recording {
+    fn drop(&mut self) {
+        let _ = self.write();
+    }
+}
+
+fn record_response(path: PathBuf, request: RecordedRequest, response: Response) -> Response {
+    let status = response.status();
+    let url = response.url().clone();
+    let headers = response.headers().clone();
+    let mut recording = Recording {
+        path,
+        request,
+        status: status.as_u16(),
+        headers: recorded_headers(&headers, SKIPPED_RESPONSE_HEADERS),
+        body: Vec::new(),
+    };
+    let chunks = response.bytes_stream().map(move |chunk| {
+        let chunk = chunk?;
+        recording.body.extend_from_slice(&chunk);
+        Ok::<Bytes, reqwest::Error>(chunk)
+    });
+    let mut builder = http::Response::builder().status(status).url(url);
+    if let Some(builder_headers) = builder.headers_mut() {
+        *builder_headers = headers;
+    }
+    builder
+        .body(Body::wrap_stream(chunks))
+        .map(Response::from)
+        .expect("status and headers come from a valid response")
+}
+
+impl HttpTransport {
+    pub fn record(dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        if std::fs::read_dir(&dir)?.next().is_some() {
+            anyhow::bail!("Cassette directory {} is not empty", dir.display());
+        }
+        Ok(Self {
+            client: Client::new(),
+            cassette: Some(Arc::new(Cassette::Record {
+                dir,
+                next: AtomicUsize::new(0),
+            })),
+        })
+    }
+    pub fn replay(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir.as_ref())?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|ent| ent.path())
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+            .collect();
+        paths.sort();
+        let interactions = paths
+            .iter()
+            .map(|path| {
+                let file = std::fs::File::open(path)?;
+                Ok(Some(serde_json::from_reader(file)?))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            client: Client::new(),
+            cassette: Some(Arc::new(Cassette::Replay {
+                interactions: Mutex::new(interactions),
+            })),
+        })
+    }
+    // the same recording or replay, through a client configured by the caller
+    pub fn with_client(&self, client: Client) -> Self {
+        Self {
+            client,
+            cassette: self.cassette.clone(),
+        }
+    }
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+    pub async fn execute(&self, request: Request) -> Result<Response, TransportError> {
+        match self.cassette.as_deref() {
+            None => Ok(self.client.execute(request).await?),
+            Some(Cassette::Record { dir, next }) => {
+                let path = dir.join(format!("{:04}.json", next.fetch_add(1, Ordering::SeqCst)));
+                let recorded = RecordedRequest::from(&request);
+                let response = self.client.execute(request).await?;
+                Ok(record_response(path, recorded, response))
+            }
+            Some(cassette @ Cassette::Replay { .. }) => {
+                let url = request.url().clone();
+                let recorded = RecordedRequest::from(&request);
+                let interaction = cassette.take_match(&recorded).ok_or_else(|| {
+                    TransportError::Cassette(format!(
+                        "No recorded response for {} {}",
+                        recorded.method, recorded.path
+                    ))
+                })?;
+                interaction.response.into_response(url)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        agent_tools::web::provider::ProviderKind,
+        anthropic::get_models::GetAnthropicModels,
+        chat::config::Config,
+        frontend::ChatEvent,
+        testing::{streamed_text, text, tool_use, Harness},
+    };
+    use rig::message::Message;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let harness = Harness::start().await;
+        harness.models().await;
+        harness
+            .tavily(
+                "/search",
+                json!({
+                    "query": "rust",
+                    "results": [{ "title": "Recorded result", "url": "https://www.rust-lang.org", "content": "A language empowering everyone", "score": 0.9 }]
+                }),
+            )
+            .await;
+        harness
+            .expect_stream(vec![tool_use(
+                "toolu_1",
+                "search_web",
+                json!({ "query": "rust" }),
+            )])
+            .await;
+        harness.expect_stream(vec![text("Recorded answer")]).await;
+        let cassette_dir = tempfile::tempdir().unwrap();
+
+        let config = harness
+            .config()
+            .with_transport(HttpTransport::record(cassette_dir.path()).unwrap());
+        let recorded_models = config.get_models().await.unwrap();
+        let (mut chat, _) = harness.chat_with_config(0, config, &[]);
+        chat.stream(Message::user("search rust")).await.unwrap();
+        let files: Vec<String> = std::fs::read_dir(cassette_dir.path())
+            .unwrap()
+            .map(|ent| std::fs::read_to_string(ent.unwrap().path()).unwrap())
+            .collect();
+        assert_eq!(files.len(), 4);
+        assert!(files
+            .iter()
+            .all(|file| !file.contains("test-anthropic-key") && !file.contains("test-tavily-key")));
+        assert!(files.iter().all(|file| file.contains(REDACTED)));
+
+        let config = Config::new("replay-key", "replay-key")
+            .with_anthropic_base_url("http://127.0.0.1:9")
+            .with_tavily_base_url("http://127.0.0.1:9")
+            .with_cache_dir(harness.chats_dir().join("replay-cache"))
+            .with_transport(HttpTransport::replay(cassette_dir.path()).unwrap());
+        let replayed_models = config.get_models().await.unwrap();
+        assert_eq!(replayed_models.len(), recorded_models.len());
+        let (mut chat, events) = harness.chat_with_config(0, config, &[]);
+        chat.stream(Message::user("search rust")).await.unwrap();
+        assert_eq!(streamed_text(&events), "Recorded answer");
+        assert!(events.lock().unwrap().iter().any(|event| matches!(
+            event,
+            ChatEvent::ToolFinished { output, is_error: false, .. } if output.contains("Recorded result")
+        )));
+        let error = chat.stream(Message::user("one more")).await.unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("No recorded response for POST /v1/messages"));
+    }
+
+    #[tokio::test]
+    async fn test_record_redacts_brave_key() {
+        let harness = Harness::start().await;
+        harness
+            .tavily_get(
+                "/res/v1/web/search",
+                json!({ "query": { "original": "rust" }, "web": { "results": [] } }),
+            )
+            .await;
+        harness
+            .expect_stream(vec![tool_use(
+                "toolu_1",
+                "search_web",
+                json!({ "query": "rust" }),
+            )])
+            .await;
+        harness.expect_stream(vec![text("Done.")]).await;
+        let cassette_dir = tempfile::tempdir().unwrap();
+        let config = harness
+            .config()
+            .with_web_provider(ProviderKind::Brave)
+            .with_brave_api_key("test-brave-key")
+            .with_brave_base_url(harness.uri())
+            .with_transport(HttpTransport::record(cassette_dir.path()).unwrap());
+        let (mut chat, _) = harness.chat_with_config(0, config, &[]);
+        chat.stream(Message::user("find rust")).await.unwrap();
+        let files: Vec<String> = std::fs::read_dir(cassette_dir.path())
+            .unwrap()
+            .map(|ent| std::fs::read_to_string(ent.unwrap().path()).unwrap())
+            .collect();
+        assert_eq!(files.len(), 3);
+        assert!(files.iter().any(|file| file.contains("/res/v1/web/search")));
+        assert!(files.iter().all(|file| !file.contains("test-brave-key")));
+    }
+
+    #[test]
+    fn test_record_requires_empty_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("0000.json"), "{}").unwrap();
+        assert!(HttpTransport::record(dir.path()).is_err());
+    }
+}
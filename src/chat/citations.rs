@@ -0,0 +1,111 @@
+use crate::agent_tools::web::sources::{Source, Sources};
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+// a held back `[` that hasn't closed within this many bytes isn't a citation
+const MAX_MARKER_LEN: usize = 64;
+
+static MARKER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[(S\d+(?:\s*,\s*S\d+)*)\]").unwrap());
+
+// turns the agent's [S3] style citations into footnote numbers as its answer streams in,
+// holding back a citation split across deltas until it closes
+#[derive(Debug, Default)]
+pub struct Citations {
+    held: String,
+    cited: Vec<Source>,
+}
+
+impl Citations {
+    pub fn push(&mut self, delta: &str, sources: &Sources) -> String {
+        self.held.push_str(delta);
+        let split = self
+            .held
+            .rfind('[')
+            .filter(|open| {
+                !self.held[*open..].contains(']') && self.held.len() - open < MAX_MARKER_LEN
+            })
+            .unwrap_or(self.held.len());
+        let rest = self.held.split_off(split);
+        let ready = std::mem::replace(&mut self.held, rest);
+        self.rewrite(&ready, sources)
+    }
+    pub fn flush(&mut self, sources: &Sources) -> String {
+        let held = std::mem::take(&mut self.held);
+        self.rewrite(&held, sources)
+    }
+    // drops text held back from an answer that failed before it was shown
+    pub fn discard_held(&mut self) {
+        self.held.clear();
+    }
+    // citations of ids the session never handed out are left as written
+    fn rewrite(&mut self, text: &str, sources: &Sources) -> String {
+        MARKER
+            .replace_all(text, |captures: &Captures| {
+                let cited: Option<Vec<Source>> = captures[1]
+                    .split(',')
+                    .map(|id| sources.get(id.trim()))
+                    .collect();
+                let Some(cited) = cited else {
+                    return captures[0].to_string();
+                };
+                let numbers: Vec<String> = cited
+                    .into_iter()
+                    .map(|source| self.number(source).to_string())
+                    .collect();
+                format!("[{}]", numbers.join(", "))
+            })
+            .into_owned()
+    }
+    fn number(&mut self, source: Source) -> usize {
+        match self.cited.iter().position(|known| known.id == source.id) {
+            Some(index) => index + 1,
+            None => {
+                self.cited.push(source);
+                self.cited.len()
+            }
+        }
+    }
+    // numbered in the order the answer first cites them
+    pub fn footnotes(&self) -> Option<String> {
+        (!self.cited.is_empty()).then(|| {
+            self.cited
+                .iter()
+                .enumerate()
+                .map(|(i, source)| match &source.title {
+                    Some(title) => format!("[{}] {} <{}>", i + 1, title, source.url),
+                    None => format!("[{}] <{}>", i + 1, source.url),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_citations_become_footnotes() {
+        let sources = Sources::default();
+        sources.cite("https://doc.rust-lang.org/book", Some("The Book"));
+        sources.cite("https://blog.rust-lang.org", None);
+        let mut citations = Citations::default();
+        let mut text = String::new();
+        for delta in [
+            "Editions are opt-in [S",
+            "2]. Traits [S1, S",
+            "2] and [S9] [not a cite",
+        ] {
+            text.push_str(&citations.push(delta, &sources));
+        }
+        assert_eq!(text, "Editions are opt-in [1]. Traits [2, 1] and [S9] ");
+        text.push_str(&citations.flush(&sources));
+        assert!(text.ends_with("[S9] [not a cite"));
+        assert_eq!(
+            citations.footnotes().unwrap(),
+            "[1] <https://blog.rust-lang.org>\n[2] The Book <https://doc.rust-lang.org/book>"
+        );
+    }
+}
@@ -1,19 +1,269 @@
+use crate::{
+    agent_tools::{
+        shell::policy::CommandPolicy,
+        web::{
+            budget::DEFAULT_TOKEN_BUDGET, cache::CACHE_DIR_NAME, provider::ProviderKind,
+            rate_limit::RateLimit, robots::RobotsCache, snapshot::store::SNAPSHOTS_DIR_NAME,
+        },
+    },
+    cassette::HttpTransport,
+    chat::{
+        permissions::Permissions,
+        research::{ResearchLimits, RESEARCH_DIR_NAME},
+        CHATS_DIR_NAME, DEFAULT_MAX_PARALLEL_TOOLS,
+    },
+};
 use dotenvy::dotenv;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+pub const ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com";
+pub const TAVILY_BASE_URL: &str = "https://api.tavily.com";
+pub const BRAVE_BASE_URL: &str = "https://api.search.brave.com";
 
 pub struct Config {
     anthropic_api_key: String,
     tavily_api_key: String,
+    anthropic_base_url: String,
+    tavily_base_url: String,
+    web_provider: ProviderKind,
+    searxng_url: Option<String>,
+    brave_api_key: Option<String>,
+    brave_base_url: String,
+    chats_dir: PathBuf,
+    cache_dir: PathBuf,
+    research_dir: PathBuf,
+    snapshots_dir: PathBuf,
+    research_limits: ResearchLimits,
+    web_token_budget: usize,
+    // the provider's own limit unless overridden
+    web_rate_limit: Option<RateLimit>,
+    max_parallel_tools: usize,
+    rust_vendor_dir: Option<PathBuf>,
+    rust_docs_dirs: Vec<PathBuf>,
+    workspace_root: PathBuf,
+    command_policy: CommandPolicy,
+    permissions: Permissions,
+    transport: HttpTransport,
+    // every fetcher built from this config shares it
+    robots: Arc<RobotsCache>,
+    allow_private_hosts: bool,
 }
 
 impl Config {
+    pub fn new(anthropic_api_key: impl Into<String>, tavily_api_key: impl Into<String>) -> Self {
+        Self {
+            anthropic_api_key: anthropic_api_key.into(),
+            tavily_api_key: tavily_api_key.into(),
+            anthropic_base_url: ANTHROPIC_BASE_URL.to_string(),
+            tavily_base_url: TAVILY_BASE_URL.to_string(),
+            web_provider: ProviderKind::default(),
+            searxng_url: None,
+            brave_api_key: None,
+            brave_base_url: BRAVE_BASE_URL.to_string(),
+            chats_dir: PathBuf::from(CHATS_DIR_NAME),
+            cache_dir: PathBuf::from(CACHE_DIR_NAME),
+            research_dir: PathBuf::from(RESEARCH_DIR_NAME),
+            snapshots_dir: PathBuf::from(SNAPSHOTS_DIR_NAME),
+            research_limits: ResearchLimits::default(),
+            web_token_budget: DEFAULT_TOKEN_BUDGET,
+            web_rate_limit: None,
+            max_parallel_tools: DEFAULT_MAX_PARALLEL_TOOLS,
+            rust_vendor_dir: None,
+            rust_docs_dirs: Vec::new(),
+            workspace_root: PathBuf::from("."),
+            command_policy: CommandPolicy::default(),
+            permissions: Permissions::default(),
+            transport: HttpTransport::default(),
+            robots: Arc::default(),
+            allow_private_hosts: false,
+        }
+    }
     pub fn from_env() -> anyhow::Result<Self> {
         dotenv().ok();
         let anthropic_api_key = std::env::var("ANTHROPIC_API_KEY")?;
-        let tavily_api_key = std::env::var("TAVILY_API_KEY")?;
-        Ok(Self {
-            anthropic_api_key,
-            tavily_api_key,
-        })
+        let web_provider = match std::env::var("MARVIN_WEB_PROVIDER") {
+            Ok(provider) => provider.parse()?,
+            Err(_) => ProviderKind::default(),
+        };
+        // only the Tavily provider needs a Tavily key
+        let tavily_api_key = match web_provider {
+            ProviderKind::Tavily => std::env::var("TAVILY_API_KEY")?,
+            _ => std::env::var("TAVILY_API_KEY").unwrap_or_default(),
+        };
+        let mut config =
+            Self::new(anthropic_api_key, tavily_api_key).with_web_provider(web_provider);
+        if let Ok(url) = std::env::var("SEARXNG_URL") {
+            config = config.with_searxng_url(url);
+        }
+        if let Ok(api_key) = std::env::var("BRAVE_API_KEY") {
+            config = config.with_brave_api_key(api_key);
+        }
+        if let Ok(url) = std::env::var("BRAVE_BASE_URL") {
+            config = config.with_brave_base_url(url);
+        }
+        if let Ok(url) = std::env::var("ANTHROPIC_BASE_URL") {
+            config = config.with_anthropic_base_url(url);
+        }
+        if let Ok(url) = std::env::var("TAVILY_BASE_URL") {
+            config = config.with_tavily_base_url(url);
+        }
+        if let Ok(dir) = std::env::var("MARVIN_CHATS_DIR") {
+            config = config.with_chats_dir(dir);
+        }
+        if let Ok(dir) = std::env::var("MARVIN_CACHE_DIR") {
+            config = config.with_cache_dir(dir);
+        }
+        if let Ok(dir) = std::env::var("MARVIN_RESEARCH_DIR") {
+            config = config.with_research_dir(dir);
+        }
+        if let Ok(dir) = std::env::var("MARVIN_SNAPSHOTS_DIR") {
+            config = config.with_snapshots_dir(dir);
+        }
+        let mut research_limits = ResearchLimits::default();
+        if let Ok(credits) = std::env::var("MARVIN_RESEARCH_MAX_CREDITS") {
+            research_limits.max_credits = credits.parse()?;
+        }
+        if let Ok(turns) = std::env::var("MARVIN_RESEARCH_MAX_TURNS") {
+            research_limits.max_turns = turns.parse()?;
+        }
+        config = config.with_research_limits(research_limits);
+        if let Ok(budget) = std::env::var("MARVIN_WEB_TOKEN_BUDGET") {
+            config = config.with_web_token_budget(budget.parse()?);
+        }
+        let mut rate_limit = config.web_rate_limit();
+        if let Ok(requests) = std::env::var("MARVIN_WEB_MAX_CONCURRENT") {
+            rate_limit.max_concurrent = requests.parse()?;
+        }
+        if let Ok(per_second) = std::env::var("MARVIN_WEB_REQUESTS_PER_SECOND") {
+            rate_limit.per_second = Some(per_second.parse()?);
+        }
+        config = config.with_web_rate_limit(rate_limit);
+        if let Ok(allow) = std::env::var("MARVIN_WEB_ALLOW_PRIVATE_HOSTS") {
+            config = config.with_private_hosts_allowed(allow.parse()?);
+        }
+        if let Ok(calls) = std::env::var("MARVIN_MAX_PARALLEL_TOOLS") {
+            config = config.with_max_parallel_tools(calls.parse()?);
+        }
+        if let Ok(dir) = std::env::var("MARVIN_WORKSPACE_ROOT") {
+            config = config.with_workspace_root(dir);
+        }
+        let allowed = std::env::var("MARVIN_ALLOWED_COMMANDS").ok();
+        let denied = std::env::var("MARVIN_DENIED_COMMANDS").ok();
+        if allowed.is_some() || denied.is_some() {
+            let defaults = CommandPolicy::default();
+            let rules = |list: Option<String>, default: Vec<String>| match list {
+                Some(list) => list
+                    .split(',')
+                    .map(|rule| rule.trim().to_string())
+                    .collect(),
+                None => default,
+            };
+            let allowed = rules(allowed, defaults.allowed());
+            let denied = rules(denied, defaults.denied());
+            config = config.with_command_policy(CommandPolicy::new(
+                allowed.iter().map(String::as_str),
+                denied.iter().map(String::as_str),
+            ));
+        }
+        if let Ok(rules) = std::env::var("MARVIN_TOOL_PERMISSIONS") {
+            let mut permissions = Permissions::default();
+            permissions.apply_rules(&rules)?;
+            config = config.with_permissions(permissions);
+        }
+        if let Some(dirs) = std::env::var_os("MARVIN_RUST_DOCS_DIRS") {
+            config = config.with_rust_docs_dirs(std::env::split_paths(&dirs));
+        }
+        if let Ok(dir) = std::env::var("MARVIN_RUST_VENDOR_DIR") {
+            config = config.with_rust_vendor_dir(dir);
+        }
+        Ok(config)
+    }
+    pub fn with_anthropic_base_url(mut self, url: impl Into<String>) -> Self {
+        self.anthropic_base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+    pub fn with_tavily_base_url(mut self, url: impl Into<String>) -> Self {
+        self.tavily_base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+    pub fn with_web_provider(mut self, provider: ProviderKind) -> Self {
+        self.web_provider = provider;
+        self
+    }
+    pub fn with_searxng_url(mut self, url: impl Into<String>) -> Self {
+        self.searxng_url = Some(url.into().trim_end_matches('/').to_string());
+        self
+    }
+    pub fn with_brave_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.brave_api_key = Some(api_key.into());
+        self
+    }
+    pub fn with_brave_base_url(mut self, url: impl Into<String>) -> Self {
+        self.brave_base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+    pub fn with_chats_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.chats_dir = dir.into();
+        self
+    }
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = dir.into();
+        self
+    }
+    pub fn with_research_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.research_dir = dir.into();
+        self
+    }
+    pub fn with_snapshots_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.snapshots_dir = dir.into();
+        self
+    }
+    pub fn with_research_limits(mut self, limits: ResearchLimits) -> Self {
+        self.research_limits = limits;
+        self
+    }
+    pub fn with_web_token_budget(mut self, tokens: usize) -> Self {
+        self.web_token_budget = tokens;
+        self
+    }
+    pub fn with_web_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.web_rate_limit = Some(limit);
+        self
+    }
+    pub fn with_private_hosts_allowed(mut self, allow: bool) -> Self {
+        self.allow_private_hosts = allow;
+        self
+    }
+    pub fn with_max_parallel_tools(mut self, calls: usize) -> Self {
+        self.max_parallel_tools = calls.max(1);
+        self
+    }
+    pub fn with_workspace_root(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.workspace_root = dir.into();
+        self
+    }
+    pub fn with_command_policy(mut self, policy: CommandPolicy) -> Self {
+        self.command_policy = policy;
+        self
+    }
+    pub fn with_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+    pub fn with_rust_vendor_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.rust_vendor_dir = Some(dir.into());
+        self
+    }
+    pub fn with_rust_docs_dirs(mut self, dirs: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.rust_docs_dirs = dirs.into_iter().collect();
+        self
+    }
+    pub fn with_transport(mut self, transport: HttpTransport) -> Self {
+        self.transport = transport;
+        self
     }
     pub fn anthropic_api_key(&self) -> &str {
         &self.anthropic_api_key
@@ -21,4 +271,74 @@ impl Config {
     pub fn tavily_api_key(&self) -> &str {
         &self.tavily_api_key
     }
+    pub fn anthropic_base_url(&self) -> &str {
+        &self.anthropic_base_url
+    }
+    pub fn tavily_base_url(&self) -> &str {
+        &self.tavily_base_url
+    }
+    pub fn web_provider(&self) -> ProviderKind {
+        self.web_provider
+    }
+    pub fn searxng_url(&self) -> Option<&str> {
+        self.searxng_url.as_deref()
+    }
+    pub fn brave_api_key(&self) -> Option<&str> {
+        self.brave_api_key.as_deref()
+    }
+    pub fn brave_base_url(&self) -> &str {
+        &self.brave_base_url
+    }
+    pub fn chats_dir(&self) -> &Path {
+        &self.chats_dir
+    }
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+    pub fn research_dir(&self) -> &Path {
+        &self.research_dir
+    }
+    pub fn snapshots_dir(&self) -> &Path {
+        &self.snapshots_dir
+    }
+    pub fn research_limits(&self) -> ResearchLimits {
+        self.research_limits
+    }
+    pub fn web_token_budget(&self) -> usize {
+        self.web_token_budget
+    }
+    pub fn web_rate_limit(&self) -> RateLimit {
+        self.web_rate_limit
+            .unwrap_or(self.web_provider.rate_limit())
+    }
+    pub fn allow_private_hosts(&self) -> bool {
+        self.allow_private_hosts
+    }
+    pub fn max_parallel_tools(&self) -> usize {
+        self.max_parallel_tools
+    }
+    pub fn workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+    pub fn command_policy(&self) -> &CommandPolicy {
+        &self.command_policy
+    }
+    pub fn permissions(&self) -> &Permissions {
+        &self.permissions
+    }
+    pub fn rust_vendor_dir(&self) -> Option<&Path> {
+        self.rust_vendor_dir.as_deref()
+    }
+    // configured directories first, then the workspace's own `cargo rustdoc` output
+    pub fn rust_docs_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = self.rust_docs_dirs.clone();
+        dirs.push(self.workspace_root.join("target").join("doc"));
+        dirs
+    }
+    pub fn transport(&self) -> &HttpTransport {
+        &self.transport
+    }
+    pub fn robots(&self) -> &Arc<RobotsCache> {
+        &self.robots
+    }
 }
@@ -0,0 +1,148 @@
+use rig::completion::{CompletionError, PromptError};
+use serde_json::Value;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ChatError {
+    #[error("Anthropic is overloaded: {0}")]
+    Overloaded(String),
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    #[error("Anthropic API error: {0}")]
+    Server(String),
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("Authentication failed: {0}")]
+    Auth(String),
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Agent exceeded {0} turns without a final response")]
+    MaxTurns(usize),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl ChatError {
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::Overloaded(_) | Self::RateLimited { .. } | Self::Server(_) | Self::Network(_)
+        )
+    }
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+    pub fn classify(error: impl ToString) -> Self {
+        let error = error.to_string();
+        let status = error
+            .split_once("Invalid status code ")
+            .and_then(|(_, rest)| rest.get(..3))
+            .and_then(|code| code.parse::<u16>().ok());
+        let body = error.find('{').and_then(|start| {
+            serde_json::Deserializer::from_str(&error[start..])
+                .into_iter::<Value>()
+                .next()
+                .and_then(|body| body.ok())
+        });
+        let error_type = body
+            .as_ref()
+            .and_then(|body| body.pointer("/error/type"))
+            .and_then(Value::as_str);
+        let message = body
+            .as_ref()
+            .and_then(|body| body.pointer("/error/message"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| error.clone());
+        let retry_after = body
+            .as_ref()
+            .and_then(|body| body.get("retry_after"))
+            .and_then(Value::as_u64)
+            .map(Duration::from_secs);
+        match (error_type, status) {
+            (Some("overloaded_error"), _) | (_, Some(529)) => Self::Overloaded(message),
+            (Some("rate_limit_error"), _) | (_, Some(429)) => Self::RateLimited {
+                message,
+                retry_after,
+            },
+            (Some("authentication_error" | "permission_error"), _) | (_, Some(401 | 403)) => {
+                Self::Auth(message)
+            }
+            (Some("invalid_request_error" | "not_found_error" | "request_too_large"), _)
+            | (_, Some(400 | 404 | 413)) => Self::InvalidRequest(message),
+            (Some("api_error"), _) | (_, Some(500..=599)) => Self::Server(message),
+            _ if is_network_error(&error) => Self::Network(message),
+            _ => Self::Other(message),
+        }
+    }
+}
+
+fn is_network_error(error: &str) -> bool {
+    let error = error.to_lowercase();
+    [
+        "error sending request",
+        "connection",
+        "timed out",
+        "dns error",
+        "error decoding response body",
+    ]
+    .iter()
+    .any(|pattern| error.contains(pattern))
+}
+
+impl From<CompletionError> for ChatError {
+    fn from(value: CompletionError) -> Self {
+        Self::classify(value)
+    }
+}
+
+impl From<PromptError> for ChatError {
+    fn from(value: PromptError) -> Self {
+        Self::classify(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_rate_limited_with_retry_after() {
+        let error = r#"CompletionError: ProviderError: SSE Error: Invalid status code 429 Too Many Requests with message: {"type":"error","error":{"type":"rate_limit_error","message":"slow down"},"retry_after":12}"#;
+        let ChatError::RateLimited {
+            message,
+            retry_after,
+        } = ChatError::classify(error)
+        else {
+            panic!("expected rate limited");
+        };
+        assert_eq!(message, "slow down");
+        assert_eq!(retry_after, Some(Duration::from_secs(12)));
+    }
+
+    #[test]
+    fn test_classify_mid_stream_overloaded() {
+        let error = r#"ResponseError: Failed to parse JSON: unknown variant (Data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}})"#;
+        let error = ChatError::classify(error);
+        assert!(matches!(error, ChatError::Overloaded(_)));
+        assert!(error.is_transient());
+    }
+
+    #[test]
+    fn test_classify_fatal() {
+        let auth = ChatError::classify(
+            r#"Invalid status code 401 Unauthorized with message: {"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#,
+        );
+        assert!(matches!(auth, ChatError::Auth(_)));
+        assert!(!auth.is_transient());
+        let network = ChatError::classify("Http client error: error sending request for url");
+        assert!(matches!(network, ChatError::Network(_)));
+    }
+}
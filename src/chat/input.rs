@@ -5,11 +5,18 @@ pub enum ChatInput {
     SaveChatHistory,
     ImportChatHistory(u16),
     ShowTokenUsage,
+    ShowCreditUsage,
+    ShowSources,
     ClearContext,
     SwitchModel,
     ShowHelpMessage,
     ShowContextSummary,
     CompactContext,
+    ReviewFile(String),
+    Permissions(String),
+    Cache(String),
+    Research(String),
+    Snapshot(String),
     SendMessage(String),
     #[default]
     None,
@@ -20,7 +27,8 @@ where
     T: AsRef<str>,
 {
     fn from(value: T) -> Self {
-        let value = value.as_ref().trim().to_lowercase();
+        let raw = value.as_ref().trim();
+        let value = raw.to_lowercase();
         if value == "/exit" {
             Self::ExitProcess
         } else if value == "/history" {
@@ -36,6 +44,10 @@ where
             Self::ImportChatHistory(id)
         } else if value == "/tokens" {
             Self::ShowTokenUsage
+        } else if value == "/credits" {
+            Self::ShowCreditUsage
+        } else if value == "/sources" {
+            Self::ShowSources
         } else if value == "/clear" {
             Self::ClearContext
         } else if value == "/model" {
@@ -46,6 +58,16 @@ where
             Self::ShowContextSummary
         } else if value == "/compact" {
             Self::CompactContext
+        } else if value == "/review" || value.starts_with("/review ") {
+            Self::ReviewFile(raw["/review".len()..].trim().to_string())
+        } else if value == "/permissions" || value.starts_with("/permissions ") {
+            Self::Permissions(value["/permissions".len()..].trim().to_string())
+        } else if value == "/cache" || value.starts_with("/cache ") {
+            Self::Cache(value["/cache".len()..].trim().to_string())
+        } else if value == "/research" || value.starts_with("/research ") {
+            Self::Research(raw["/research".len()..].trim().to_string())
+        } else if value == "/snapshot" || value.starts_with("/snapshot ") {
+            Self::Snapshot(raw["/snapshot".len()..].trim().to_string())
         } else if value.is_empty() {
             Self::None
         } else {
@@ -65,3 +87,24 @@ impl ChatInput {
         matches!(self, ChatInput::None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_keeps_path_case() {
+        assert!(matches!(
+            ChatInput::from("  /REVIEW src/Main.rs "),
+            ChatInput::ReviewFile(path) if path == "src/Main.rs"
+        ));
+        assert!(matches!(
+            ChatInput::from("/review"),
+            ChatInput::ReviewFile(path) if path.is_empty()
+        ));
+        assert!(matches!(
+            ChatInput::from("/reviewer"),
+            ChatInput::SendMessage(_)
+        ));
+    }
+}
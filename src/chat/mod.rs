@@ -1,46 +1,97 @@
+pub mod citations;
 pub mod config;
+pub mod error;
 pub mod input;
+pub mod permissions;
+pub mod research;
+pub mod retry;
+pub mod saved_chat;
+pub use error::ChatError;
 pub use input::ChatInput;
 
 use crate::{
-    agent_tools::{math_tools, web::tavily::TavilyClient, WebTools},
-    anthropic::{get_models::GetAnthropicModels, ModelInfo},
-    chat::config::Config,
-    ui::{horizontal_line, welcome_message},
+    agent_tools::{
+        code::{review_rust::ReviewRust, run_rust::RunRust},
+        code_tools, datetime_tools, docs_tools,
+        fs::{workspace::Workspace, write_preview},
+        fs_tools, math_tools,
+        shell::run_command::{RunCommand, RunCommandArgs},
+        shell_tools, units_tools,
+        web::{
+            local::LocalFetcher,
+            provider::{web_provider, WebSearchProvider},
+            snapshot::store::SnapshotStore,
+            sources::Sources,
+        },
+        web_tools,
+    },
+    anthropic::{
+        get_models::GetAnthropicModels,
+        http::AnthropicHttpClient,
+        pricing::{cost_usd, max_output_tokens, CONTEXT_WINDOW},
+        AnthropicClient, AnthropicModel, ModelInfo,
+    },
+    chat::{
+        citations::Citations,
+        config::Config,
+        permissions::{Approval, Permissions},
+        retry::RetryPolicy,
+        saved_chat::SavedChat,
+    },
+    frontend::{ChatEvent, Frontend, Status},
+    ui::welcome_message,
 };
 use futures::StreamExt;
 use rig::{
-    agent::{Agent, MultiTurnStreamItem},
+    agent::Agent,
     client::CompletionClient,
-    completion::Chat as ChatTrait,
-    message::Message,
-    providers::anthropic::{
-        completion::CompletionModel,
-        streaming::{PartialUsage, StreamingCompletionResponse},
-        Client,
-    },
-    streaming::{StreamedAssistantContent, StreamingChat},
+    completion::{Chat as ChatTrait, Prompt},
+    message::{Message, ToolCall, ToolResult, ToolResultContent, UserContent},
+    providers::anthropic::streaming::{PartialUsage, StreamingCompletionResponse},
+    streaming::{StreamedAssistantContent, StreamingCompletion},
+    tool::Tool,
+    OneOrMany,
+};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
 };
-use std::{collections::HashSet, sync::Arc};
 
 pub static PREAMBLE: &str = include_str!("preamble.txt");
+pub const MAX_TURNS: usize = 100;
+pub const DEFAULT_MAX_PARALLEL_TOOLS: usize = 4;
+// tools whose calls change the workspace, so they never overlap with other calls
+const SEQUENTIAL_TOOLS: &[&str] = &["apply_patch", "run_command", "write_file"];
 
 pub struct Chat {
     id: u16,
     config: Config,
     model_options: Vec<ModelInfo>,
-    agent: Agent<CompletionModel>,
+    agent: Agent<AnthropicModel>,
     chat_history: Vec<Message>,
     input: ChatInput,
     total_input_tokens_used: usize,
     total_output_tokens_used: usize,
-    tavily_client: Arc<TavilyClient>,
+    context_tokens: usize,
+    total_cost_usd: f64,
+    web_provider: Arc<dyn WebSearchProvider>,
+    sources: Arc<Sources>,
+    citations: Citations,
+    frontend: Box<dyn Frontend>,
+    permissions: Permissions,
+    always_allowed_commands: HashSet<String>,
 }
 
 pub const CHATS_DIR_NAME: &str = "chats";
-fn next_chat_id() -> anyhow::Result<u16> {
-    std::fs::create_dir_all(CHATS_DIR_NAME)?;
-    let existing_chat_ids: HashSet<u16> = std::fs::read_dir(CHATS_DIR_NAME)?
+pub fn chat_file_path(chats_dir: &Path, id: u16) -> PathBuf {
+    chats_dir.join(format!("{}.json", id))
+}
+
+pub fn saved_chat_ids(chats_dir: &Path) -> anyhow::Result<Vec<u16>> {
+    std::fs::create_dir_all(chats_dir)?;
+    let mut chat_ids: Vec<u16> = std::fs::read_dir(chats_dir)?
         .collect::<Result<Vec<_>, _>>()?
         .iter()
         .filter(|ent| ent.path().extension().and_then(|ostr| ostr.to_str()) == Some("json"))
@@ -51,7 +102,13 @@ fn next_chat_id() -> anyhow::Result<u16> {
                 .and_then(|str| str.parse::<u16>().ok())
         })
         .collect();
-    match (0..u16::MAX).find(|id| !existing_chat_ids.contains(id)) {
+    chat_ids.sort();
+    Ok(chat_ids)
+}
+
+fn next_chat_id(chats_dir: &Path) -> anyhow::Result<u16> {
+    let existing_chat_ids = saved_chat_ids(chats_dir)?;
+    match (0..u16::MAX).find(|id| existing_chat_ids.binary_search(id).is_err()) {
         Some(id) => Ok(id),
         None => Err(anyhow::anyhow!(
             "Chat count has hit its limit of {}",
@@ -60,46 +117,98 @@ fn next_chat_id() -> anyhow::Result<u16> {
     }
 }
 
+fn client(config: &Config) -> anyhow::Result<AnthropicClient> {
+    Ok(AnthropicClient::builder()
+        .api_key(config.anthropic_api_key())
+        .base_url(config.anthropic_base_url())
+        .http_client(AnthropicHttpClient::new(config.transport().clone()))
+        .build()?)
+}
+
+fn build_agent(
+    config: &Config,
+    model_id: &str,
+    web_provider: &Arc<dyn WebSearchProvider>,
+    sources: &Arc<Sources>,
+) -> anyhow::Result<Agent<AnthropicModel>> {
+    let workspace = Workspace::new(config.workspace_root())?;
+    Ok(client(config)?
+        .agent(model_id)
+        .name("Marvin")
+        .preamble(PREAMBLE)
+        .max_tokens(max_output_tokens(model_id))
+        .tools(math_tools())
+        .tools(units_tools())
+        .tools(datetime_tools())
+        .tools(code_tools(config.rust_vendor_dir()))
+        .tools(docs_tools(config.rust_docs_dirs()))
+        .tools(fs_tools(workspace.clone()))
+        .tools(shell_tools(workspace, config.command_policy().clone()))
+        .tools(web_tools(
+            web_provider.clone(),
+            LocalFetcher::from(config),
+            SnapshotStore::new(config.snapshots_dir()),
+            sources.clone(),
+            config.web_token_budget(),
+        ))
+        .default_max_turns(MAX_TURNS)
+        .build())
+}
+
+fn select_model(
+    frontend: &mut dyn Frontend,
+    model_options: &[ModelInfo],
+) -> anyhow::Result<Option<ModelInfo>> {
+    frontend.emit(ChatEvent::Notice("Current model: not set".to_string()));
+    for (i, model) in model_options.iter().enumerate() {
+        frontend.emit(ChatEvent::Notice(format!(
+            "{}. {}",
+            i + 1,
+            model.display_name
+        )));
+    }
+    frontend.emit(ChatEvent::Separator);
+    loop {
+        frontend.emit(ChatEvent::Notice("Select a model".to_string()));
+        frontend.emit(ChatEvent::Separator);
+        let input = frontend.read_line()?;
+        if let Some(model) = model_options
+            .iter()
+            .enumerate()
+            .find(|(i, _)| (i + 1).to_string() == input.trim())
+            .map(|(_, model)| model.to_owned())
+        {
+            return Ok(Some(model));
+        } else if input.trim() == "/exit" {
+            return Ok(None);
+        }
+    }
+}
+
 impl Chat {
-    pub async fn new() -> anyhow::Result<Self> {
-        let id = next_chat_id()?;
-        welcome_message(id);
-        let config = Config::from_env()?;
-        let tavily_client = Arc::new(TavilyClient::from(&config));
+    pub async fn new(config: Config, mut frontend: Box<dyn Frontend>) -> anyhow::Result<Self> {
+        let id = next_chat_id(config.chats_dir())?;
+        welcome_message(frontend.as_mut(), id);
         let model_options = config.get_models().await?;
-        let agent: Agent<CompletionModel>;
-        let mut input = String::new();
-        println!("Current model: not set");
-        for (i, model) in model_options.iter().enumerate() {
-            println!("{}. {}", i + 1, model.display_name);
-        }
-        horizontal_line();
-        loop {
-            println!("Select a model");
-            horizontal_line();
-            std::io::stdin().read_line(&mut input)?;
-            if let Some((_, ModelInfo { id, .. })) = model_options
-                .iter()
-                .enumerate()
-                .find(|(i, _)| (i + 1).to_string() == input.trim())
-            {
-                agent = Client::new(config.anthropic_api_key())?
-                    .agent(id)
-                    .name("Marvin")
-                    .preamble(PREAMBLE)
-                    .tools(math_tools())
-                    .tools(tavily_client.web_tools())
-                    .default_max_turns(100)
-                    .build();
-                horizontal_line();
-                break;
-            } else if input.trim() == "/exit" {
-                println!("Farewell!");
-                std::process::exit(0);
-            } else {
-                input.clear();
-            }
-        }
+        let Some(model) = select_model(frontend.as_mut(), &model_options)? else {
+            frontend.emit(ChatEvent::Notice("Farewell!".to_string()));
+            drop(frontend);
+            std::process::exit(0);
+        };
+        frontend.emit(ChatEvent::Separator);
+        Self::with_model(id, config, model_options, &model.id, frontend)
+    }
+    pub fn with_model(
+        id: u16,
+        config: Config,
+        model_options: Vec<ModelInfo>,
+        model_id: &str,
+        frontend: Box<dyn Frontend>,
+    ) -> anyhow::Result<Self> {
+        let web_provider = web_provider(&config)?;
+        let sources = Arc::new(Sources::default());
+        let agent = build_agent(&config, model_id, &web_provider, &sources)?;
+        let permissions = config.permissions().clone();
         Ok(Self {
             id,
             config,
@@ -109,7 +218,14 @@ impl Chat {
             input: ChatInput::new(),
             total_input_tokens_used: 0,
             total_output_tokens_used: 0,
-            tavily_client,
+            context_tokens: 0,
+            total_cost_usd: 0.0,
+            web_provider,
+            sources,
+            citations: Citations::default(),
+            frontend,
+            permissions,
+            always_allowed_commands: HashSet::new(),
         })
     }
     pub fn id(&self) -> u16 {
@@ -129,116 +245,414 @@ impl Chat {
             .unwrap_or("")
     }
     pub fn set_agent(&mut self, model: ModelInfo) -> anyhow::Result<()> {
-        self.agent = Client::new(self.config().anthropic_api_key())?
-            .agent(model.id)
-            .preamble(PREAMBLE)
-            .tools(math_tools())
-            .tools(self.tavily_client().web_tools())
-            .build();
+        self.agent = build_agent(
+            self.config(),
+            &model.id,
+            &self.web_provider(),
+            &self.sources,
+        )?;
+        self.emit_status();
         Ok(())
     }
-    pub async fn send(&mut self, message: impl Into<Message>) -> anyhow::Result<String> {
+    pub async fn send(&mut self, message: impl Into<Message>) -> Result<String, ChatError> {
         let message = message.into();
-        self.add_to_chat_history(message.clone());
-        let response = self
-            .agent
-            .chat(message, self.chat_history().to_owned())
-            .await?;
+        let retry_policy = RetryPolicy::default();
+        let mut attempt = 0;
+        let response = loop {
+            match self
+                .agent
+                .chat(message.clone(), self.chat_history().to_owned())
+                .await
+                .map_err(ChatError::from)
+            {
+                Ok(response) => break response,
+                Err(e) => match retry_policy.delay(attempt, &e) {
+                    Some(delay) => {
+                        attempt += 1;
+                        self.citations.discard_held();
+                        self.retry_notice(&e, delay, attempt, &retry_policy);
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => return Err(e),
+                },
+            }
+        };
+        self.add_to_chat_history(message);
         self.add_to_chat_history(Message::assistant(response.clone()));
         Ok(response)
     }
-    pub async fn stream(&mut self, message: impl Into<Message>) {
-        horizontal_line();
-        let message: Message = message.into();
-        self.add_to_chat_history(message.clone());
+    // a single completion with its own preamble and no tools or chat history, for workflows
+    // that drive the model step by step
+    pub async fn complete(&mut self, preamble: &str, prompt: &str) -> Result<String, ChatError> {
+        let model_id = self.agent.model.model.clone();
+        let agent = client(&self.config)
+            .map_err(|e| ChatError::Other(e.to_string()))?
+            .agent(&model_id)
+            .preamble(preamble)
+            .max_tokens(max_output_tokens(&model_id))
+            .build();
+        let retry_policy = RetryPolicy::default();
+        let mut attempt = 0;
+        let response = loop {
+            match agent
+                .prompt(prompt)
+                .extended_details()
+                .await
+                .map_err(ChatError::from)
+            {
+                Ok(response) => break response,
+                Err(e) => match retry_policy.delay(attempt, &e) {
+                    Some(delay) => {
+                        attempt += 1;
+                        self.citations.discard_held();
+                        self.retry_notice(&e, delay, attempt, &retry_policy);
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => return Err(e),
+                },
+            }
+        };
+        let input_tokens = response.total_usage.input_tokens as usize;
+        let output_tokens = response.total_usage.output_tokens as usize;
+        self.emit(ChatEvent::Usage {
+            input_tokens,
+            output_tokens,
+        });
+        self.add_input_tokens_used(input_tokens);
+        self.add_output_tokens_used(output_tokens);
+        self.total_cost_usd += cost_usd(&model_id, input_tokens, output_tokens);
+        self.emit_status();
+        Ok(response.output)
+    }
+    pub async fn stream(&mut self, message: impl Into<Message>) -> Result<(), ChatError> {
+        self.separator();
+        let checkpoint = self.chat_history.len();
+        self.citations = Citations::default();
+        self.add_to_chat_history(message);
+        let result = self.run_turns().await;
+        if result.is_err() {
+            self.chat_history.truncate(checkpoint);
+        }
+        result
+    }
+    async fn run_turns(&mut self) -> Result<(), ChatError> {
+        for _ in 0..MAX_TURNS {
+            let tool_calls = self.stream_turn_with_retry().await?;
+            if tool_calls.is_empty() {
+                if let Some(footnotes) = self.citations.footnotes() {
+                    self.emit(ChatEvent::TextDelta(format!("\n{}", footnotes)));
+                    self.emit(ChatEvent::TextEnd);
+                }
+                return Ok(());
+            }
+            let tool_results = self.call_tools(tool_calls).await;
+            self.add_to_chat_history(Message::User {
+                content: tool_results,
+            });
+        }
+        Err(ChatError::MaxTurns(MAX_TURNS))
+    }
+    async fn stream_turn_with_retry(&mut self) -> Result<Vec<ToolCall>, ChatError> {
+        let retry_policy = RetryPolicy::default();
+        let mut attempt = 0;
+        loop {
+            let mut emitted = false;
+            match self.stream_turn(&mut emitted).await {
+                Ok(tool_calls) => return Ok(tool_calls),
+                // the user has already seen part of this answer, a retry would show it twice
+                Err(e) if emitted => return Err(e),
+                Err(e) => match retry_policy.delay(attempt, &e) {
+                    Some(delay) => {
+                        attempt += 1;
+                        self.citations.discard_held();
+                        self.retry_notice(&e, delay, attempt, &retry_policy);
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => return Err(e),
+                },
+            }
+        }
+    }
+    // `emitted` says whether any of the answer reached the frontend before an error
+    async fn stream_turn(&mut self, emitted: &mut bool) -> Result<Vec<ToolCall>, ChatError> {
+        let Some((prompt, history)) = self.chat_history.split_last() else {
+            return Err(ChatError::Other("Nothing to send".to_string()));
+        };
         let mut stream = self
             .agent
-            .stream_chat(message, self.chat_history().to_owned())
-            .await;
+            .stream_completion(prompt.clone(), history.to_vec())
+            .await?
+            .stream()
+            .await?;
+        let mut tool_calls = Vec::new();
         while let Some(result) = stream.next().await {
-            match result {
-                Ok(MultiTurnStreamItem::FinalResponse(final_response)) => {
-                    self.chat_history
-                        .push(Message::assistant(final_response.response()));
+            match result? {
+                StreamedAssistantContent::Text(text) if !text.text().trim().is_empty() => {
+                    let text = self.citations.push(text.text(), &self.sources);
+                    if !text.is_empty() {
+                        *emitted = true;
+                        self.emit(ChatEvent::TextDelta(text));
+                    }
                 }
-                Ok(MultiTurnStreamItem::StreamAssistantItem(StreamedAssistantContent::Text(
-                    text,
-                ))) if !text.text().trim().is_empty() => {
-                    print!("{}", text.text());
+                StreamedAssistantContent::ToolCall { tool_call, .. } => {
+                    tool_calls.push(tool_call);
                 }
-                Ok(MultiTurnStreamItem::StreamAssistantItem(StreamedAssistantContent::Final(
-                    StreamingCompletionResponse {
-                        usage:
-                            PartialUsage {
-                                output_tokens,
-                                input_tokens,
-                            },
-                    },
-                ))) => {
-                    println!();
-                    self.add_output_tokens_used(output_tokens);
-                    if let Some(input_tokens) = input_tokens {
-                        self.add_input_tokens_used(input_tokens);
+                StreamedAssistantContent::Final(StreamingCompletionResponse {
+                    usage:
+                        PartialUsage {
+                            output_tokens,
+                            input_tokens,
+                        },
+                }) => {
+                    *emitted = true;
+                    let held = self.citations.flush(&self.sources);
+                    if !held.is_empty() {
+                        self.emit(ChatEvent::TextDelta(held));
                     }
+                    self.emit(ChatEvent::TextEnd);
+                    let input_tokens = input_tokens.unwrap_or_default();
+                    self.emit(ChatEvent::Usage {
+                        input_tokens,
+                        output_tokens,
+                    });
+                    self.add_output_tokens_used(output_tokens);
+                    self.add_input_tokens_used(input_tokens);
+                    self.context_tokens = input_tokens + output_tokens;
+                    self.total_cost_usd +=
+                        cost_usd(&self.agent.model.model, input_tokens, output_tokens);
+                    self.emit_status();
                 }
-                Ok(_) => (),
-                Err(e) => {
-                    eprintln!("Stream Error: {}", e);
+                _ => (),
+            }
+        }
+        self.add_to_chat_history(Message::Assistant {
+            id: stream.message_id.clone(),
+            content: stream.choice.clone(),
+        });
+        Ok(tool_calls)
+    }
+    async fn call_tools(&mut self, tool_calls: Vec<ToolCall>) -> OneOrMany<UserContent> {
+        let results = self.run_tool_calls(&tool_calls).await;
+        let tool_results: Vec<UserContent> = tool_calls
+            .into_iter()
+            .zip(results)
+            .map(|(ToolCall { id, call_id, .. }, result)| {
+                UserContent::ToolResult(ToolResult {
+                    id,
+                    call_id,
+                    content: ToolResultContent::from_tool_output(result.unwrap_or_else(|e| e)),
+                })
+            })
+            .collect();
+        OneOrMany::many(tool_results).expect("at least one tool call")
+    }
+    // runs independent calls together, at most max_parallel_tools at a time, after asking about
+    // each in order. calls that change the workspace run alone, after the calls requested before
+    // them, and are asked about only then so their preview shows what they will really change.
+    // results come back in the order of the calls
+    pub async fn run_tool_calls(&mut self, tool_calls: &[ToolCall]) -> Vec<Result<String, String>> {
+        let calls: Vec<(&String, &str, String)> = tool_calls
+            .iter()
+            .map(|ToolCall { id, function, .. }| {
+                (id, function.name.as_str(), function.arguments.to_string())
+            })
+            .collect();
+        let sequential = |i: usize| SEQUENTIAL_TOOLS.contains(&calls[i].1);
+        let max_parallel = self.config.max_parallel_tools();
+        let tool_server = self.agent.tool_server_handle.clone();
+        let mut results = vec![None; calls.len()];
+        let mut start = 0;
+        while start < calls.len() {
+            let end = match sequential(start) {
+                true => start + 1,
+                false => (start..calls.len())
+                    .find(|i| sequential(*i))
+                    .unwrap_or(calls.len()),
+            };
+            let mut approvals = Vec::new();
+            for (id, name, args) in &calls[start..end] {
+                self.emit(ChatEvent::ToolStarted {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    args: args.clone(),
+                });
+                approvals.push(self.confirm_tool_call(name, args));
+            }
+            let offset = start;
+            let mut running = futures::stream::iter(calls[start..end].iter().zip(approvals))
+                .enumerate()
+                .map(|(i, ((_, name, args), approval))| {
+                    let tool_server = tool_server.clone();
+                    async move {
+                        let started = Instant::now();
+                        let result = match approval {
+                            Ok(()) => tool_server
+                                .call_tool(name, args)
+                                .await
+                                .map_err(|e| e.to_string()),
+                            Err(e) => Err(e),
+                        };
+                        (offset + i, result, started.elapsed())
+                    }
+                })
+                .buffer_unordered(max_parallel);
+            // shown as each call finishes, so a slow call doesn't hide the ones done before it
+            while let Some((i, result, elapsed)) = running.next().await {
+                let (output, is_error) = match &result {
+                    Ok(output) => (output.clone(), false),
+                    Err(e) => (e.clone(), true),
+                };
+                self.emit(ChatEvent::ToolFinished {
+                    id: calls[i].0.clone(),
+                    name: calls[i].1.to_string(),
+                    output,
+                    is_error,
+                    elapsed,
+                });
+                results[i] = Some(result);
+            }
+            start = end;
+        }
+        results
+            .into_iter()
+            .map(|result| result.expect("every call ran"))
+            .collect()
+    }
+    // returns the text shown when asking, or None when the call was already approved for the session
+    fn tool_call_preview(&self, name: &str, args: &str) -> Result<Option<String>, String> {
+        if name == RunCommand::NAME {
+            let Ok(args) = serde_json::from_str::<RunCommandArgs>(args) else {
+                return Ok(None);
+            };
+            let command = self
+                .config
+                .command_policy()
+                .check(&args.command)
+                .map_err(|e| e.to_string())?
+                .join(" ");
+            if self.always_allowed_commands.contains(&command) {
+                return Ok(None);
+            }
+            return Ok(Some(format!(
+                "`{}` in {}",
+                command,
+                args.cwd.as_deref().unwrap_or(".")
+            )));
+        }
+        // code reads better in a block than as an escaped JSON string
+        if (name == RunRust::NAME || name == ReviewRust::NAME)
+            && let Ok(args) = serde_json::from_str::<serde_json::Value>(args)
+            && let Some(code) = args["code"].as_str()
+        {
+            return Ok(Some(format!("```rust\n{}\n```", code.trim_end())));
+        }
+        let workspace = Workspace::new(self.config.workspace_root()).map_err(|e| e.to_string())?;
+        if let Some(preview) = write_preview(&workspace, name, args) {
+            return preview.map(Some).map_err(|e| e.to_string());
+        }
+        Ok(Some(
+            serde_json::from_str::<serde_json::Value>(args)
+                .and_then(|args| serde_json::to_string_pretty(&args))
+                .unwrap_or_else(|_| args.to_string()),
+        ))
+    }
+    fn confirm_tool_call(&mut self, name: &str, args: &str) -> Result<(), String> {
+        let approval = self.permissions.get(name);
+        if approval == Approval::Never {
+            return Err(format!(
+                "The user has disabled the {} tool, do not call it again",
+                name
+            ));
+        }
+        let Some(preview) = self.tool_call_preview(name, args)? else {
+            return Ok(());
+        };
+        if approval == Approval::Always {
+            return Ok(());
+        }
+        self.notice(format!("Tool call: {}", name));
+        self.notice(preview);
+        self.notice(format!("Allow {}? [y/N/always]", name));
+        let answer = self.frontend.read_line().unwrap_or_default();
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => Ok(()),
+            // for commands "always" covers this exact command line, not every command
+            "a" | "always" if name == RunCommand::NAME => {
+                if let Ok(args) = serde_json::from_str::<RunCommandArgs>(args)
+                    && let Ok(words) = self.config.command_policy().check(&args.command)
+                {
+                    self.always_allowed_commands.insert(words.join(" "));
                 }
+                Ok(())
             }
+            "a" | "always" => {
+                self.permissions.set(name, Approval::Always);
+                Ok(())
+            }
+            _ => Err(format!(
+                "The user declined this {} call, ask them how to proceed or try something else",
+                name
+            )),
         }
     }
+    pub fn permissions(&self) -> &Permissions {
+        &self.permissions
+    }
+    pub fn permissions_mut(&mut self) -> &mut Permissions {
+        &mut self.permissions
+    }
+    pub async fn tool_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .agent
+            .tool_server_handle
+            .get_tool_defs(None)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|definition| definition.name)
+            .collect();
+        names.sort();
+        names
+    }
     pub fn chat_history(&self) -> &[Message] {
         self.chat_history.as_slice()
     }
     pub fn clear_chat_history(&mut self) {
         self.chat_history.clear();
+        self.context_tokens = 0;
+        self.emit_status();
     }
     pub fn add_to_chat_history(&mut self, message: impl Into<Message>) {
         self.chat_history.push(message.into());
     }
     pub fn save_chat_history_to_file(&self) -> anyhow::Result<()> {
-        let file_path = format!("{}/{}.json", CHATS_DIR_NAME, self.id());
-        let file = std::fs::File::create(file_path)?;
-        let chat_history_json = serde_json::to_value(self.chat_history())?;
-        serde_json::to_writer_pretty(file, &chat_history_json)?;
-        Ok(())
+        let file_path = chat_file_path(self.config.chats_dir(), self.id());
+        SavedChat {
+            messages: self.chat_history.clone(),
+            sources: self.sources.all(),
+        }
+        .write(&file_path)
     }
     pub fn append_chat_history_from_file_infallible(&mut self, id: u16) {
-        let file_path = format!("{}/{}.json", CHATS_DIR_NAME, id);
-        let file_result = std::fs::File::open(file_path);
-        match file_result {
-            Ok(mut file) => {
-                println!("chat_history with ID: {} found!", id);
-                let file_str = {
-                    let mut file_str = String::new();
-                    let Ok(_) =
-                        <std::fs::File as std::io::Read>::read_to_string(&mut file, &mut file_str)
-                    else {
-                        println!("Failed to read file");
-                        return;
-                    };
-                    file_str
-                };
-                let Ok(chat_history) = serde_json::from_str::<Vec<Message>>(&file_str) else {
-                    println!("Failed to serialize file to `Vec<Message>`");
-                    return;
-                };
-                self.chat_history.extend(chat_history);
+        let file_path = chat_file_path(self.config.chats_dir(), id);
+        match SavedChat::read(&file_path) {
+            Ok(saved) => {
+                self.notice(format!("chat_history with ID: {} found!", id));
+                self.chat_history.extend(saved.messages);
+                self.sources.restore(saved.sources);
             }
-            Err(e) => println!("Failed to get chat_history: {}", e),
+            Err(e) => self.notice(format!("Failed to get chat_history: {}", e)),
         }
     }
     pub fn input(&self) -> &ChatInput {
         &self.input
     }
     pub fn get_input(&mut self) {
-        let mut input_str = String::new();
-        match std::io::stdin().read_line(&mut input_str) {
-            Ok(_) => self.input = ChatInput::from(input_str),
+        match self.frontend.read_line() {
+            Ok(input_str) => self.input = ChatInput::from(input_str),
             Err(e) => {
-                eprintln!("Error: {}", e);
-                println!("Input failed");
+                self.error(e);
+                self.notice("Input failed");
                 self.clear_input();
             }
         }
@@ -258,7 +672,154 @@ impl Chat {
     pub fn add_output_tokens_used(&mut self, output_tokens: usize) {
         self.total_output_tokens_used += output_tokens;
     }
-    pub fn tavily_client(&self) -> Arc<TavilyClient> {
-        self.tavily_client.clone()
+    pub fn total_cost_usd(&self) -> f64 {
+        self.total_cost_usd
+    }
+    pub fn web_provider(&self) -> Arc<dyn WebSearchProvider> {
+        self.web_provider.clone()
+    }
+    pub fn sources(&self) -> &Sources {
+        &self.sources
+    }
+    pub fn status(&self) -> Status {
+        Status {
+            model: self.model().to_string(),
+            context_tokens: self.context_tokens,
+            context_window: CONTEXT_WINDOW,
+            cost_usd: self.total_cost_usd,
+        }
+    }
+    pub fn emit(&mut self, event: ChatEvent) {
+        self.frontend.emit(event);
+    }
+    pub fn emit_status(&mut self) {
+        let status = self.status();
+        self.emit(ChatEvent::Status(status));
+    }
+    pub fn notice(&mut self, notice: impl Into<String>) {
+        self.emit(ChatEvent::Notice(notice.into()));
+    }
+    pub fn error(&mut self, error: impl ToString) {
+        self.emit(ChatEvent::Error(error.to_string()));
+    }
+    pub fn separator(&mut self) {
+        self.emit(ChatEvent::Separator);
+    }
+    fn retry_notice(
+        &mut self,
+        error: &ChatError,
+        delay: Duration,
+        attempt: u32,
+        retry_policy: &RetryPolicy,
+    ) {
+        self.emit(ChatEvent::TextEnd);
+        self.error(format!(
+            "{} (retrying in {:.1}s, attempt {}/{})",
+            error,
+            delay.as_secs_f32(),
+            attempt,
+            retry_policy.max_retries()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        frontend::scripted::Scripted,
+        testing::{model_options, TEST_MODEL_ID},
+        user_commands::switch_model::SwitchModel,
+    };
+    use rig::message::ToolFunction;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    fn test_chat(inputs: &[&str]) -> (Chat, Arc<Mutex<Vec<ChatEvent>>>) {
+        let (frontend, events) = Scripted::new(inputs);
+        let chat = Chat::with_model(
+            0,
+            Config::new("test", "test"),
+            model_options(),
+            TEST_MODEL_ID,
+            Box::new(frontend),
+        )
+        .unwrap();
+        (chat, events)
+    }
+
+    #[test]
+    fn test_select_model() {
+        let (mut frontend, _) = Scripted::new(&["9", "2"]);
+        let options = model_options();
+        let model = select_model(&mut frontend, &options).unwrap();
+        assert_eq!(
+            model.map(|model| model.id),
+            Some("claude-test-2".to_string())
+        );
+        let (mut frontend, _) = Scripted::new(&["/exit"]);
+        assert!(select_model(&mut frontend, &options).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_switch_model_reads_from_frontend() {
+        let (mut chat, events) = test_chat(&["2"]);
+        assert_eq!(chat.model(), "Claude Test 1");
+        chat.switch_model().unwrap();
+        assert_eq!(chat.model(), "Claude Test 2");
+        let events = events.lock().unwrap();
+        assert!(events.iter().any(|event| matches!(
+            event,
+            ChatEvent::Status(Status { model, .. }) if model == "Claude Test 2"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_emit_started_and_finished() {
+        let (mut chat, events) = test_chat(&[]);
+        let tool_calls = vec![
+            ToolCall::new(
+                "toolu_1".to_string(),
+                ToolFunction::new("calculate".to_string(), json!({ "expression": "2 + 3" })),
+            ),
+            ToolCall::new(
+                "toolu_2".to_string(),
+                ToolFunction::new("missing".to_string(), json!({})),
+            ),
+        ];
+        let results = chat.call_tools(tool_calls).await;
+        let ids: Vec<_> = results
+            .iter()
+            .map(|content| match content {
+                UserContent::ToolResult(result) => result.id.as_str(),
+                _ => "",
+            })
+            .collect();
+        assert_eq!(ids, vec!["toolu_1", "toolu_2"]);
+        let events = events.lock().unwrap();
+        let mut finished: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                ChatEvent::ToolFinished {
+                    id,
+                    output,
+                    is_error,
+                    ..
+                } => Some((id.as_str(), output.as_str(), *is_error)),
+                _ => None,
+            })
+            .collect();
+        // finished in whichever order the calls completed
+        finished.sort();
+        assert_eq!(
+            finished[0],
+            ("toolu_1", r#"{"result":"5","exact":true}"#, false)
+        );
+        assert_eq!(finished[1].0, "toolu_2");
+        assert!(finished[1].2);
+        assert!(matches!(
+            &events[0],
+            ChatEvent::ToolStarted { name, .. } if name == "calculate"
+        ));
     }
 }
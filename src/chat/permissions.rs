@@ -0,0 +1,130 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+use thiserror::Error;
+
+// tools that write files, run commands or code, or spend many Tavily credits ask first by default.
+// review_rust runs cargo on the code, so build scripts and macros in it run too
+const ASK_BY_DEFAULT: &[&str] = &[
+    "apply_patch",
+    "crawl_website",
+    "map_website",
+    "review_rust",
+    "run_command",
+    "run_rust",
+    "write_file",
+];
+
+#[derive(Debug, Error)]
+pub enum PermissionError {
+    #[error("Unknown approval '{0}', expected always, ask or never")]
+    UnknownApproval(String),
+    #[error("Invalid permission rule '{0}', expected <tool>=<always|ask|never>")]
+    InvalidRule(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Approval {
+    Always,
+    Ask,
+    Never,
+}
+
+impl FromStr for Approval {
+    type Err = PermissionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "always" => Ok(Self::Always),
+            "ask" => Ok(Self::Ask),
+            "never" => Ok(Self::Never),
+            other => Err(PermissionError::UnknownApproval(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Approval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Always => "always",
+            Self::Ask => "ask",
+            Self::Never => "never",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Permissions {
+    default: Approval,
+    tools: BTreeMap<String, Approval>,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Self {
+            default: Approval::Always,
+            tools: ASK_BY_DEFAULT
+                .iter()
+                .map(|tool| (tool.to_string(), Approval::Ask))
+                .collect(),
+        }
+    }
+}
+
+impl Permissions {
+    pub fn get(&self, tool: &str) -> Approval {
+        self.tools.get(tool).copied().unwrap_or(self.default)
+    }
+    // "*" sets the approval for every tool, replacing per-tool settings
+    pub fn set(&mut self, tool: &str, approval: Approval) {
+        if tool == "*" {
+            self.default = approval;
+            self.tools.clear();
+        } else {
+            self.tools.insert(tool.to_string(), approval);
+        }
+    }
+    // rules look like "crawl_website=never,search_web=ask" and apply in order
+    pub fn apply_rules(&mut self, rules: &str) -> Result<(), PermissionError> {
+        for rule in rules
+            .split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+        {
+            let (tool, approval) = rule
+                .split_once('=')
+                .ok_or_else(|| PermissionError::InvalidRule(rule.to_string()))?;
+            let tool = tool.trim();
+            if tool.is_empty() {
+                return Err(PermissionError::InvalidRule(rule.to_string()));
+            }
+            self.set(tool, approval.parse()?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_apply_in_order() {
+        let mut permissions = Permissions::default();
+        assert_eq!(permissions.get("crawl_website"), Approval::Ask);
+        assert_eq!(permissions.get("run_rust"), Approval::Ask);
+        assert_eq!(permissions.get("calculate"), Approval::Always);
+        permissions
+            .apply_rules("*=ask, search_web=always,run_command=never")
+            .unwrap();
+        assert_eq!(permissions.get("calculate"), Approval::Ask);
+        assert_eq!(permissions.get("search_web"), Approval::Always);
+        assert_eq!(permissions.get("run_command"), Approval::Never);
+        assert!(matches!(
+            permissions.apply_rules("search_web"),
+            Err(PermissionError::InvalidRule(_))
+        ));
+        assert!(matches!(
+            permissions.apply_rules("search_web=sometimes"),
+            Err(PermissionError::UnknownApproval(_))
+        ));
+    }
+}
@@ -0,0 +1,219 @@
+use crate::agent_tools::web::{budget::truncate, provider::WebSearchProvider};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+pub const RESEARCH_DIR_NAME: &str = "research";
+// one model call plans, one writes the report and each sub-question needs one for its notes
+pub const MIN_RESEARCH_TURNS: usize = 3;
+pub const RESULTS_PER_QUESTION: usize = 5;
+pub const SOURCES_PER_QUESTION: usize = 2;
+// Tavily's prices, a basic search is one credit and a basic extract one per five URLs
+pub const SEARCH_CREDITS: f64 = 1.0;
+pub const EXTRACT_CREDITS: f64 = 1.0;
+const MAX_SOURCE_CHARS: usize = 6000;
+const MAX_SLUG_CHARS: usize = 60;
+
+pub static RESEARCH_PREAMBLE: &str = "You are a careful research assistant. You plan web research, take notes from sources and write reports that cite them. Cite a source right after the statement that relies on it as [S3], or [S1, S3] for several, using only the ids given with the sources. Never invent facts, sources or ids, and say plainly when the sources disagree or leave a question open.";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResearchLimits {
+    pub max_questions: usize,
+    // Tavily credits a run may spend, other providers spend none
+    pub max_credits: f64,
+    // model calls a run may make, planning and the report included
+    pub max_turns: usize,
+}
+
+impl Default for ResearchLimits {
+    fn default() -> Self {
+        Self {
+            max_questions: 4,
+            max_credits: 20.0,
+            max_turns: 8,
+        }
+    }
+}
+
+// what a research run has left to spend, credits are read off the provider's ledger
+pub struct Allowance {
+    limits: ResearchLimits,
+    turns: usize,
+    start_credits: f64,
+}
+
+fn credits(provider: &dyn WebSearchProvider) -> f64 {
+    provider.credits().map_or(0.0, |ledger| ledger.total())
+}
+
+impl Allowance {
+    pub fn new(limits: ResearchLimits, provider: &dyn WebSearchProvider) -> Self {
+        Self {
+            limits,
+            turns: 0,
+            start_credits: credits(provider),
+        }
+    }
+    pub fn credits_spent(&self, provider: &dyn WebSearchProvider) -> f64 {
+        credits(provider) - self.start_credits
+    }
+    // how many calls costing `each` credits still fit
+    pub fn affordable(&self, provider: &dyn WebSearchProvider, each: f64) -> usize {
+        if provider.credits().is_none() {
+            return usize::MAX;
+        }
+        let left = self.limits.max_credits - self.credits_spent(provider);
+        (left / each).floor().max(0.0) as usize
+    }
+    pub fn take_turn(&mut self) {
+        self.turns += 1;
+    }
+    pub fn turns(&self) -> usize {
+        self.turns
+    }
+}
+
+// a page as the web tools return it, search and extract output share these fields
+#[derive(Debug, Clone, Deserialize)]
+pub struct Found {
+    pub source: Option<String>,
+    pub title: Option<String>,
+    pub url: String,
+    #[serde(default)]
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Results {
+    pub results: Vec<Found>,
+}
+
+pub fn plan_prompt(question: &str, max_questions: usize) -> String {
+    format!(
+        "Break this research question into at most {} focused sub-questions that web searches can answer, covering what a good answer needs and nothing more. Reply with one sub-question per line and nothing else.\n\nQuestion: {}",
+        max_questions, question
+    )
+}
+
+// the sub-questions in a plan, numbering and bullets removed
+pub fn parse_plan(plan: &str, max_questions: usize) -> Vec<String> {
+    plan.lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .trim_start_matches(['.', ')', '-', '*', '•'])
+                .trim()
+                .to_string()
+        })
+        .filter(|line| !line.is_empty() && !line.ends_with(':'))
+        .take(max_questions)
+        .collect()
+}
+
+pub fn notes_prompt(question: &str, sub_question: &str, sources: &[Found]) -> String {
+    let sources: Vec<String> = sources
+        .iter()
+        .map(|found| {
+            format!(
+                "[{}] {} <{}>\n{}",
+                found.source.as_deref().unwrap_or("?"),
+                found.title.as_deref().unwrap_or("Untitled"),
+                found.url,
+                truncate(&found.content, MAX_SOURCE_CHARS)
+            )
+        })
+        .collect();
+    format!(
+        "We are researching: {}\n\nTake concise notes answering this sub-question from the sources below: {}\nKeep facts, numbers and caveats that matter, cite each one, and say what the sources leave unanswered.\n\nSources:\n\n{}",
+        question,
+        sub_question,
+        sources.join("\n\n")
+    )
+}
+
+pub fn report_prompt(question: &str, notes: &[(String, String)]) -> String {
+    let notes: Vec<String> = notes
+        .iter()
+        .map(|(sub_question, notes)| format!("### {}\n{}", sub_question, notes))
+        .collect();
+    format!(
+        "Write a Markdown research report answering: {}\n\nStart with a short summary, then cover the findings in sections and end with the open questions. Base it only on the notes below and keep their citations. Do not add a title or a list of sources, both are added for you.\n\nNotes:\n\n{}",
+        question,
+        notes.join("\n\n")
+    )
+}
+
+fn slug(question: &str) -> String {
+    let words: Vec<String> = question
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let mut slug = String::new();
+    for word in words {
+        if !slug.is_empty() && slug.len() + word.len() + 1 > MAX_SLUG_CHARS {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word);
+    }
+    if slug.is_empty() {
+        "research".to_string()
+    } else {
+        slug
+    }
+}
+
+// a file for the report that doesn't overwrite an earlier one on the same question
+pub fn report_path(dir: &Path, question: &str) -> PathBuf {
+    let slug = slug(question);
+    (1..)
+        .map(|n| match n {
+            1 => dir.join(format!("{}.md", slug)),
+            n => dir.join(format!("{}-{}.md", slug, n)),
+        })
+        .find(|path| !path.exists())
+        .expect("a free file name")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_and_report_path() {
+        let plan = "Sub-questions:\n1. How does tokio schedule tasks?\n2) What is smol's executor model?\n\n- Does glommio need io_uring?\n* Extra question";
+        assert_eq!(
+            parse_plan(plan, 3),
+            vec![
+                "How does tokio schedule tasks?",
+                "What is smol's executor model?",
+                "Does glommio need io_uring?",
+            ]
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let path = report_path(
+            dir.path(),
+            "Compare tokio, smol and glommio for our use case!",
+        );
+        assert_eq!(
+            path.file_name().unwrap(),
+            "compare-tokio-smol-and-glommio-for-our-use-case.md"
+        );
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(
+            report_path(
+                dir.path(),
+                "compare tokio smol and glommio for our use case"
+            )
+            .file_name()
+            .unwrap(),
+            "compare-tokio-smol-and-glommio-for-our-use-case-2.md"
+        );
+        assert_eq!(
+            report_path(dir.path(), "???").file_name().unwrap(),
+            "research.md"
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use crate::chat::error::ChatError;
+use std::time::Duration;
+
+pub struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 4,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+    pub fn delay(&self, attempt: u32, error: &ChatError) -> Option<Duration> {
+        if !error.is_transient() || attempt >= self.max_retries {
+            return None;
+        }
+        let backoff = self.base_delay.saturating_mul(2u32.saturating_pow(attempt));
+        Some(error.retry_after().unwrap_or(backoff).min(self.max_delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        let policy = RetryPolicy::default();
+        let overloaded = ChatError::Overloaded(String::new());
+        assert_eq!(policy.delay(0, &overloaded), Some(Duration::from_secs(1)));
+        assert_eq!(policy.delay(2, &overloaded), Some(Duration::from_secs(4)));
+        assert_eq!(policy.delay(4, &overloaded), None);
+        let rate_limited = ChatError::RateLimited {
+            message: String::new(),
+            retry_after: Some(Duration::from_secs(20)),
+        };
+        assert_eq!(
+            policy.delay(0, &rate_limited),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(policy.delay(0, &ChatError::Auth(String::new())), None);
+    }
+}
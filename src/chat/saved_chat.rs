@@ -0,0 +1,59 @@
+use crate::agent_tools::web::sources::Source;
+use rig::message::Message;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// what a chat file holds, the sources let citations in the messages resolve after an import
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SavedChat {
+    pub messages: Vec<Message>,
+    #[serde(default)]
+    pub sources: Vec<Source>,
+}
+
+// files saved before sources were tracked are a bare list of messages
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChatFile {
+    Saved(SavedChat),
+    Messages(Vec<Message>),
+}
+
+impl SavedChat {
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Ok(match serde_json::from_reader(file)? {
+            ChatFile::Saved(saved) => saved,
+            ChatFile::Messages(messages) => SavedChat {
+                messages,
+                sources: Vec::new(),
+            },
+        })
+    }
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_bare_message_lists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("0.json");
+        std::fs::write(
+            &path,
+            serde_json::to_string(&vec![Message::user("hi")]).unwrap(),
+        )
+        .unwrap();
+        let saved = SavedChat::read(&path).unwrap();
+        assert_eq!(saved.messages.len(), 1);
+        assert!(saved.sources.is_empty());
+        std::fs::write(&path, "[]").unwrap();
+        assert!(SavedChat::read(&path).unwrap().messages.is_empty());
+    }
+}
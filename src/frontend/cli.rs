@@ -0,0 +1,76 @@
+use crate::{
+    frontend::{ChatEvent, Frontend},
+    ui::horizontal_line,
+};
+use std::{
+    io::{stdout, Write},
+    time::Duration,
+};
+
+const MAX_TOOL_TEXT_LEN: usize = 80;
+
+pub struct Cli;
+
+pub fn truncate(text: &str, max_len: usize) -> String {
+    let text = text.replace('\n', " ");
+    match text.char_indices().nth(max_len) {
+        Some((index, _)) => format!("{}...", &text[..index]),
+        None => text,
+    }
+}
+
+// milliseconds for quick calls, tenths of a second for the rest
+pub fn elapsed(duration: Duration) -> String {
+    match duration.as_millis() {
+        millis if millis < 1000 => format!("{}ms", millis),
+        _ => format!("{:.1}s", duration.as_secs_f64()),
+    }
+}
+
+impl Frontend for Cli {
+    fn emit(&mut self, event: ChatEvent) {
+        match event {
+            ChatEvent::TextDelta(text) => {
+                print!("{}", text);
+                let _ = stdout().flush();
+            }
+            ChatEvent::TextEnd => println!(),
+            ChatEvent::Notice(notice) => println!("{}", notice),
+            ChatEvent::Error(error) => eprintln!("Error: {}", error),
+            ChatEvent::Separator => horizontal_line(),
+            ChatEvent::ToolStarted { name, args, .. } => {
+                println!("[tool] {}({})", name, truncate(&args, MAX_TOOL_TEXT_LEN))
+            }
+            ChatEvent::ToolFinished {
+                name,
+                output,
+                is_error,
+                elapsed: duration,
+                ..
+            } => {
+                if is_error {
+                    println!(
+                        "[tool] {} failed after {}: {}",
+                        name,
+                        elapsed(duration),
+                        output
+                    );
+                } else {
+                    println!(
+                        "[tool] {} -> {} ({})",
+                        name,
+                        truncate(&output, MAX_TOOL_TEXT_LEN),
+                        elapsed(duration)
+                    );
+                }
+            }
+            ChatEvent::Usage { .. } => (),
+            ChatEvent::Status(_) | ChatEvent::Saved => (),
+        }
+    }
+    fn read_line(&mut self) -> anyhow::Result<String> {
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        Ok(input)
+    }
+}
@@ -0,0 +1,51 @@
+pub mod cli;
+#[cfg(test)]
+pub mod scripted;
+pub mod tui;
+
+pub use cli::Cli;
+pub use tui::Tui;
+
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub enum ChatEvent {
+    TextDelta(String),
+    TextEnd,
+    Notice(String),
+    Error(String),
+    Separator,
+    ToolStarted {
+        id: String,
+        name: String,
+        args: String,
+    },
+    ToolFinished {
+        id: String,
+        name: String,
+        output: String,
+        is_error: bool,
+        // wall-clock time the call took to run
+        elapsed: Duration,
+    },
+    Usage {
+        input_tokens: usize,
+        output_tokens: usize,
+    },
+    Status(Status),
+    // a chat was written to the chats directory
+    Saved,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Status {
+    pub model: String,
+    pub context_tokens: usize,
+    pub context_window: usize,
+    pub cost_usd: f64,
+}
+
+pub trait Frontend: Send {
+    fn emit(&mut self, event: ChatEvent);
+    fn read_line(&mut self) -> anyhow::Result<String>;
+}
@@ -0,0 +1,33 @@
+use crate::frontend::{ChatEvent, Frontend};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+pub struct Scripted {
+    inputs: VecDeque<String>,
+    events: Arc<Mutex<Vec<ChatEvent>>>,
+}
+
+impl Scripted {
+    pub fn new(inputs: &[&str]) -> (Self, Arc<Mutex<Vec<ChatEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let scripted = Self {
+            inputs: inputs.iter().map(|input| input.to_string()).collect(),
+            events: events.clone(),
+        };
+        (scripted, events)
+    }
+}
+
+impl Frontend for Scripted {
+    fn emit(&mut self, event: ChatEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+    fn read_line(&mut self) -> anyhow::Result<String> {
+        Ok(self
+            .inputs
+            .pop_front()
+            .unwrap_or_else(|| "/exit".to_string()))
+    }
+}
@@ -0,0 +1,374 @@
+use crate::{
+    chat::{chat_file_path, saved_chat::SavedChat, saved_chat_ids},
+    frontend::{
+        cli::{elapsed, truncate},
+        ChatEvent, Status,
+    },
+};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rig::message::{Message, UserContent};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::mpsc::Sender,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    User,
+    Assistant,
+    Notice,
+    Error,
+    Tool,
+    Separator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Input,
+    Sidebar,
+}
+
+pub struct Session {
+    pub id: u16,
+    pub preview: String,
+}
+
+pub struct App {
+    input_tx: Sender<String>,
+    chats_dir: PathBuf,
+    pub lines: Vec<(LineKind, String)>,
+    text_open: bool,
+    tool_lines: HashMap<String, usize>,
+    pub input: Vec<String>,
+    pub cursor: (usize, usize),
+    pub scroll: usize,
+    pub focus: Focus,
+    pub sessions: Vec<Session>,
+    pub selected_session: usize,
+    pub status: Status,
+    pub last_usage: Option<(usize, usize)>,
+    pub exit_requested: bool,
+    pub quit: bool,
+}
+
+const PREVIEW_LEN: usize = 24;
+const SCROLL_STEP: usize = 10;
+const MAX_TOOL_TEXT_LEN: usize = 120;
+
+impl App {
+    pub fn new(input_tx: Sender<String>, chats_dir: PathBuf) -> Self {
+        let mut app = Self {
+            input_tx,
+            chats_dir,
+            lines: Vec::new(),
+            text_open: false,
+            tool_lines: HashMap::new(),
+            input: vec![String::new()],
+            cursor: (0, 0),
+            scroll: 0,
+            focus: Focus::Input,
+            sessions: Vec::new(),
+            selected_session: 0,
+            status: Status::default(),
+            last_usage: None,
+            exit_requested: false,
+            quit: false,
+        };
+        app.refresh_sessions();
+        app
+    }
+    pub fn refresh_sessions(&mut self) {
+        self.sessions = saved_chat_ids(&self.chats_dir)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|id| Session {
+                id,
+                preview: session_preview(&self.chats_dir, id).unwrap_or_default(),
+            })
+            .collect();
+        self.selected_session = self
+            .selected_session
+            .min(self.sessions.len().saturating_sub(1));
+    }
+    fn push_lines(&mut self, kind: LineKind, text: &str) {
+        self.text_open = false;
+        for line in text.split('\n') {
+            self.lines.push((kind, line.to_string()));
+        }
+    }
+    pub fn apply(&mut self, event: ChatEvent) {
+        match event {
+            ChatEvent::TextDelta(text) => {
+                for (i, chunk) in text.split('\n').enumerate() {
+                    match self.lines.last_mut() {
+                        Some((LineKind::Assistant, line)) if self.text_open && i == 0 => {
+                            line.push_str(chunk)
+                        }
+                        _ => self.lines.push((LineKind::Assistant, chunk.to_string())),
+                    }
+                    self.text_open = true;
+                }
+            }
+            ChatEvent::TextEnd => self.text_open = false,
+            ChatEvent::Notice(notice) => self.push_lines(LineKind::Notice, &notice),
+            ChatEvent::Error(error) => {
+                self.push_lines(LineKind::Error, &format!("Error: {}", error))
+            }
+            ChatEvent::Separator => self.push_lines(LineKind::Separator, ""),
+            ChatEvent::ToolStarted { id, name, args } => {
+                self.push_lines(
+                    LineKind::Tool,
+                    &format!("[tool] {}({})", name, truncate(&args, MAX_TOOL_TEXT_LEN)),
+                );
+                self.tool_lines.insert(id, self.lines.len() - 1);
+            }
+            ChatEvent::ToolFinished {
+                id,
+                name,
+                output,
+                is_error,
+                elapsed: duration,
+            } => {
+                let result = if is_error {
+                    format!(
+                        " failed after {}: {}",
+                        elapsed(duration),
+                        truncate(&output, MAX_TOOL_TEXT_LEN)
+                    )
+                } else {
+                    format!(
+                        " -> {} ({})",
+                        truncate(&output, MAX_TOOL_TEXT_LEN),
+                        elapsed(duration)
+                    )
+                };
+                match self
+                    .tool_lines
+                    .remove(&id)
+                    .and_then(|index| self.lines.get_mut(index))
+                {
+                    Some((_, line)) => line.push_str(&result),
+                    None => self.push_lines(LineKind::Tool, &format!("[tool] {}{}", name, result)),
+                }
+            }
+            ChatEvent::Usage {
+                input_tokens,
+                output_tokens,
+            } => self.last_usage = Some((input_tokens, output_tokens)),
+            ChatEvent::Status(status) => self.status = status,
+            // the sidebar lists saved chats, so it only changes when one is saved
+            ChatEvent::Saved => self.refresh_sessions(),
+        }
+    }
+    fn submit(&mut self, line: String) {
+        if self.input_tx.send(line).is_err() {
+            self.quit = true;
+        }
+    }
+    fn submit_input(&mut self) {
+        let message = self.input.join("\n");
+        self.push_lines(LineKind::User, &format!("> {}", message));
+        self.input = vec![String::new()];
+        self.cursor = (0, 0);
+        self.scroll = 0;
+        self.submit(message);
+    }
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c' | 'd') if ctrl => {
+                if self.exit_requested {
+                    self.quit = true;
+                } else {
+                    self.exit_requested = true;
+                    self.submit("/exit".to_string());
+                }
+            }
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_add(SCROLL_STEP),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(SCROLL_STEP),
+            KeyCode::Tab => {
+                self.refresh_sessions();
+                self.focus = match self.focus {
+                    Focus::Input if !self.sessions.is_empty() => Focus::Sidebar,
+                    _ => Focus::Input,
+                };
+            }
+            KeyCode::Esc => self.focus = Focus::Input,
+            _ => match self.focus {
+                Focus::Input => self.handle_input_key(key),
+                Focus::Sidebar => self.handle_sidebar_key(key),
+            },
+        }
+    }
+    fn handle_sidebar_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Up => self.selected_session = self.selected_session.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected_session =
+                    (self.selected_session + 1).min(self.sessions.len().saturating_sub(1))
+            }
+            KeyCode::Enter => {
+                if let Some(session) = self.sessions.get(self.selected_session) {
+                    let command = format!("/import {}", session.id);
+                    self.push_lines(LineKind::User, &format!("> {}", command));
+                    self.submit(command);
+                    self.focus = Focus::Input;
+                }
+            }
+            _ => (),
+        }
+    }
+    fn handle_input_key(&mut self, key: KeyEvent) {
+        let (row, col) = self.cursor;
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let newline = key
+            .modifiers
+            .intersects(KeyModifiers::ALT | KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Enter if newline => self.insert_newline(),
+            KeyCode::Char('j') if ctrl => self.insert_newline(),
+            KeyCode::Enter => self.submit_input(),
+            KeyCode::Char(c) if !ctrl => {
+                let index = byte_index(&self.input[row], col);
+                self.input[row].insert(index, c);
+                self.cursor.1 += 1;
+            }
+            KeyCode::Backspace if col > 0 => {
+                let index = byte_index(&self.input[row], col - 1);
+                self.input[row].remove(index);
+                self.cursor.1 -= 1;
+            }
+            KeyCode::Backspace if row > 0 => {
+                let line = self.input.remove(row);
+                let previous = &mut self.input[row - 1];
+                self.cursor = (row - 1, previous.chars().count());
+                previous.push_str(&line);
+            }
+            KeyCode::Delete if col < self.input[row].chars().count() => {
+                let index = byte_index(&self.input[row], col);
+                self.input[row].remove(index);
+            }
+            KeyCode::Delete if row + 1 < self.input.len() => {
+                let line = self.input.remove(row + 1);
+                self.input[row].push_str(&line);
+            }
+            KeyCode::Left if col > 0 => self.cursor.1 -= 1,
+            KeyCode::Left if row > 0 => {
+                self.cursor = (row - 1, self.input[row - 1].chars().count())
+            }
+            KeyCode::Right if col < self.input[row].chars().count() => self.cursor.1 += 1,
+            KeyCode::Right if row + 1 < self.input.len() => self.cursor = (row + 1, 0),
+            KeyCode::Up if row > 0 => {
+                self.cursor = (row - 1, col.min(self.input[row - 1].chars().count()))
+            }
+            KeyCode::Down if row + 1 < self.input.len() => {
+                self.cursor = (row + 1, col.min(self.input[row + 1].chars().count()))
+            }
+            KeyCode::Home => self.cursor.1 = 0,
+            KeyCode::End => self.cursor.1 = self.input[row].chars().count(),
+            _ => (),
+        }
+    }
+    fn insert_newline(&mut self) {
+        let (row, col) = self.cursor;
+        let index = byte_index(&self.input[row], col);
+        let rest = self.input[row].split_off(index);
+        self.input.insert(row + 1, rest);
+        self.cursor = (row + 1, 0);
+    }
+}
+
+fn byte_index(line: &str, col: usize) -> usize {
+    line.char_indices()
+        .nth(col)
+        .map(|(i, _)| i)
+        .unwrap_or(line.len())
+}
+
+fn session_preview(chats_dir: &Path, id: u16) -> Option<String> {
+    let saved = SavedChat::read(&chat_file_path(chats_dir, id)).ok()?;
+    saved.messages.iter().find_map(|message| match message {
+        Message::User { content } => match content.first() {
+            UserContent::Text(text) => Some(text.text().chars().take(PREVIEW_LEN).collect()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::KeyEvent;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_text_deltas_join_into_lines() {
+        let (input_tx, _input_rx) = mpsc::channel();
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut app = App::new(input_tx, chats_dir.path().to_path_buf());
+        app.apply(ChatEvent::TextDelta("Hello".to_string()));
+        app.apply(ChatEvent::TextDelta(", world\nsecond".to_string()));
+        app.apply(ChatEvent::TextEnd);
+        app.apply(ChatEvent::TextDelta("third".to_string()));
+        let lines: Vec<_> = app.lines.iter().map(|(_, line)| line.as_str()).collect();
+        assert_eq!(lines, vec!["Hello, world", "second", "third"]);
+    }
+
+    #[test]
+    fn test_tool_finished_updates_started_line() {
+        let (input_tx, _input_rx) = mpsc::channel();
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut app = App::new(input_tx, chats_dir.path().to_path_buf());
+        app.apply(ChatEvent::ToolStarted {
+            id: "toolu_1".to_string(),
+            name: "add".to_string(),
+            args: r#"{"lhs":1,"rhs":2}"#.to_string(),
+        });
+        app.apply(ChatEvent::Notice("meanwhile".to_string()));
+        app.apply(ChatEvent::ToolFinished {
+            id: "toolu_1".to_string(),
+            name: "add".to_string(),
+            output: "3".to_string(),
+            is_error: false,
+            elapsed: std::time::Duration::from_millis(1300),
+        });
+        assert_eq!(
+            app.lines[0].1,
+            r#"[tool] add({"lhs":1,"rhs":2}) -> 3 (1.3s)"#
+        );
+        assert_eq!(app.lines.len(), 2);
+    }
+
+    #[test]
+    fn test_multi_line_input_submits_joined() {
+        let (input_tx, input_rx) = mpsc::channel();
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut app = App::new(input_tx, chats_dir.path().to_path_buf());
+        for c in "ab".chars() {
+            app.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT));
+        app.handle_key(KeyEvent::from(KeyCode::Char('c')));
+        app.handle_key(KeyEvent::from(KeyCode::Left));
+        app.handle_key(KeyEvent::from(KeyCode::Backspace));
+        app.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(input_rx.recv().unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_sessions_refresh_when_a_chat_is_saved() {
+        let (input_tx, _input_rx) = mpsc::channel();
+        let chats_dir = tempfile::tempdir().unwrap();
+        let mut app = App::new(input_tx, chats_dir.path().to_path_buf());
+        SavedChat::default()
+            .write(&chat_file_path(chats_dir.path(), 4))
+            .unwrap();
+        app.apply(ChatEvent::Status(Status::default()));
+        assert!(app.sessions.is_empty());
+        app.apply(ChatEvent::Saved);
+        assert_eq!(app.sessions.len(), 1);
+        assert_eq!(app.sessions[0].id, 4);
+    }
+}
@@ -0,0 +1,88 @@
+mod app;
+mod render;
+
+use crate::frontend::{ChatEvent, Frontend};
+use app::App;
+use ratatui::{
+    crossterm::event::{self, Event, KeyEventKind},
+    DefaultTerminal,
+};
+use std::{
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+pub struct Tui {
+    events_tx: Option<Sender<ChatEvent>>,
+    input_rx: Receiver<String>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Tui {
+    pub fn start(chats_dir: PathBuf) -> anyhow::Result<Self> {
+        let (events_tx, events_rx) = mpsc::channel();
+        let (input_tx, input_rx) = mpsc::channel();
+        let terminal = ratatui::try_init()?;
+        let handle = std::thread::spawn(move || {
+            let result = run(terminal, App::new(input_tx, chats_dir), events_rx);
+            ratatui::restore();
+            if let Err(e) = result {
+                eprintln!("Error: {}", e);
+            }
+        });
+        Ok(Self {
+            events_tx: Some(events_tx),
+            input_rx,
+            handle: Some(handle),
+        })
+    }
+}
+
+fn run(
+    mut terminal: DefaultTerminal,
+    mut app: App,
+    events_rx: Receiver<ChatEvent>,
+) -> anyhow::Result<()> {
+    while !app.quit {
+        loop {
+            match events_rx.try_recv() {
+                Ok(event) => app.apply(event),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Ok(()),
+            }
+        }
+        terminal.draw(|frame| render::draw(frame, &app))?;
+        if event::poll(POLL_INTERVAL)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key);
+        }
+    }
+    Ok(())
+}
+
+impl Frontend for Tui {
+    fn emit(&mut self, event: ChatEvent) {
+        if let Some(events_tx) = &self.events_tx {
+            let _ = events_tx.send(event);
+        }
+    }
+    fn read_line(&mut self) -> anyhow::Result<String> {
+        // the interface thread is gone once the user force quits, so end the session
+        Ok(self.input_rx.recv().unwrap_or_else(|_| "/exit".to_string()))
+    }
+}
+
+impl Drop for Tui {
+    fn drop(&mut self) {
+        self.events_tx.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
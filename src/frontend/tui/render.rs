@@ -0,0 +1,166 @@
+use crate::frontend::tui::app::{App, Focus, LineKind};
+use ratatui::{
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+const SIDEBAR_WIDTH: u16 = 30;
+const MAX_INPUT_HEIGHT: u16 = 8;
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let input_height = (app.input.len() as u16 + 2).min(MAX_INPUT_HEIGHT);
+    let [main, input, status] = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(input_height),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [sidebar, conversation] =
+        Layout::horizontal([Constraint::Length(SIDEBAR_WIDTH), Constraint::Min(10)]).areas(main);
+    draw_sidebar(frame, app, sidebar);
+    draw_conversation(frame, app, conversation);
+    draw_input(frame, app, input);
+    draw_status(frame, app, status);
+}
+
+fn border_style(focused: bool) -> Style {
+    if focused {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    }
+}
+
+fn draw_sidebar(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .sessions
+        .iter()
+        .map(|session| ListItem::new(format!("#{} {}", session.id, session.preview)))
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border_style(app.focus == Focus::Sidebar))
+                .title(" Sessions (Tab) "),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    if app.focus == Focus::Sidebar {
+        state.select(Some(app.selected_session));
+    }
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    if text.is_empty() || width == 0 {
+        return vec![String::new()];
+    }
+    text.chars()
+        .collect::<Vec<_>>()
+        .chunks(width)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+fn draw_conversation(frame: &mut Frame, app: &App, area: Rect) {
+    let width = area.width.saturating_sub(2) as usize;
+    let height = area.height.saturating_sub(2) as usize;
+    let lines: Vec<Line> = app
+        .lines
+        .iter()
+        .flat_map(|(kind, text)| {
+            let style = match kind {
+                LineKind::User => Style::default().fg(Color::Green),
+                LineKind::Assistant => Style::default(),
+                LineKind::Notice => Style::default().fg(Color::Yellow),
+                LineKind::Error => Style::default().fg(Color::Red),
+                LineKind::Tool => Style::default().fg(Color::Magenta),
+                LineKind::Separator => Style::default().fg(Color::DarkGray),
+            };
+            let text = match kind {
+                LineKind::Separator => "─".repeat(width),
+                _ => text.clone(),
+            };
+            wrap(&text, width)
+                .into_iter()
+                .map(move |line| Line::from(Span::styled(line, style)))
+        })
+        .collect();
+    let scroll = app.scroll.min(lines.len().saturating_sub(height));
+    let start = lines.len().saturating_sub(height + scroll);
+    let visible: Vec<Line> = lines.into_iter().skip(start).take(height).collect();
+    let title = if scroll > 0 {
+        format!(" Marvin (scrolled up {} lines, PgDn) ", scroll)
+    } else {
+        " Marvin (PgUp/PgDn to scroll) ".to_string()
+    };
+    let paragraph = Paragraph::new(visible).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style(false))
+            .title(title),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_input(frame: &mut Frame, app: &App, area: Rect) {
+    let height = area.height.saturating_sub(2) as usize;
+    let (row, col) = app.cursor;
+    let offset = (row + 1).saturating_sub(height);
+    let lines: Vec<Line> = app
+        .input
+        .iter()
+        .skip(offset)
+        .map(|line| Line::from(line.as_str()))
+        .collect();
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style(app.focus == Focus::Input))
+            .title(" Message (Enter to send, Alt+Enter for newline) "),
+    );
+    frame.render_widget(paragraph, area);
+    if app.focus == Focus::Input {
+        frame.set_cursor_position(Position::new(
+            area.x + 1 + col as u16,
+            area.y + 1 + (row - offset) as u16,
+        ));
+    }
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let status = &app.status;
+    let fill = if status.context_window > 0 {
+        status.context_tokens as f64 / status.context_window as f64 * 100.0
+    } else {
+        0.0
+    };
+    let model = if status.model.is_empty() {
+        "not set"
+    } else {
+        status.model.as_str()
+    };
+    let line = Line::from(vec![
+        Span::styled(
+            format!(" {} ", model),
+            Style::default().add_modifier(Modifier::REVERSED),
+        ),
+        Span::raw(format!(
+            " context {}/{} ({:.1}%) ",
+            status.context_tokens, status.context_window, fill
+        )),
+        Span::raw(format!("| cost ${:.4} ", status.cost_usd)),
+        Span::raw(match app.last_usage {
+            Some((input_tokens, output_tokens)) => {
+                format!("| last turn {} in / {} out ", input_tokens, output_tokens)
+            }
+            None => String::new(),
+        }),
+        Span::styled("| Ctrl+C to exit", Style::default().fg(Color::DarkGray)),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
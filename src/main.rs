@@ -1,15 +1,42 @@
 mod agent_tools;
 mod anthropic;
+mod cassette;
 mod chat;
+mod frontend;
 mod runner;
+#[cfg(test)]
+mod testing;
 mod ui;
 mod user_commands;
 
-use crate::{chat::Chat, runner::Runner};
+use crate::{
+    cassette::HttpTransport,
+    chat::{config::Config, Chat},
+    frontend::{Cli, Frontend, Tui},
+    runner::Runner,
+};
+
+fn flag_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1).cloned())
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let state = Chat::new().await?;
+    let mut config = Config::from_env()?;
+    if let Some(dir) = flag_value("--record") {
+        config = config.with_transport(HttpTransport::record(dir)?);
+    } else if let Some(dir) = flag_value("--replay") {
+        config = config.with_transport(HttpTransport::replay(dir)?);
+    }
+    let frontend: Box<dyn Frontend> = if std::env::args().any(|arg| arg == "--tui") {
+        Box::new(Tui::start(config.chats_dir().to_path_buf())?)
+    } else {
+        Box::new(Cli)
+    };
+    let state = Chat::new(config, frontend).await?;
     Runner::run(state).await?;
     Ok(())
 }
@@ -1,6 +1,5 @@
 use crate::{
     chat::{Chat, ChatInput},
-    ui::horizontal_line,
     user_commands::*,
 };
 use rig::message::Message;
@@ -9,11 +8,12 @@ pub struct Runner;
 
 impl Runner {
     pub async fn run(mut chat: Chat) -> anyhow::Result<()> {
-        println!("Agent: {}", chat.model());
-        horizontal_line();
-        println!("Type a message and click enter to submit");
+        chat.notice(format!("Agent: {}", chat.model()));
+        chat.emit_status();
+        chat.separator();
+        chat.notice("Type a message and click enter to submit");
         loop {
-            horizontal_line();
+            chat.separator();
             if chat.input().is_none() {
                 chat.get_input();
             }
@@ -42,16 +42,63 @@ impl Runner {
                     chat.show_token_usage();
                     continue;
                 }
+                ChatInput::ShowCreditUsage => {
+                    chat.show_credit_usage().await;
+                    continue;
+                }
+                ChatInput::ShowSources => {
+                    chat.show_sources();
+                    continue;
+                }
                 ChatInput::SwitchModel => {
                     chat.switch_model()?;
                     continue;
                 }
                 ChatInput::ShowContextSummary => {
-                    chat.show_context_summary().await?;
+                    if let Err(e) = chat.show_context_summary().await {
+                        chat.error(e);
+                    }
                     continue;
                 }
                 ChatInput::CompactContext => {
-                    chat.compact_context().await?;
+                    if let Err(e) = chat.compact_context().await {
+                        chat.error(e);
+                    }
+                    continue;
+                }
+                ChatInput::ReviewFile(path) => {
+                    let path = path.to_owned();
+                    if let Err(e) = chat.review_file(path).await {
+                        chat.error(e);
+                    }
+                    continue;
+                }
+                ChatInput::Permissions(args) => {
+                    let args = args.to_owned();
+                    if let Err(e) = chat.manage_permissions(&args).await {
+                        chat.error(e);
+                    }
+                    continue;
+                }
+                ChatInput::Cache(args) => {
+                    let args = args.to_owned();
+                    if let Err(e) = chat.manage_cache(&args) {
+                        chat.error(e);
+                    }
+                    continue;
+                }
+                ChatInput::Research(question) => {
+                    let question = question.to_owned();
+                    if let Err(e) = chat.research_question(question).await {
+                        chat.error(e);
+                    }
+                    continue;
+                }
+                ChatInput::Snapshot(args) => {
+                    let args = args.to_owned();
+                    if let Err(e) = chat.take_snapshot(&args).await {
+                        chat.error(e);
+                    }
                     continue;
                 }
                 ChatInput::None => continue,
@@ -61,13 +108,16 @@ impl Runner {
                 }
                 ChatInput::SendMessage(message) => {
                     if message.is_empty() {
-                        println!("Type a message and click enter");
+                        chat.notice("Type a message and click enter");
                         chat.clear_input();
                         continue;
                     }
                     let message = message.to_owned();
                     chat.clear_input();
-                    chat.stream(Message::user(message)).await;
+                    if let Err(e) = chat.stream(Message::user(message)).await {
+                        chat.error(e);
+                        chat.notice("Message was not sent, chat history is unchanged");
+                    }
                 }
             }
         }
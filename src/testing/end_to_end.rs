@@ -0,0 +1,1273 @@
+use crate::{
+    agent_tools::web::provider::ProviderKind,
+    anthropic::get_models::GetAnthropicModels,
+    chat::{
+        chat_file_path, permissions::Permissions, research::ResearchLimits, saved_chat::SavedChat,
+        ChatError,
+    },
+    frontend::ChatEvent,
+    runner::Runner,
+    testing::{notices, streamed_text, text, tool_use, Harness},
+    user_commands::{
+        compact_context::CompactContext, import_chat_history::ImportChatHistory,
+        manage_cache::ManageCache, research_question::ResearchQuestion,
+        save_chat_history::SaveChatHistory, show_credit_usage::ShowCreditUsage,
+    },
+};
+use rig::message::{AssistantContent, Message, UserContent};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+fn saved_messages(harness: &Harness, id: u16) -> Vec<Message> {
+    saved_chat(harness, id).messages
+}
+
+fn saved_chat(harness: &Harness, id: u16) -> SavedChat {
+    SavedChat::read(&chat_file_path(harness.chats_dir(), id)).unwrap()
+}
+
+#[tokio::test]
+async fn test_repl_commands() {
+    let harness = Harness::start().await;
+    let (chat, events) = harness.chat(0, &["/help", "/tokens", "/history", "/clear"]);
+    Runner::run(chat).await.unwrap();
+    let notices = notices(&events);
+    assert!(notices.iter().any(|notice| notice == "Commands:"));
+    assert!(notices.contains(&"Total Input Tokens Used: 0".to_string()));
+    assert!(notices.contains(&"No chat history".to_string()));
+    assert_eq!(notices.last().unwrap(), "Farewell!");
+    assert!(saved_messages(&harness, 0).is_empty());
+}
+
+#[tokio::test]
+async fn test_streamed_message_is_saved_on_exit() {
+    let harness = Harness::start().await;
+    harness
+        .expect_stream(vec![text("Hello from the mock")])
+        .await;
+    let (chat, events) = harness.chat(3, &["hi there"]);
+    Runner::run(chat).await.unwrap();
+    assert_eq!(streamed_text(&events), "Hello from the mock");
+    assert!(events.lock().unwrap().iter().any(|event| matches!(
+        event,
+        ChatEvent::Usage {
+            input_tokens: 100,
+            output_tokens: 20
+        }
+    )));
+    let messages = saved_messages(&harness, 3);
+    assert_eq!(messages.len(), 2);
+    assert!(matches!(
+        &messages[1],
+        Message::Assistant { content, .. }
+            if matches!(content.first(), AssistantContent::Text(text) if text.text == "Hello from the mock")
+    ));
+    let request = &harness.requests("/v1/messages").await[0];
+    assert_eq!(request["messages"][0]["content"][0]["text"], "hi there");
+}
+
+#[tokio::test]
+async fn test_tool_loop_calls_tavily() {
+    let harness = Harness::start().await;
+    harness
+        .tavily(
+            "/search",
+            json!({
+                "query": "rust 2024",
+                "results": [
+                    { "title": "Rust 2024", "url": "https://blog.rust-lang.org", "content": "Rust 2024 is stable", "score": 0.8 },
+                    { "title": "Editions", "url": "https://doc.rust-lang.org/edition-guide", "content": "The edition guide", "score": 0.9 }
+                ],
+                "response_time": 1.2,
+                "request_id": "req-1"
+            }),
+        )
+        .await;
+    harness
+        .expect_stream(vec![
+            text("Let me look that up."),
+            tool_use("toolu_1", "search_web", json!({ "query": "rust 2024" })),
+        ])
+        .await;
+    harness
+        .expect_stream(vec![text("Rust 2024 shipped.")])
+        .await;
+    let (mut chat, events) = harness.chat(0, &[]);
+    chat.stream(Message::user("what is new in rust?"))
+        .await
+        .unwrap();
+    assert_eq!(chat.chat_history().len(), 4);
+    assert_eq!(
+        harness.requests("/search").await,
+        vec![json!({ "query": "rust 2024", "include_usage": true })]
+    );
+    let requests = harness.requests("/v1/messages").await;
+    assert_eq!(requests.len(), 2);
+    let tool_result = &requests[1]["messages"][2]["content"][0];
+    assert_eq!(tool_result["type"], "tool_result");
+    assert_eq!(tool_result["tool_use_id"], "toolu_1");
+    let output: Value =
+        serde_json::from_str(tool_result["content"][0]["text"].as_str().unwrap()).unwrap();
+    assert_eq!(
+        output["results"][0]["url"],
+        "https://doc.rust-lang.org/edition-guide"
+    );
+    assert!(output.get("request_id").is_none());
+    assert!(events.lock().unwrap().iter().any(|event| matches!(
+        event,
+        ChatEvent::ToolFinished { name, is_error: false, .. } if name == "search_web"
+    )));
+    assert!(streamed_text(&events).ends_with("Rust 2024 shipped."));
+}
+
+#[tokio::test]
+async fn test_tool_calls_in_one_turn_run_concurrently_in_order() {
+    let harness = Harness::start().await;
+    let results = |query: &str| {
+        json!({
+            "query": query,
+            "results": [{ "title": query, "url": format!("https://example.com/{}", query), "content": query, "score": 0.9 }]
+        })
+    };
+    harness
+        .tavily_slow(
+            "/search",
+            "slow",
+            Duration::from_millis(600),
+            results("slow"),
+        )
+        .await;
+    harness
+        .tavily_slow(
+            "/search",
+            "fast",
+            Duration::from_millis(100),
+            results("fast"),
+        )
+        .await;
+    harness
+        .expect_stream(vec![
+            tool_use("toolu_1", "search_web", json!({ "query": "slow" })),
+            tool_use("toolu_2", "search_web", json!({ "query": "fast" })),
+        ])
+        .await;
+    harness.expect_stream(vec![text("Both found.")]).await;
+    let (mut chat, events) = harness.chat(0, &[]);
+    chat.stream(Message::user("search both")).await.unwrap();
+    let finished: Vec<(String, Duration)> = events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|event| match event {
+            ChatEvent::ToolFinished { id, elapsed, .. } => Some((id.clone(), *elapsed)),
+            _ => None,
+        })
+        .collect();
+    // the fast call, requested second, can only finish first if it ran alongside the slow one
+    assert_eq!(finished[0].0, "toolu_2");
+    assert_eq!(finished[1].0, "toolu_1");
+    assert!(finished[1].1 >= Duration::from_millis(600));
+    let requests = harness.requests("/v1/messages").await;
+    let content = &requests[1]["messages"][2]["content"];
+    for (i, (id, query)) in [("toolu_1", "slow"), ("toolu_2", "fast")]
+        .iter()
+        .enumerate()
+    {
+        assert_eq!(content[i]["tool_use_id"], *id);
+        let output = content[i]["content"][0]["text"].as_str().unwrap();
+        assert!(output.contains(&format!("https://example.com/{}", query)));
+    }
+}
+
+#[tokio::test]
+async fn test_math_tool_loop() {
+    let harness = Harness::start().await;
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "calculate",
+            json!({ "expression": "(2 + 40) / 4" }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("10.5")]).await;
+    let (mut chat, _) = harness.chat(0, &[]);
+    chat.stream(Message::user("(2 + 40) / 4?")).await.unwrap();
+    let requests = harness.requests("/v1/messages").await;
+    let tool_result = &requests[1]["messages"][2]["content"][0]["content"][0];
+    assert_eq!(
+        tool_result["text"],
+        r#"{"result":"10.5","exact":true,"fraction":"21/2"}"#
+    );
+}
+
+#[tokio::test]
+async fn test_compaction_replaces_history_with_summary() {
+    let harness = Harness::start().await;
+    harness.expect_stream(vec![text("First answer")]).await;
+    harness.expect_completion("We talked about testing.").await;
+    let (mut chat, events) = harness.chat(0, &[]);
+    chat.stream(Message::user("first question")).await.unwrap();
+    chat.compact_context().await.unwrap();
+    assert_eq!(chat.chat_history().len(), 1);
+    assert!(matches!(
+        &chat.chat_history()[0],
+        Message::Assistant { content, .. }
+            if matches!(content.first(), AssistantContent::Text(text) if text.text == "We talked about testing.")
+    ));
+    assert!(notices(&events).contains(&"Compaction completed in 4 words".to_string()));
+    let compaction_request = &harness.requests("/v1/messages").await[1];
+    assert_eq!(compaction_request["messages"].as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_save_and_import() {
+    let harness = Harness::start().await;
+    harness.expect_stream(vec![text("Saved answer")]).await;
+    let (mut first, _) = harness.chat(0, &[]);
+    first.stream(Message::user("remember this")).await.unwrap();
+    first.save_chat_history().unwrap();
+    let (mut second, events) = harness.chat(1, &[]);
+    second.import_chat_history(0);
+    assert!(notices(&events).contains(&"chat_history with ID: 0 found!".to_string()));
+    assert_eq!(
+        serde_json::to_value(second.chat_history()).unwrap(),
+        serde_json::to_value(first.chat_history()).unwrap()
+    );
+    assert!(matches!(
+        &second.chat_history()[0],
+        Message::User { content } if matches!(content.first(), UserContent::Text(text) if text.text == "remember this")
+    ));
+}
+
+#[tokio::test]
+async fn test_rate_limit_is_retried() {
+    let harness = Harness::start().await;
+    harness.expect_error(429, "rate_limit_error", Some(0)).await;
+    harness.expect_stream(vec![text("Recovered")]).await;
+    let (mut chat, events) = harness.chat(0, &[]);
+    chat.stream(Message::user("hello")).await.unwrap();
+    assert_eq!(streamed_text(&events), "Recovered");
+    assert_eq!(chat.chat_history().len(), 2);
+    assert!(events.lock().unwrap().iter().any(
+        |event| matches!(event, ChatEvent::Error(error) if error.contains("retrying in 0.0s"))
+    ));
+}
+
+#[tokio::test]
+async fn test_stream_broken_after_output_is_not_retried() {
+    let harness = Harness::start().await;
+    harness.expect_broken_stream("Half an ans").await;
+    harness
+        .expect_stream(vec![text("Half an answer, again")])
+        .await;
+    let (mut chat, events) = harness.chat(0, &[]);
+    let error = chat.stream(Message::user("hello")).await.unwrap_err();
+    assert!(error.is_transient());
+    assert_eq!(streamed_text(&events), "Half an ans");
+    assert!(chat.chat_history().is_empty());
+    assert_eq!(chat.total_output_tokens_used(), 0);
+    assert_eq!(harness.requests("/v1/messages").await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_stream_broken_before_output_is_retried() {
+    let harness = Harness::start().await;
+    harness.expect_broken_stream("\n").await;
+    harness.expect_stream(vec![text("Recovered")]).await;
+    let (mut chat, events) = harness.chat(0, &[]);
+    chat.stream(Message::user("hello")).await.unwrap();
+    assert_eq!(streamed_text(&events), "Recovered");
+    assert_eq!(chat.total_output_tokens_used(), 20);
+    assert_eq!(harness.requests("/v1/messages").await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_fatal_error_rolls_back_history() {
+    let harness = Harness::start().await;
+    harness
+        .expect_error(401, "authentication_error", None)
+        .await;
+    let (mut chat, _) = harness.chat(0, &[]);
+    let error = chat.stream(Message::user("hello")).await.unwrap_err();
+    assert!(matches!(error, ChatError::Auth(_)));
+    assert!(chat.chat_history().is_empty());
+    assert_eq!(harness.requests("/v1/messages").await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_get_models() {
+    let harness = Harness::start().await;
+    harness.models().await;
+    let models = harness.config().get_models().await.unwrap();
+    let ids: Vec<Value> = models.iter().map(|model| json!(model.id)).collect();
+    assert_eq!(ids, vec![json!("claude-test-1"), json!("claude-test-2")]);
+}
+
+#[tokio::test]
+async fn test_review_command_sends_lints_to_agent() {
+    let harness = Harness::start().await;
+    harness.expect_stream(vec![text("Drop the return.")]).await;
+    let file = harness.chats_dir().join("lib.rs");
+    std::fs::write(&file, "pub fn one() -> i32 {\n    return 1;\n}\n").unwrap();
+    let command = format!("/review {}", file.display());
+    let (chat, events) = harness.chat(0, &[&command]);
+    Runner::run(chat).await.unwrap();
+    assert!(notices(&events)
+        .iter()
+        .any(|notice| notice.ends_with("0 error(s), 1 warning(s), formatted")));
+    let request = &harness.requests("/v1/messages").await[0];
+    let prompt = request["messages"][0]["content"][0]["text"]
+        .as_str()
+        .unwrap();
+    assert!(prompt.contains("clippy::needless_return"));
+    assert_eq!(streamed_text(&events), "Drop the return.");
+}
+
+async fn write_file_with_answer(answer: &str) -> (Harness, Value) {
+    let harness = Harness::start().await;
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "write_file",
+            json!({ "path": "notes/todo.txt", "content": "ship it\n" }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("Done.")]).await;
+    let (mut chat, events) = harness.chat(0, &[answer]);
+    chat.stream(Message::user("write a todo")).await.unwrap();
+    assert!(notices(&events)
+        .iter()
+        .any(|notice| notice.contains("+ship it")));
+    let requests = harness.requests("/v1/messages").await;
+    let tool_result = requests[1]["messages"][2]["content"][0].clone();
+    (harness, tool_result)
+}
+
+#[tokio::test]
+async fn test_confirmed_write_is_applied() {
+    let (harness, tool_result) = write_file_with_answer("y").await;
+    let written = std::fs::read_to_string(harness.chats_dir().join("notes/todo.txt")).unwrap();
+    assert_eq!(written, "ship it\n");
+    assert!(tool_result["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains(r#""created":true"#));
+}
+
+#[tokio::test]
+async fn test_declined_write_is_not_applied() {
+    let (harness, tool_result) = write_file_with_answer("n").await;
+    assert!(!harness.chats_dir().join("notes").exists());
+    assert!(tool_result["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("declined"));
+}
+
+#[tokio::test]
+async fn test_patch_after_write_in_one_turn_previews_the_written_file() {
+    let harness = Harness::start().await;
+    harness
+        .expect_stream(vec![
+            tool_use(
+                "toolu_1",
+                "write_file",
+                json!({ "path": "notes.txt", "content": "draft\n" }),
+            ),
+            tool_use(
+                "toolu_2",
+                "apply_patch",
+                json!({ "path": "notes.txt", "search": "draft", "replace": "final" }),
+            ),
+        ])
+        .await;
+    harness.expect_stream(vec![text("Done.")]).await;
+    let (mut chat, events) = harness.chat(0, &["y", "y"]);
+    chat.stream(Message::user("write then fix the notes"))
+        .await
+        .unwrap();
+    // the patch is asked about after the write ran, so its diff is against the new file
+    let notices = notices(&events);
+    assert!(notices.iter().any(|notice| notice.contains("-draft")));
+    assert!(notices.iter().any(|notice| notice.contains("+final")));
+    assert_eq!(
+        std::fs::read_to_string(harness.chats_dir().join("notes.txt")).unwrap(),
+        "final\n"
+    );
+}
+
+#[tokio::test]
+async fn test_always_approves_repeated_command() {
+    let harness = Harness::start().await;
+    for id in ["toolu_1", "toolu_2"] {
+        harness
+            .expect_stream(vec![tool_use(
+                id,
+                "run_command",
+                json!({ "command": "echo 'hello there'" }),
+            )])
+            .await;
+    }
+    harness.expect_stream(vec![text("It printed hello.")]).await;
+    let (mut chat, events) = harness.chat(0, &["always"]);
+    chat.stream(Message::user("say hello twice")).await.unwrap();
+    let prompts = notices(&events)
+        .into_iter()
+        .filter(|notice| notice == "`echo hello there` in .")
+        .count();
+    assert_eq!(prompts, 1);
+    let requests = harness.requests("/v1/messages").await;
+    for (request, index) in requests[1..].iter().zip([2, 4]) {
+        let tool_result = &request["messages"][index]["content"][0]["content"][0]["text"];
+        assert!(tool_result
+            .as_str()
+            .unwrap()
+            .contains(r#""stdout":"hello there\n""#));
+    }
+}
+
+#[tokio::test]
+async fn test_command_outside_allowlist_is_rejected_without_prompt() {
+    let harness = Harness::start().await;
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "run_command",
+            json!({ "command": "rm -rf src" }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("I can't do that.")]).await;
+    let (mut chat, events) = harness.chat(0, &[]);
+    chat.stream(Message::user("delete src")).await.unwrap();
+    assert!(!notices(&events)
+        .iter()
+        .any(|notice| notice.starts_with("Run `")));
+    let requests = harness.requests("/v1/messages").await;
+    let tool_result = requests[1]["messages"][2]["content"][0]["content"][0]["text"]
+        .as_str()
+        .unwrap();
+    assert!(tool_result.contains("denied by the 'rm' rule"));
+}
+
+#[tokio::test]
+async fn test_never_permission_returns_tool_error() {
+    let harness = Harness::start().await;
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "search_web",
+            json!({ "query": "rust 2024" }),
+        )])
+        .await;
+    harness
+        .expect_stream(vec![text("Search is disabled.")])
+        .await;
+    let mut permissions = Permissions::default();
+    permissions.apply_rules("search_web=never").unwrap();
+    let config = harness.config().with_permissions(permissions);
+    let (mut chat, _) = harness.chat_with_config(0, config, &[]);
+    chat.stream(Message::user("search for rust 2024"))
+        .await
+        .unwrap();
+    assert!(harness.requests("/search").await.is_empty());
+    let requests = harness.requests("/v1/messages").await;
+    let tool_result = requests[1]["messages"][2]["content"][0]["content"][0]["text"]
+        .as_str()
+        .unwrap();
+    assert!(tool_result.contains("disabled the search_web tool"));
+}
+
+#[tokio::test]
+async fn test_permissions_command_shows_and_sets_approval() {
+    let harness = Harness::start().await;
+    let (chat, events) = harness.chat(
+        0,
+        &[
+            "/permissions crawl_website never",
+            "/permissions",
+            "/permissions nope ask",
+        ],
+    );
+    Runner::run(chat).await.unwrap();
+    let notices = notices(&events);
+    assert!(notices.contains(&"crawl_website set to never".to_string()));
+    assert!(notices
+        .iter()
+        .any(|notice| notice.split_whitespace().eq(["crawl_website", "never"])));
+    assert!(notices
+        .iter()
+        .any(|notice| notice.split_whitespace().eq(["write_file", "ask"])));
+    assert!(notices
+        .iter()
+        .any(|notice| notice.starts_with("Unknown tool 'nope'")));
+}
+
+#[tokio::test]
+async fn test_crawl_output_is_capped_to_token_budget() {
+    let harness = Harness::start().await;
+    let page = |i: usize| json!({ "url": format!("https://docs.rs/page/{}", i), "raw_content": "lorem ipsum ".repeat(500) });
+    harness
+        .tavily(
+            "/crawl",
+            json!({ "base_url": "https://docs.rs", "results": (0..50).map(page).collect::<Vec<_>>(), "response_time": 9.5 }),
+        )
+        .await;
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "crawl_website",
+            json!({ "url": "https://docs.rs" }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("Crawled.")]).await;
+    let config = harness.config().with_web_token_budget(1000);
+    let (mut chat, _) = harness.chat_with_config(0, config, &["y"]);
+    chat.stream(Message::user("crawl docs.rs")).await.unwrap();
+    let requests = harness.requests("/v1/messages").await;
+    let text = requests[1]["messages"][2]["content"][0]["content"][0]["text"]
+        .as_str()
+        .unwrap();
+    assert!(text.len() < 1000 * 4 + 1000, "{}", text.len());
+    let output: Value = serde_json::from_str(text).unwrap();
+    let kept = output["results"].as_array().unwrap().len();
+    assert_eq!(
+        output["omitted"],
+        format!(
+            "{} more page(s) omitted to stay within the 1000-token budget",
+            50 - kept
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_unexpected_tavily_response_is_a_tool_error() {
+    let harness = Harness::start().await;
+    harness
+        .tavily(
+            "/map",
+            json!({ "base_url": "https://docs.rs", "results": "not a list" }),
+        )
+        .await;
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "map_website",
+            json!({ "url": "https://docs.rs" }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("The map failed.")]).await;
+    let (mut chat, events) = harness.chat(0, &["y"]);
+    chat.stream(Message::user("map docs.rs")).await.unwrap();
+    assert!(events.lock().unwrap().iter().any(|event| matches!(
+        event,
+        ChatEvent::ToolFinished { output, is_error: true, .. }
+            if output.contains("Tavily /map returned an unexpected response")
+    )));
+}
+
+#[tokio::test]
+async fn test_repeated_search_is_served_from_cache() {
+    let harness = Harness::start().await;
+    harness
+        .tavily(
+            "/search",
+            json!({ "query": "rust", "results": [{ "title": "Rust", "url": "https://www.rust-lang.org", "content": "A language empowering everyone", "score": 0.9 }] }),
+        )
+        .await;
+    for (id, query) in [("toolu_1", "Rust  release"), ("toolu_2", "rust release ")] {
+        harness
+            .expect_stream(vec![tool_use(id, "search_web", json!({ "query": query }))])
+            .await;
+        harness.expect_stream(vec![text("Found it.")]).await;
+    }
+    let (mut chat, events) = harness.chat(0, &[]);
+    chat.stream(Message::user("search rust")).await.unwrap();
+    chat.stream(Message::user("again")).await.unwrap();
+    assert_eq!(harness.requests("/search").await.len(), 1);
+    let requests = harness.requests("/v1/messages").await;
+    let result = |request: &Value, index: usize| {
+        let text = request["messages"][index]["content"][0]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        serde_json::from_str::<Value>(&text).unwrap()
+    };
+    assert!(result(&requests[1], 2)["cache"].is_null());
+    let cached = result(&requests[3], 6);
+    assert_eq!(cached["results"][0]["title"], "Rust");
+    assert!(cached["cache"]
+        .as_str()
+        .unwrap()
+        .starts_with("Served from the local cache (fetched just now)"));
+
+    chat.manage_cache("stats").unwrap();
+    chat.manage_cache("clear").unwrap();
+    let notices = notices(&events);
+    assert!(notices.iter().any(|notice| notice
+        .split_whitespace()
+        .take(4)
+        .eq(["/search", "1", "entries", "(0"])));
+    assert!(notices.contains(&"This session: 1 hits, 1 misses".to_string()));
+    assert!(notices.contains(&"Removed 1 cached responses".to_string()));
+}
+
+#[tokio::test]
+async fn test_credits_are_recorded_per_tool() {
+    let harness = Harness::start().await;
+    harness
+        .tavily(
+            "/search",
+            json!({ "query": "rust", "results": [], "usage": { "credits": 2 } }),
+        )
+        .await;
+    harness
+        .tavily_get(
+            "/usage",
+            json!({
+                "key": { "usage": 150, "limit": null },
+                "account": { "current_plan": "Researcher", "plan_usage": 500, "plan_limit": 1000 }
+            }),
+        )
+        .await;
+    for _ in 0..2 {
+        harness
+            .expect_stream(vec![tool_use(
+                "toolu_1",
+                "search_web",
+                json!({ "query": "rust", "search_depth": "advanced" }),
+            )])
+            .await;
+        harness.expect_stream(vec![text("Nothing found.")]).await;
+    }
+    let (mut chat, events) = harness.chat(0, &[]);
+    chat.stream(Message::user("search rust")).await.unwrap();
+    chat.stream(Message::user("again")).await.unwrap();
+    let searches = harness.requests("/search").await;
+    assert_eq!(searches.len(), 1);
+    assert_eq!(searches[0]["include_usage"], true);
+
+    chat.show_credit_usage().await;
+    let notices = notices(&events);
+    assert!(notices.contains(&"Tavily Credits Used This Session: 2".to_string()));
+    assert!(notices.iter().any(|notice| notice.split_whitespace().eq([
+        "search_web",
+        "2",
+        "calls",
+        "(1",
+        "cached),",
+        "2",
+        "credits"
+    ])));
+    assert!(
+        notices.contains(&"Account (Researcher plan): 500 of 1000 used, 500 remaining".to_string())
+    );
+    assert!(notices.contains(&"API key: 150 used, no limit".to_string()));
+}
+
+fn tool_output(request: &Value, index: usize) -> String {
+    request["messages"][index]["content"][0]["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_searxng_provider_searches_and_fetches_pages_directly() {
+    let harness = Harness::start().await;
+    harness
+        .tavily_get(
+            "/search",
+            json!({
+                "query": "rust",
+                "results": [
+                    { "url": "https://www.rust-lang.org/", "title": "Rust", "content": "A language empowering everyone", "score": 2.0 },
+                    { "url": "https://example.com/rust", "title": "Elsewhere", "content": "Off-site" }
+                ],
+                "answers": []
+            }),
+        )
+        .await;
+    harness
+        .page(
+            "/guide",
+            "text/html; charset=utf-8",
+            "<html><head><title>Guide</title></head><body><p>Read the <b>book</b>.</p></body></html>",
+        )
+        .await;
+    let guide = format!("{}/guide", harness.uri());
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "search_web",
+            json!({ "query": "rust", "include_domains": ["rust-lang.org"] }),
+        )])
+        .await;
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_2",
+            "extract_url",
+            json!({ "urls": [guide] }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("Done.")]).await;
+    let config = harness
+        .config()
+        .with_web_provider(ProviderKind::Searxng)
+        .with_searxng_url(harness.uri());
+    let (mut chat, _) = harness.chat_with_config(0, config, &[]);
+    chat.stream(Message::user("find rust")).await.unwrap();
+    assert!(harness.requests("/search").await.is_empty());
+    let requests = harness.requests("/v1/messages").await;
+    let search: Value = serde_json::from_str(&tool_output(&requests[1], 2)).unwrap();
+    assert_eq!(search["results"].as_array().unwrap().len(), 1);
+    assert_eq!(search["results"][0]["title"], "Rust");
+    let extract: Value = serde_json::from_str(&tool_output(&requests[2], 4)).unwrap();
+    assert_eq!(
+        extract["results"][0]["content"],
+        "# Guide\n\nRead the **book**."
+    );
+}
+
+#[tokio::test]
+async fn test_brave_provider_strips_snippet_markup() {
+    let harness = Harness::start().await;
+    harness
+        .tavily_get(
+            "/res/v1/web/search",
+            json!({
+                "query": { "original": "rust" },
+                "web": { "results": [
+                    { "url": "https://www.rust-lang.org/", "title": "<strong>Rust</strong> Programming Language", "description": "A language empowering <strong>everyone</strong>" }
+                ] }
+            }),
+        )
+        .await;
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "search_web",
+            json!({ "query": "rust" }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("Done.")]).await;
+    let config = harness
+        .config()
+        .with_web_provider(ProviderKind::Brave)
+        .with_brave_api_key("test-brave-key")
+        .with_brave_base_url(harness.uri());
+    let (mut chat, _) = harness.chat_with_config(0, config, &[]);
+    chat.stream(Message::user("find rust")).await.unwrap();
+    let requests = harness.requests("/v1/messages").await;
+    let search: Value = serde_json::from_str(&tool_output(&requests[1], 2)).unwrap();
+    assert_eq!(search["results"][0]["title"], "Rust Programming Language");
+    assert_eq!(
+        search["results"][0]["content"],
+        "A language empowering everyone"
+    );
+}
+
+#[tokio::test]
+async fn test_local_provider_crawls_same_site_links() {
+    let harness = Harness::start().await;
+    harness
+        .page(
+            "/docs",
+            "text/html",
+            r#"<h1>Docs</h1><a href="/docs/a">A</a> <a href="b">B</a> <a href="https://example.com/">Out</a>"#,
+        )
+        .await;
+    harness
+        .page("/docs/a", "text/html", "<h1>Page A</h1>")
+        .await;
+    harness.page("/b", "text/plain", "plain B").await;
+    let docs = format!("{}/docs", harness.uri());
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "crawl_website",
+            json!({ "url": docs }),
+        )])
+        .await;
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_2",
+            "search_web",
+            json!({ "query": "rust" }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("Done.")]).await;
+    let config = harness.config().with_web_provider(ProviderKind::Local);
+    let (mut chat, events) = harness.chat_with_config(0, config, &["y"]);
+    chat.stream(Message::user("crawl the docs")).await.unwrap();
+    let requests = harness.requests("/v1/messages").await;
+    let crawl: Value = serde_json::from_str(&tool_output(&requests[1], 2)).unwrap();
+    let mut contents: Vec<&str> = crawl["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|page| page["content"].as_str().unwrap())
+        .collect();
+    contents.sort();
+    assert_eq!(contents.len(), 3);
+    assert_eq!(contents[1..], ["# Page A", "plain B"]);
+    assert!(events.lock().unwrap().iter().any(|event| matches!(
+        event,
+        ChatEvent::ToolFinished { output, is_error: true, .. }
+            if output.contains("The local web provider cannot search the web")
+    )));
+}
+
+#[tokio::test]
+async fn test_local_crawl_resolves_links_against_the_redirected_page() {
+    let harness = Harness::start().await;
+    harness.redirect("/serde", "/serde/latest/serde/").await;
+    harness
+        .page(
+            "/serde/latest/serde/",
+            "text/html",
+            r#"<h1>Serde</h1><a href="de/index.html">de</a>"#,
+        )
+        .await;
+    harness
+        .page(
+            "/serde/latest/serde/de/index.html",
+            "text/html",
+            "<h1>Module de</h1>",
+        )
+        .await;
+    let start = format!("{}/serde", harness.uri());
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "crawl_website",
+            json!({ "url": start }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("Done.")]).await;
+    let config = harness.config().with_web_provider(ProviderKind::Local);
+    let (mut chat, _) = harness.chat_with_config(0, config, &["y"]);
+    chat.stream(Message::user("crawl serde")).await.unwrap();
+    let requests = harness.requests("/v1/messages").await;
+    let crawl: Value = serde_json::from_str(&tool_output(&requests[1], 2)).unwrap();
+    let pages: Vec<(&str, &str)> = crawl["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|page| {
+            (
+                page["url"].as_str().unwrap(),
+                page["content"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    let uri = harness.uri();
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0].0, format!("{}/serde/latest/serde/", uri));
+    assert_eq!(
+        pages[1],
+        (
+            format!("{}/serde/latest/serde/de/index.html", uri).as_str(),
+            "# Module de"
+        )
+    );
+    assert_eq!(harness.hits("/de/index.html").await, 0);
+}
+
+#[tokio::test]
+async fn test_local_crawl_respects_robots_after_redirects() {
+    let harness = Harness::start().await;
+    harness
+        .page(
+            "/robots.txt",
+            "text/plain",
+            "User-agent: *\nDisallow: /private\n",
+        )
+        .await;
+    harness
+        .page(
+            "/docs",
+            "text/html",
+            r#"<h1>Docs</h1><a href="/docs/a">A</a> <a href="/private/b">B</a> <a href="/moved">Moved</a> <a href="/loop">Loop</a>"#,
+        )
+        .await;
+    harness
+        .page("/docs/a", "text/html", "<h1>Page A</h1>")
+        .await;
+    harness.page("/private/b", "text/plain", "private B").await;
+    harness.page("/private/c", "text/plain", "private C").await;
+    harness.redirect("/moved", "/private/c").await;
+    harness.redirect("/loop", "/loop").await;
+    let docs = format!("{}/docs", harness.uri());
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "crawl_website",
+            json!({ "url": docs }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("Done.")]).await;
+    let config = harness.config().with_web_provider(ProviderKind::Local);
+    let (mut chat, _) = harness.chat_with_config(0, config, &["y"]);
+    chat.stream(Message::user("crawl the docs")).await.unwrap();
+    let requests = harness.requests("/v1/messages").await;
+    let crawl: Value = serde_json::from_str(&tool_output(&requests[1], 2)).unwrap();
+    let mut contents: Vec<&str> = crawl["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|page| page["content"].as_str().unwrap())
+        .collect();
+    contents.sort();
+    assert_eq!(contents.len(), 2);
+    assert_eq!(contents[1], "# Page A");
+    assert!(!contents.iter().any(|content| content.contains("private C")));
+    assert_eq!(harness.hits("/private/b").await, 0);
+    assert_eq!(harness.hits("/private/c").await, 0);
+    assert_eq!(harness.hits("/moved").await, 1);
+    // the first request and ten redirects
+    assert_eq!(harness.hits("/loop").await, 11);
+    assert_eq!(harness.hits("/robots.txt").await, 1);
+}
+
+#[tokio::test]
+async fn test_fetch_url_reads_pages_and_respects_robots() {
+    let harness = Harness::start().await;
+    harness
+        .page(
+            "/robots.txt",
+            "text/plain",
+            "User-agent: *\nDisallow: /private\n",
+        )
+        .await;
+    harness
+        .page(
+            "/post",
+            "text/html",
+            r#"<html><head><title>Traits</title></head><body>
+              <nav><a href="/">Home</a></nav>
+              <div class="content"><p>Traits define shared behaviour, read the <a href="/book">book</a> for more.</p></div>
+              <footer><p>Copyright 2024, all rights reserved, nothing to see here.</p></footer>
+            </body></html>"#,
+        )
+        .await;
+    harness
+        .page("/data.json", "application/json", r#"{"edition":"2024"}"#)
+        .await;
+    let urls: Vec<String> = ["/post", "/data.json", "/private/keys"]
+        .iter()
+        .map(|path| format!("{}{}", harness.uri(), path))
+        .collect();
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "fetch_url",
+            json!({ "urls": urls }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("Done.")]).await;
+    let (mut chat, _) = harness.chat(0, &[]);
+    chat.stream(Message::user("read these")).await.unwrap();
+    assert!(harness.requests("/extract").await.is_empty());
+    assert_eq!(harness.hits("/private/keys").await, 0);
+    let requests = harness.requests("/v1/messages").await;
+    let fetched: Value = serde_json::from_str(&tool_output(&requests[1], 2)).unwrap();
+    let content = |url: &str| {
+        fetched["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|page| page["url"] == url)
+            .map(|page| page["content"].as_str().unwrap().to_string())
+            .unwrap()
+    };
+    assert_eq!(
+        content(&urls[0]),
+        format!(
+            "# Traits\n\nTraits define shared behaviour, read the [book][1] for more.\n\n[1]: {}/book",
+            harness.uri()
+        )
+    );
+    assert_eq!(content(&urls[1]), "{\n  \"edition\": \"2024\"\n}");
+    assert_eq!(fetched["failed_results"][0]["url"], urls[2]);
+    assert_eq!(
+        fetched["failed_results"][0]["error"],
+        format!("robots.txt disallows fetching {}", urls[2])
+    );
+}
+
+#[tokio::test]
+async fn test_fetch_url_refuses_private_hosts_unless_allowed() {
+    let harness = Harness::start().await;
+    harness.page("/admin", "text/plain", "secrets").await;
+    // once as an address, once as a name that only resolves to one
+    let urls = [
+        format!("{}/admin", harness.uri()),
+        harness.uri().replace("127.0.0.1", "localhost") + "/admin",
+    ];
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "fetch_url",
+            json!({ "urls": urls }),
+        )])
+        .await;
+    harness.expect_stream(vec![text("Done.")]).await;
+    let config = harness.config().with_private_hosts_allowed(false);
+    let (mut chat, _) = harness.chat_with_config(0, config, &[]);
+    chat.stream(Message::user("read these")).await.unwrap();
+    assert_eq!(harness.hits("/admin").await, 0);
+    assert_eq!(harness.hits("/robots.txt").await, 0);
+    let requests = harness.requests("/v1/messages").await;
+    let fetched: Value = serde_json::from_str(&tool_output(&requests[1], 2)).unwrap();
+    assert!(fetched["results"].as_array().unwrap().is_empty());
+    assert_eq!(fetched["failed_results"].as_array().unwrap().len(), 2);
+    for (failed, host) in fetched["failed_results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .zip(["127.0.0.1", "localhost"])
+    {
+        assert_eq!(
+            failed["error"],
+            format!(
+                "{} is a loopback, private or link-local address, fetching it needs MARVIN_WEB_ALLOW_PRIVATE_HOSTS=true",
+                host
+            )
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_cited_sources_become_footnotes_and_are_saved() {
+    let harness = Harness::start().await;
+    harness
+        .tavily(
+            "/search",
+            json!({
+                "query": "rust 2024",
+                "results": [
+                    { "title": "Rust 2024", "url": "https://blog.rust-lang.org", "content": "Rust 2024 is stable", "score": 0.8 },
+                    { "title": "Editions", "url": "https://doc.rust-lang.org/edition-guide", "content": "The edition guide", "score": 0.9 }
+                ]
+            }),
+        )
+        .await;
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "search_web",
+            json!({ "query": "rust 2024" }),
+        )])
+        .await;
+    harness
+        .expect_stream(vec![text(
+            "Editions are opt-in [S1] and 2024 is stable [S2, S1], unlike [S7].",
+        )])
+        .await;
+    let (mut chat, events) = harness.chat(0, &[]);
+    chat.stream(Message::user("what is new in rust?"))
+        .await
+        .unwrap();
+    let requests = harness.requests("/v1/messages").await;
+    let output: Value = serde_json::from_str(&tool_output(&requests[1], 2)).unwrap();
+    assert_eq!(output["results"][0]["source"], "S1");
+    assert_eq!(output["results"][1]["source"], "S2");
+    assert_eq!(
+        streamed_text(&events),
+        "Editions are opt-in [1] and 2024 is stable [2, 1], unlike [S7].\n\
+         [1] Editions <https://doc.rust-lang.org/edition-guide>\n\
+         [2] Rust 2024 <https://blog.rust-lang.org>"
+    );
+    chat.save_chat_history().unwrap();
+    let sources = saved_chat(&harness, 0).sources;
+    assert_eq!(
+        sources
+            .iter()
+            .map(|source| (source.id.as_str(), source.url.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            ("S1", "https://doc.rust-lang.org/edition-guide"),
+            ("S2", "https://blog.rust-lang.org")
+        ]
+    );
+    let (chat, events) = harness.chat(1, &["/sources", "/import 0", "/sources"]);
+    Runner::run(chat).await.unwrap();
+    let notices = notices(&events);
+    assert!(notices.contains(&"No web sources yet".to_string()));
+    assert!(notices.contains(&"Web sources this session: 2".to_string()));
+    assert!(notices.contains(&"  [S2] Rust 2024 <https://blog.rust-lang.org>".to_string()));
+}
+
+#[tokio::test]
+async fn test_research_writes_a_cited_report_within_the_credit_limit() {
+    let harness = Harness::start().await;
+    harness
+        .tavily(
+            "/search",
+            json!({
+                "query": "async runtimes",
+                "results": [
+                    { "title": "Tokio", "url": "https://tokio.rs", "content": "Tokio has a work-stealing scheduler", "score": 0.9 },
+                    { "title": "Smol", "url": "https://github.com/smol-rs/smol", "content": "Smol is a small runtime", "score": 0.8 },
+                    { "title": "Glommio", "url": "https://github.com/DataDog/glommio", "content": "Glommio uses io_uring", "score": 0.7 }
+                ],
+                "usage": { "credits": 1 }
+            }),
+        )
+        .await;
+    harness
+        .tavily(
+            "/extract",
+            json!({
+                "results": [
+                    { "url": "https://tokio.rs", "raw_content": "Tokio schedules tasks with work stealing across threads." },
+                    { "url": "https://github.com/smol-rs/smol", "raw_content": "Smol builds on async-executor." }
+                ],
+                "usage": { "credits": 1 }
+            }),
+        )
+        .await;
+    harness
+        .expect_completion(
+            "Sub-questions:\n1. How do tokio and smol schedule tasks?\n2. What does glommio need?",
+        )
+        .await;
+    harness
+        .expect_completion("Tokio steals work [S1], smol is small [S2].")
+        .await;
+    harness
+        .expect_completion("Glommio needs io_uring [S3].")
+        .await;
+    harness
+        .expect_completion("Tokio steals work [S1] while glommio needs io_uring [S3].")
+        .await;
+    let config = harness.config().with_research_limits(ResearchLimits {
+        max_credits: 3.0,
+        ..ResearchLimits::default()
+    });
+    let (mut chat, events) = harness.chat_with_config(0, config, &[]);
+    chat.research_question("Compare tokio, smol and glommio".to_string())
+        .await
+        .unwrap();
+    let searches = harness.requests("/search").await;
+    assert_eq!(searches.len(), 2);
+    assert_eq!(searches[1]["query"], "What does glommio need?");
+    // two searches leave one credit, so only the first sub-question's pages are read
+    let extracts = harness.requests("/extract").await;
+    assert_eq!(extracts.len(), 1);
+    assert_eq!(
+        extracts[0]["urls"],
+        json!(["https://tokio.rs", "https://github.com/smol-rs/smol"])
+    );
+    let requests = harness.requests("/v1/messages").await;
+    assert_eq!(requests.len(), 4);
+    assert!(requests
+        .iter()
+        .all(|request| request.get("tools").is_none()));
+    let notes_prompt = requests[2]["messages"][0]["content"][0]["text"]
+        .as_str()
+        .unwrap();
+    assert!(notes_prompt
+        .contains("[S3] Glommio <https://github.com/DataDog/glommio>\nGlommio uses io_uring"));
+    let notices = notices(&events);
+    assert!(notices.contains(&"  2. What does glommio need?".to_string()));
+    assert!(notices
+        .contains(&"Credit limit reached, 1 sub-question(s) get search snippets only".to_string()));
+    let report = "Tokio steals work [1] while glommio needs io_uring [2].";
+    assert_eq!(
+        streamed_text(&events),
+        format!(
+            "{}\n\n[1] Tokio <https://tokio.rs>\n[2] Glommio <https://github.com/DataDog/glommio>",
+            report
+        )
+    );
+    let path = harness
+        .chats_dir()
+        .join("research")
+        .join("compare-tokio-smol-and-glommio.md");
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        format!(
+            "# Compare tokio, smol and glommio\n\n{}\n\n## Sources\n\n\
+             [1] Tokio <https://tokio.rs>\n\n[2] Glommio <https://github.com/DataDog/glommio>\n",
+            report
+        )
+    );
+    assert!(notices.contains(&format!(
+        "Report saved to {} (4 model calls, 3 credits)",
+        path.display()
+    )));
+    assert_eq!(chat.chat_history().len(), 2);
+}
+
+#[tokio::test]
+async fn test_snapshot_is_searched_offline_in_a_later_session() {
+    let harness = Harness::start().await;
+    let book = format!("{}/book", harness.uri());
+    harness
+        .tavily(
+            "/crawl",
+            json!({
+                "base_url": book,
+                "results": [
+                    { "url": format!("{}/pinning", book), "raw_content": "# Pinning\n\nPin keeps a future from moving in memory." },
+                    { "url": format!("{}/executors", book), "raw_content": "# Executors\n\nAn executor polls futures until they finish." },
+                    { "url": format!("{}/empty", book), "raw_content": "  " }
+                ],
+                "usage": { "credits": 1 }
+            }),
+        )
+        .await;
+    let (chat, events) = harness.chat(
+        0,
+        &[
+            &format!("/snapshot {} --paths /book/.* --limit 10", book),
+            "/snapshot",
+        ],
+    );
+    Runner::run(chat).await.unwrap();
+    let crawl = &harness.requests("/crawl").await[0];
+    assert_eq!(crawl["select_paths"], json!(["/book/.*"]));
+    assert_eq!(crawl["max_depth"], 2);
+    assert_eq!(crawl["limit"], 10);
+    assert_eq!(crawl["allow_external"], false);
+    let notices = notices(&events);
+    let saved = notices
+        .iter()
+        .find(|notice| notice.starts_with("Saved 2 pages as snapshot 127.0.0.1-book in "))
+        .unwrap();
+    assert!(saved.ends_with("search_snapshots can now answer from it offline"));
+    assert!(notices
+        .iter()
+        .any(|notice| notice
+            .starts_with(&format!("  127.0.0.1-book: 2 pages from {}, taken ", book))));
+
+    harness
+        .expect_stream(vec![tool_use(
+            "toolu_1",
+            "search_snapshots",
+            json!({ "query": "what does pin do", "site": "book" }),
+        )])
+        .await;
+    harness
+        .expect_stream(vec![text("Pinning stops a future from moving [S1].")])
+        .await;
+    let (mut chat, events) = harness.chat(1, &[]);
+    chat.stream(Message::user("what does pin do?"))
+        .await
+        .unwrap();
+    let requests = harness.requests("/v1/messages").await;
+    let output: Value = serde_json::from_str(&tool_output(&requests[1], 2)).unwrap();
+    assert_eq!(output["results"][0]["source"], "S1");
+    assert_eq!(output["results"][0]["title"], "Pinning");
+    assert_eq!(
+        output["results"][0]["content"],
+        "# Pinning\n\nPin keeps a future from moving in memory."
+    );
+    assert!(output["searched"][0]
+        .as_str()
+        .unwrap()
+        .starts_with(&format!("127.0.0.1-book ({}, taken ", book)));
+    assert!(streamed_text(&events).ends_with(&format!("[1] Pinning <{}/pinning>", book)));
+    assert_eq!(harness.requests("/crawl").await.len(), 1);
+}
@@ -0,0 +1,342 @@
+mod end_to_end;
+
+use crate::{
+    anthropic::ModelInfo,
+    chat::{config::Config, Chat},
+    frontend::{scripted::Scripted, ChatEvent},
+};
+use serde_json::{json, Value};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tempfile::TempDir;
+use wiremock::{
+    matchers::{body_partial_json, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+pub const TEST_MODEL_ID: &str = "claude-test-1";
+
+pub enum Block {
+    Text(String),
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+}
+
+pub fn text(text: &str) -> Block {
+    Block::Text(text.to_string())
+}
+
+pub fn tool_use(id: &str, name: &str, input: Value) -> Block {
+    Block::ToolUse {
+        id: id.to_string(),
+        name: name.to_string(),
+        input,
+    }
+}
+
+pub fn model_options() -> Vec<ModelInfo> {
+    [
+        (TEST_MODEL_ID, "Claude Test 1"),
+        ("claude-test-2", "Claude Test 2"),
+    ]
+    .into_iter()
+    .map(|(id, display_name)| ModelInfo {
+        id: id.to_string(),
+        created_at: "2025-01-01T00:00:00Z".to_string(),
+        display_name: display_name.to_string(),
+        r#type: "model".to_string(),
+    })
+    .collect()
+}
+
+fn sse_event(event: &str, data: Value) -> String {
+    format!("event: {}\ndata: {}\n\n", event, data)
+}
+
+pub fn sse_body(blocks: &[Block], input_tokens: usize, output_tokens: usize) -> String {
+    let mut body = sse_event(
+        "message_start",
+        json!({
+            "type": "message_start",
+            "message": {
+                "id": "msg_test",
+                "type": "message",
+                "role": "assistant",
+                "content": [],
+                "model": TEST_MODEL_ID,
+                "stop_reason": null,
+                "stop_sequence": null,
+                "usage": { "input_tokens": input_tokens, "output_tokens": 1 }
+            }
+        }),
+    );
+    for (index, block) in blocks.iter().enumerate() {
+        let (content_block, delta) = match block {
+            Block::Text(text) => (
+                json!({ "type": "text", "text": "" }),
+                json!({ "type": "text_delta", "text": text }),
+            ),
+            Block::ToolUse { id, name, input } => (
+                json!({ "type": "tool_use", "id": id, "name": name, "input": {} }),
+                json!({ "type": "input_json_delta", "partial_json": input.to_string() }),
+            ),
+        };
+        body += &sse_event(
+            "content_block_start",
+            json!({ "type": "content_block_start", "index": index, "content_block": content_block }),
+        );
+        body += &sse_event(
+            "content_block_delta",
+            json!({ "type": "content_block_delta", "index": index, "delta": delta }),
+        );
+        body += &sse_event(
+            "content_block_stop",
+            json!({ "type": "content_block_stop", "index": index }),
+        );
+    }
+    let stop_reason = if blocks
+        .iter()
+        .any(|block| matches!(block, Block::ToolUse { .. }))
+    {
+        "tool_use"
+    } else {
+        "end_turn"
+    };
+    body += &sse_event(
+        "message_delta",
+        json!({
+            "type": "message_delta",
+            "delta": { "stop_reason": stop_reason, "stop_sequence": null },
+            "usage": { "output_tokens": output_tokens }
+        }),
+    );
+    body += &sse_event("message_stop", json!({ "type": "message_stop" }));
+    body
+}
+
+pub struct Harness {
+    server: MockServer,
+    chats_dir: TempDir,
+    cache_dir: TempDir,
+}
+
+impl Harness {
+    pub async fn start() -> Self {
+        Self {
+            server: MockServer::start().await,
+            chats_dir: tempfile::tempdir().expect("temp dir"),
+            cache_dir: tempfile::tempdir().expect("temp dir"),
+        }
+    }
+    pub fn config(&self) -> Config {
+        Config::new("test-anthropic-key", "test-tavily-key")
+            .with_anthropic_base_url(self.server.uri())
+            .with_tavily_base_url(self.server.uri())
+            .with_chats_dir(self.chats_dir.path())
+            .with_workspace_root(self.chats_dir.path())
+            .with_cache_dir(self.cache_dir.path())
+            .with_research_dir(self.chats_dir.path().join("research"))
+            .with_snapshots_dir(self.chats_dir.path().join("snapshots"))
+            // the mock server listens on localhost
+            .with_private_hosts_allowed(true)
+    }
+    pub fn chats_dir(&self) -> &Path {
+        self.chats_dir.path()
+    }
+    pub fn chat(&self, id: u16, inputs: &[&str]) -> (Chat, Arc<Mutex<Vec<ChatEvent>>>) {
+        self.chat_with_config(id, self.config(), inputs)
+    }
+    pub fn chat_with_config(
+        &self,
+        id: u16,
+        config: Config,
+        inputs: &[&str],
+    ) -> (Chat, Arc<Mutex<Vec<ChatEvent>>>) {
+        let (frontend, events) = Scripted::new(inputs);
+        let chat = Chat::with_model(
+            id,
+            config,
+            model_options(),
+            TEST_MODEL_ID,
+            Box::new(frontend),
+        )
+        .expect("chat");
+        (chat, events)
+    }
+    pub async fn expect_stream(&self, blocks: Vec<Block>) {
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(json!({ "stream": true })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw(sse_body(&blocks, 100, 20), "text/event-stream"),
+            )
+            .up_to_n_times(1)
+            .mount(&self.server)
+            .await;
+    }
+    // starts streaming an answer and then fails partway through it with an overloaded error.
+    // the error frame is malformed so the client reports it rather than skipping an unknown event
+    pub async fn expect_broken_stream(&self, text: &str) {
+        let body = sse_body(&[Block::Text(text.to_string())], 100, 20);
+        let cut = body
+            .find("event: content_block_stop")
+            .expect("a text block");
+        let error = json!({
+            "type": "error",
+            "error": { "type": "overloaded_error", "message": "mock overloaded_error" }
+        });
+        let body = format!("{}event: error\ndata: {}}}\n\n", &body[..cut], error);
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(json!({ "stream": true })))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .up_to_n_times(1)
+            .mount(&self.server)
+            .await;
+    }
+    pub async fn expect_completion(&self, text: &str) {
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "msg_test",
+                "type": "message",
+                "role": "assistant",
+                "model": TEST_MODEL_ID,
+                "content": [{ "type": "text", "text": text }],
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": { "input_tokens": 100, "output_tokens": 20 }
+            })))
+            .up_to_n_times(1)
+            .mount(&self.server)
+            .await;
+    }
+    pub async fn expect_error(&self, status: u16, error_type: &str, retry_after: Option<u64>) {
+        let mut response = ResponseTemplate::new(status).set_body_json(json!({
+            "type": "error",
+            "error": { "type": error_type, "message": format!("mock {}", error_type) }
+        }));
+        if let Some(secs) = retry_after {
+            response = response.insert_header("retry-after", secs.to_string().as_str());
+        }
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(response)
+            .up_to_n_times(1)
+            .mount(&self.server)
+            .await;
+    }
+    pub async fn tavily(&self, endpoint: &str, body: Value) {
+        Mock::given(method("POST"))
+            .and(path(endpoint))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&self.server)
+            .await;
+    }
+    // answers requests for one query only after a delay, to see calls overlap
+    pub async fn tavily_slow(&self, endpoint: &str, query: &str, delay: Duration, body: Value) {
+        Mock::given(method("POST"))
+            .and(path(endpoint))
+            .and(body_partial_json(json!({ "query": query })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(body)
+                    .set_delay(delay),
+            )
+            .mount(&self.server)
+            .await;
+    }
+    pub async fn tavily_get(&self, endpoint: &str, body: Value) {
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&self.server)
+            .await;
+    }
+    pub async fn page(&self, endpoint: &str, content_type: &str, body: &str) {
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, content_type))
+            .mount(&self.server)
+            .await;
+    }
+    pub async fn redirect(&self, endpoint: &str, location: &str) {
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", location))
+            .mount(&self.server)
+            .await;
+    }
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+    pub async fn models(&self) {
+        let data: Vec<Value> = model_options()
+            .into_iter()
+            .map(|model| {
+                json!({
+                    "id": model.id,
+                    "created_at": model.created_at,
+                    "display_name": model.display_name,
+                    "type": model.r#type,
+                })
+            })
+            .collect();
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": data })))
+            .mount(&self.server)
+            .await;
+    }
+    pub async fn requests(&self, endpoint: &str) -> Vec<Value> {
+        self.server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter(|request| request.url.path() == endpoint)
+            .flat_map(|request| request.body_json::<Value>().ok())
+            .collect()
+    }
+    // how many requests reached an endpoint, whatever their body
+    pub async fn hits(&self, endpoint: &str) -> usize {
+        self.server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter(|request| request.url.path() == endpoint)
+            .count()
+    }
+}
+
+pub fn notices(events: &Mutex<Vec<ChatEvent>>) -> Vec<String> {
+    events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|event| match event {
+            ChatEvent::Notice(notice) => Some(notice.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn streamed_text(events: &Mutex<Vec<ChatEvent>>) -> String {
+    events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|event| match event {
+            ChatEvent::TextDelta(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
@@ -1,4 +1,4 @@
-use std::io::{stdout, Write};
+use crate::frontend::{ChatEvent, Frontend};
 
 const WIDTH: usize = 50;
 const HORIZONTAL_LINE_STR: &str = "-";
@@ -6,14 +6,18 @@ pub fn horizontal_line() {
     println!("{}", HORIZONTAL_LINE_STR.repeat(WIDTH));
 }
 
-pub fn welcome_message(chat_id: u16) {
-    println!("Hello, I am Marvin, your personal AI assistant!");
-    print!("Initiating new chat state (ID = {})", chat_id);
+pub fn welcome_message(frontend: &mut dyn Frontend, chat_id: u16) {
+    frontend.emit(ChatEvent::Notice(
+        "Hello, I am Marvin, your personal AI assistant!".to_string(),
+    ));
+    frontend.emit(ChatEvent::TextDelta(format!(
+        "Initiating new chat state (ID = {})",
+        chat_id
+    )));
     for _ in 0..3 {
         std::thread::sleep(std::time::Duration::from_millis(500));
-        print!(".");
-        let _ = stdout().flush();
+        frontend.emit(ChatEvent::TextDelta(".".to_string()));
     }
-    println!();
-    horizontal_line();
+    frontend.emit(ChatEvent::TextEnd);
+    frontend.emit(ChatEvent::Separator);
 }
@@ -1,4 +1,4 @@
-use crate::{chat::Chat, ui::horizontal_line};
+use crate::chat::Chat;
 
 pub trait ClearContext {
     fn clear_context(&mut self) -> anyhow::Result<()>;
@@ -6,8 +6,8 @@ pub trait ClearContext {
 
 impl ClearContext for Chat {
     fn clear_context(&mut self) -> anyhow::Result<()> {
-        horizontal_line();
-        println!("Chat history cleared");
+        self.separator();
+        self.notice("Chat history cleared");
         self.clear_chat_history();
         self.clear_input();
         Ok(())
@@ -1,4 +1,4 @@
-use crate::{chat::Chat, ui::horizontal_line};
+use crate::chat::Chat;
 use rig::message::Message;
 
 pub trait CompactContext {
@@ -7,16 +7,16 @@ pub trait CompactContext {
 
 impl CompactContext for Chat {
     async fn compact_context(&mut self) -> anyhow::Result<()> {
-        horizontal_line();
+        self.separator();
         self.clear_input();
         let prompt = "Provide a concise context summary of our conversation that could be used to continue this chat. Include key decisions made, current task state, and any important details. Write it as a brief paragraph, not a list.";
         let response = self.send(Message::user(prompt)).await?;
         self.clear_chat_history();
         self.add_to_chat_history(Message::assistant(response.clone()));
-        println!(
+        self.notice(format!(
             "Compaction completed in {} words",
             response.split_whitespace().count()
-        );
+        ));
         Ok(())
     }
 }
@@ -1,4 +1,4 @@
-use crate::{chat::Chat, ui::horizontal_line, user_commands::save_chat_history::SaveChatHistory};
+use crate::{chat::Chat, user_commands::save_chat_history::SaveChatHistory};
 
 pub trait ExitProcess {
     fn exit_process(&mut self) -> anyhow::Result<()>;
@@ -7,8 +7,8 @@ pub trait ExitProcess {
 impl ExitProcess for Chat {
     fn exit_process(&mut self) -> anyhow::Result<()> {
         self.save_chat_history()?;
-        horizontal_line();
-        println!("Farewell!");
+        self.separator();
+        self.notice("Farewell!");
         Ok(())
     }
 }
@@ -0,0 +1,60 @@
+use crate::chat::Chat;
+
+const USAGE: &str = "Usage: /cache stats|clear";
+
+fn human_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+pub trait ManageCache {
+    fn manage_cache(&mut self, args: &str) -> std::io::Result<()>;
+}
+
+impl ManageCache for Chat {
+    fn manage_cache(&mut self, args: &str) -> std::io::Result<()> {
+        self.clear_input();
+        let provider = self.web_provider();
+        let Some(cache) = provider.cache() else {
+            self.notice(format!(
+                "The {} web provider does not cache responses",
+                provider.name()
+            ));
+            return Ok(());
+        };
+        match args {
+            "" | "stats" => {
+                let stats = cache.stats();
+                self.separator();
+                self.notice(format!(
+                    "Web response cache in {}: {} entries, {}",
+                    cache.dir().display(),
+                    stats.entries(),
+                    human_bytes(stats.bytes())
+                ));
+                for (endpoint, endpoint_stats) in &stats.endpoints {
+                    self.notice(format!(
+                        "  {:<10} {} entries ({} expired), {}",
+                        endpoint,
+                        endpoint_stats.entries,
+                        endpoint_stats.expired,
+                        human_bytes(endpoint_stats.bytes)
+                    ));
+                }
+                self.notice(format!(
+                    "This session: {} hits, {} misses",
+                    stats.hits, stats.misses
+                ));
+            }
+            "clear" => {
+                let removed = cache.clear()?;
+                self.notice(format!("Removed {} cached responses", removed));
+            }
+            _ => self.notice(USAGE),
+        }
+        Ok(())
+    }
+}
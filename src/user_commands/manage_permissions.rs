@@ -0,0 +1,50 @@
+use crate::chat::{
+    permissions::{Approval, PermissionError},
+    Chat,
+};
+
+const USAGE: &str = "Usage: /permissions [<tool>|* always|ask|never]";
+
+pub trait ManagePermissions {
+    fn manage_permissions(
+        &mut self,
+        args: &str,
+    ) -> impl Future<Output = Result<(), PermissionError>>;
+}
+
+impl ManagePermissions for Chat {
+    async fn manage_permissions(&mut self, args: &str) -> Result<(), PermissionError> {
+        self.clear_input();
+        let words: Vec<&str> = args.split_whitespace().collect();
+        match words.as_slice() {
+            [] => {
+                self.separator();
+                self.notice("Tool permissions:");
+                for tool in self.tool_names().await {
+                    let approval = self.permissions().get(&tool);
+                    self.notice(format!("  {:<18} {}", tool, approval));
+                }
+                self.notice(USAGE);
+            }
+            [tool, approval] => {
+                let approval: Approval = approval.parse()?;
+                let tools = self.tool_names().await;
+                if *tool != "*" && !tools.iter().any(|name| name == tool) {
+                    self.notice(format!(
+                        "Unknown tool '{}', available: {}",
+                        tool,
+                        tools.join(", ")
+                    ));
+                    return Ok(());
+                }
+                self.permissions_mut().set(tool, approval);
+                match *tool {
+                    "*" => self.notice(format!("All tools set to {}", approval)),
+                    tool => self.notice(format!("{} set to {}", tool, approval)),
+                }
+            }
+            _ => self.notice(USAGE),
+        }
+        Ok(())
+    }
+}
@@ -2,20 +2,34 @@ pub mod clear_context;
 pub mod compact_context;
 pub mod exit_process;
 pub mod import_chat_history;
+pub mod manage_cache;
+pub mod manage_permissions;
+pub mod research_question;
+pub mod review_file;
 pub mod save_chat_history;
 pub mod show_chat_history;
 pub mod show_context_summary;
+pub mod show_credit_usage;
 pub mod show_help_message;
+pub mod show_sources;
 pub mod show_token_usage;
 pub mod switch_model;
+pub mod take_snapshot;
 
 pub use clear_context::ClearContext;
 pub use compact_context::CompactContext;
 pub use exit_process::ExitProcess;
 pub use import_chat_history::ImportChatHistory;
+pub use manage_cache::ManageCache;
+pub use manage_permissions::ManagePermissions;
+pub use research_question::ResearchQuestion;
+pub use review_file::ReviewFile;
 pub use save_chat_history::SaveChatHistory;
 pub use show_chat_history::ShowChatHistory;
 pub use show_context_summary::ShowContextSummary;
+pub use show_credit_usage::ShowCreditUsage;
 pub use show_help_message::ShowHelpMessage;
+pub use show_sources::ShowSources;
 pub use show_token_usage::ShowTokenUsage;
 pub use switch_model::SwitchModel;
+pub use take_snapshot::TakeSnapshot;
@@ -0,0 +1,231 @@
+use crate::{
+    agent_tools::web::{extract::Extract, search::Search},
+    chat::{
+        citations::Citations,
+        research::{
+            notes_prompt, parse_plan, plan_prompt, report_path, report_prompt, Allowance, Found,
+            Results, EXTRACT_CREDITS, MIN_RESEARCH_TURNS, RESEARCH_PREAMBLE, RESULTS_PER_QUESTION,
+            SEARCH_CREDITS, SOURCES_PER_QUESTION,
+        },
+        Chat,
+    },
+    frontend::ChatEvent,
+};
+use rig::{
+    message::{Message, ToolCall, ToolFunction},
+    tool::Tool,
+};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+pub trait ResearchQuestion {
+    fn research_question(&mut self, question: String) -> impl Future<Output = anyhow::Result<()>>;
+}
+
+fn tool_call(id: String, name: &str, args: Value) -> ToolCall {
+    ToolCall::new(id, ToolFunction::new(name.to_string(), args))
+}
+
+fn results(output: Result<String, String>) -> Result<Vec<Found>, String> {
+    output.and_then(|output| {
+        serde_json::from_str::<Results>(&output)
+            .map(|results| results.results)
+            .map_err(|e| e.to_string())
+    })
+}
+
+// plans sub-questions, searches them together, reads the top results, takes notes on each
+// and writes a cited report, within the configured credit and model call limits
+impl ResearchQuestion for Chat {
+    async fn research_question(&mut self, question: String) -> anyhow::Result<()> {
+        self.clear_input();
+        if question.is_empty() {
+            self.notice("Usage: /research <question>");
+            return Ok(());
+        }
+        let limits = self.config().research_limits();
+        if limits.max_turns < MIN_RESEARCH_TURNS {
+            anyhow::bail!(
+                "Research needs at least {} model calls, the limit is {}",
+                MIN_RESEARCH_TURNS,
+                limits.max_turns
+            );
+        }
+        let provider = self.web_provider();
+        let mut allowance = Allowance::new(limits, provider.as_ref());
+        self.separator();
+
+        self.notice(format!("Planning research: {}", question));
+        // planning and the report take a call each, every sub-question needs one for its notes
+        let max_questions = limits.max_questions.min(limits.max_turns - 2).max(1);
+        let plan = self
+            .complete(RESEARCH_PREAMBLE, &plan_prompt(&question, max_questions))
+            .await?;
+        allowance.take_turn();
+        let mut sub_questions = parse_plan(&plan, max_questions);
+        if sub_questions.is_empty() {
+            sub_questions.push(question.clone());
+        }
+        let affordable = allowance.affordable(provider.as_ref(), SEARCH_CREDITS);
+        if affordable == 0 {
+            anyhow::bail!(
+                "The research credit limit of {} leaves nothing to search with",
+                limits.max_credits
+            );
+        }
+        if sub_questions.len() > affordable {
+            self.notice(format!(
+                "Credit limit reached, dropping {} sub-question(s)",
+                sub_questions.len() - affordable
+            ));
+            sub_questions.truncate(affordable);
+        }
+        for (i, sub_question) in sub_questions.iter().enumerate() {
+            self.notice(format!("  {}. {}", i + 1, sub_question));
+        }
+
+        self.notice(format!(
+            "Searching {} sub-question(s)...",
+            sub_questions.len()
+        ));
+        let searches: Vec<ToolCall> = sub_questions
+            .iter()
+            .enumerate()
+            .map(|(i, sub_question)| {
+                tool_call(
+                    format!("research_search_{}", i + 1),
+                    Search::NAME,
+                    json!({ "query": sub_question, "max_results": RESULTS_PER_QUESTION }),
+                )
+            })
+            .collect();
+        let mut found = Vec::new();
+        for (sub_question, output) in sub_questions
+            .iter()
+            .zip(self.run_tool_calls(&searches).await)
+        {
+            match results(output) {
+                Ok(results) => found.push(results),
+                Err(e) => {
+                    self.notice(format!("Search failed for \"{}\": {}", sub_question, e));
+                    found.push(Vec::new());
+                }
+            }
+        }
+
+        // the top results of each sub-question, leaving out pages an earlier one already reads
+        let mut chosen = HashSet::new();
+        let reading: Vec<Vec<String>> = found
+            .iter()
+            .map(|results| {
+                results
+                    .iter()
+                    .map(|found| found.url.clone())
+                    .filter(|url| chosen.insert(url.clone()))
+                    .take(SOURCES_PER_QUESTION)
+                    .collect()
+            })
+            .collect();
+        let wanted: Vec<usize> = (0..reading.len())
+            .filter(|i| !reading[*i].is_empty())
+            .collect();
+        let affordable = allowance.affordable(provider.as_ref(), EXTRACT_CREDITS);
+        if wanted.len() > affordable {
+            self.notice(format!(
+                "Credit limit reached, {} sub-question(s) get search snippets only",
+                wanted.len() - affordable
+            ));
+        }
+        let wanted: Vec<usize> = wanted.into_iter().take(affordable).collect();
+        self.notice(format!(
+            "Reading {} source(s)...",
+            wanted.iter().map(|i| reading[*i].len()).sum::<usize>()
+        ));
+        let extracts: Vec<ToolCall> = wanted
+            .iter()
+            .map(|i| {
+                tool_call(
+                    format!("research_extract_{}", i + 1),
+                    Extract::NAME,
+                    json!({ "urls": reading[*i], "query": sub_questions[*i] }),
+                )
+            })
+            .collect();
+        let mut read = vec![Vec::new(); sub_questions.len()];
+        for (i, output) in wanted.iter().zip(self.run_tool_calls(&extracts).await) {
+            match results(output) {
+                Ok(results) => read[*i] = results,
+                Err(e) => self.notice(format!(
+                    "Reading failed for \"{}\": {}",
+                    sub_questions[*i], e
+                )),
+            }
+        }
+
+        let mut notes = Vec::new();
+        for (i, sub_question) in sub_questions.iter().enumerate() {
+            let mut sources = read[i].clone();
+            sources.extend(
+                found[i]
+                    .iter()
+                    .filter(|found| !read[i].iter().any(|read| read.url == found.url))
+                    .cloned(),
+            );
+            if sources.is_empty() {
+                notes.push((sub_question.clone(), "No sources found.".to_string()));
+                continue;
+            }
+            self.notice(format!(
+                "Taking notes {}/{}: {}",
+                i + 1,
+                sub_questions.len(),
+                sub_question
+            ));
+            let prompt = notes_prompt(&question, sub_question, &sources);
+            notes.push((
+                sub_question.clone(),
+                self.complete(RESEARCH_PREAMBLE, &prompt).await?,
+            ));
+            allowance.take_turn();
+        }
+
+        self.notice("Writing the report...");
+        let report = self
+            .complete(RESEARCH_PREAMBLE, &report_prompt(&question, &notes))
+            .await?;
+        allowance.take_turn();
+        let mut citations = Citations::default();
+        let mut body = citations.push(&report, self.sources());
+        body.push_str(&citations.flush(self.sources()));
+        let body = body.trim().to_string();
+        let footnotes = citations.footnotes();
+        self.separator();
+        self.emit(ChatEvent::TextDelta(body.clone()));
+        let mut markdown = format!("# {}\n\n{}\n", question, body);
+        if let Some(footnotes) = footnotes {
+            self.emit(ChatEvent::TextDelta(format!("\n\n{}", footnotes)));
+            markdown.push_str(&format!(
+                "\n## Sources\n\n{}\n",
+                footnotes.replace('\n', "\n\n")
+            ));
+        }
+        self.emit(ChatEvent::TextEnd);
+        let dir = self.config().research_dir();
+        std::fs::create_dir_all(dir)?;
+        let path = report_path(dir, &question);
+        std::fs::write(&path, markdown)?;
+        // the report joins the conversation so follow-up questions can build on it
+        self.add_to_chat_history(Message::user(format!(
+            "Research this question: {}",
+            question
+        )));
+        self.add_to_chat_history(Message::assistant(report));
+        self.notice(format!(
+            "Report saved to {} ({} model calls, {} credits)",
+            path.display(),
+            allowance.turns(),
+            allowance.credits_spent(provider.as_ref())
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{
+    agent_tools::code::{
+        review_rust::{request::ReviewRustArgs, review::review},
+        sandbox::Sandbox,
+    },
+    chat::Chat,
+};
+use rig::message::Message;
+
+pub trait ReviewFile {
+    fn review_file(&mut self, path: String) -> impl Future<Output = anyhow::Result<()>>;
+}
+
+impl ReviewFile for Chat {
+    async fn review_file(&mut self, path: String) -> anyhow::Result<()> {
+        self.clear_input();
+        if path.is_empty() {
+            self.notice("Usage: /review <file>");
+            return Ok(());
+        }
+        let code = std::fs::read_to_string(&path)?;
+        self.notice(format!("Running rustfmt and clippy on {}...", path));
+        let sandbox = Sandbox::new(self.config().rust_vendor_dir().map(|dir| dir.to_path_buf()));
+        let args = ReviewRustArgs {
+            code,
+            dependencies: None,
+            edition: None,
+        };
+        let output = review(&sandbox, &args).await?;
+        let lints = output
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.level != "error")
+            .count();
+        let errors = output.diagnostics.len() - lints;
+        self.notice(format!(
+            "{}: {} error(s), {} warning(s), {}",
+            path,
+            errors,
+            lints,
+            match output.formatted {
+                Some(true) => "formatted",
+                Some(false) => "needs rustfmt",
+                None => "rustfmt failed",
+            }
+        ));
+        let prompt = format!(
+            "Review the Rust file `{}`. These are the rustfmt and clippy results from the review_rust tool:\n```json\n{}\n```\nThe file:\n```rust\n{}\n```\nExplain the most important findings, citing the lint names, and show how to fix them.",
+            path,
+            serde_json::to_string_pretty(&output)?,
+            args.code
+        );
+        self.stream(Message::user(prompt)).await?;
+        Ok(())
+    }
+}
@@ -1,7 +1,4 @@
-use crate::{
-    chat::{Chat, CHATS_DIR_NAME},
-    ui::horizontal_line,
-};
+use crate::{chat::Chat, frontend::ChatEvent};
 
 pub trait SaveChatHistory {
     fn save_chat_history(&mut self) -> anyhow::Result<()>;
@@ -11,12 +8,13 @@ impl SaveChatHistory for Chat {
     fn save_chat_history(&mut self) -> anyhow::Result<()> {
         self.clear_input();
         self.save_chat_history_to_file()?;
-        horizontal_line();
-        println!(
+        self.emit(ChatEvent::Saved);
+        self.separator();
+        self.notice(format!(
             "Saved chat (ID = {}) history to the {}/ directory",
             self.id(),
-            CHATS_DIR_NAME
-        );
+            self.config().chats_dir().display()
+        ));
         Ok(())
     }
 }
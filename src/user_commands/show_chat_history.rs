@@ -1,6 +1,6 @@
 use rig::message::{AssistantContent, Message, UserContent};
 
-use crate::{chat::Chat, ui::horizontal_line};
+use crate::chat::Chat;
 
 pub const HISTORY_LEN: usize = 10;
 
@@ -13,13 +13,13 @@ impl ShowChatHistory for Chat {
         const TRUNCATE_AT: usize = 300;
         if self.chat_history().is_empty() {
             self.clear_input();
-            horizontal_line();
-            println!("No chat history");
+            self.separator();
+            self.notice("No chat history");
             return;
         }
-        horizontal_line();
-        println!("Showing last {} messages", HISTORY_LEN);
-        horizontal_line();
+        self.separator();
+        self.notice(format!("Showing last {} messages", HISTORY_LEN));
+        self.separator();
         self.clear_input();
         let messages: Vec<_> = self
             .chat_history()
@@ -27,9 +27,10 @@ impl ShowChatHistory for Chat {
             .rev()
             .take(HISTORY_LEN)
             .rev()
+            .cloned()
             .collect();
         for (i, message) in messages.iter().enumerate() {
-            println!();
+            self.notice("");
             match message {
                 Message::User { content } => match content.first() {
                     UserContent::Text(text) => {
@@ -40,13 +41,13 @@ impl ShowChatHistory for Chat {
                         } else {
                             String::new()
                         };
-                        println!("*User*: \"{}{}\"", truncated.trim(), end);
+                        self.notice(format!("*User*: \"{}{}\"", truncated.trim(), end));
                     }
-                    UserContent::Image(_) => println!("*User*: *image*"),
-                    UserContent::Audio(_) => println!("*User*: *audio*"),
-                    UserContent::Video(_) => println!("*User*: *video*"),
-                    UserContent::Document(_) => println!("*User*: *document*"),
-                    UserContent::ToolResult(_) => println!("*User*: *tool result*"),
+                    UserContent::Image(_) => self.notice("*User*: *image*"),
+                    UserContent::Audio(_) => self.notice("*User*: *audio*"),
+                    UserContent::Video(_) => self.notice("*User*: *video*"),
+                    UserContent::Document(_) => self.notice("*User*: *document*"),
+                    UserContent::ToolResult(_) => self.notice("*User*: *tool result*"),
                 },
                 Message::Assistant { content, .. } => match content.first() {
                     AssistantContent::Text(text) => {
@@ -57,18 +58,18 @@ impl ShowChatHistory for Chat {
                         } else {
                             String::new()
                         };
-                        println!("*Assistant*: \"{}{}\"", truncated.trim(), end);
+                        self.notice(format!("*Assistant*: \"{}{}\"", truncated.trim(), end));
                     }
-                    AssistantContent::Image(_) => println!("*Assistant*: *image*"),
-                    AssistantContent::ToolCall(_) => println!("*Assistant*: *tool call*"),
-                    AssistantContent::Reasoning(_) => println!("*Assistant*: *reasoning*"),
+                    AssistantContent::Image(_) => self.notice("*Assistant*: *image*"),
+                    AssistantContent::ToolCall(_) => self.notice("*Assistant*: *tool call*"),
+                    AssistantContent::Reasoning(_) => self.notice("*Assistant*: *reasoning*"),
                 },
             }
-            println!();
+            self.notice("");
             if let Some(final_i) = messages.len().checked_sub(1)
                 && i != final_i
             {
-                println!("---");
+                self.notice("---");
             }
         }
     }
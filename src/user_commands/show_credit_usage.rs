@@ -0,0 +1,67 @@
+use crate::chat::Chat;
+
+pub trait ShowCreditUsage {
+    fn show_credit_usage(&mut self) -> impl Future<Output = ()>;
+}
+
+fn used_of(used: f64, limit: Option<f64>, remaining: Option<f64>) -> String {
+    match (limit, remaining) {
+        (Some(limit), Some(remaining)) => {
+            format!("{} of {} used, {} remaining", used, limit, remaining)
+        }
+        _ => format!("{} used, no limit", used),
+    }
+}
+
+impl ShowCreditUsage for Chat {
+    async fn show_credit_usage(&mut self) {
+        self.clear_input();
+        self.separator();
+        let provider = self.web_provider();
+        let Some(credits) = provider.credits() else {
+            self.notice(format!(
+                "The {} web provider does not use Tavily credits",
+                provider.name()
+            ));
+            return;
+        };
+        self.notice(format!(
+            "Tavily Credits Used This Session: {}",
+            credits.total()
+        ));
+        for (tool, usage) in credits.tools() {
+            self.notice(format!(
+                "  {:<14} {} calls ({} cached), {} credits",
+                tool, usage.calls, usage.cached, usage.credits
+            ));
+        }
+        match provider.account_usage().await {
+            Ok(usage) => {
+                if let Some(account) = usage.account {
+                    self.notice(format!(
+                        "Account ({} plan): {}",
+                        account.current_plan.as_deref().unwrap_or("unknown"),
+                        used_of(account.plan_usage, account.plan_limit, account.remaining())
+                    ));
+                    if account.paygo_limit.is_some() {
+                        self.notice(format!(
+                            "Pay-as-you-go: {}",
+                            used_of(
+                                account.paygo_usage,
+                                account.paygo_limit,
+                                account.paygo_remaining()
+                            )
+                        ));
+                    }
+                }
+                if let Some(key) = usage.key {
+                    self.notice(format!(
+                        "API key: {}",
+                        used_of(key.usage, key.limit, key.remaining())
+                    ));
+                }
+            }
+            Err(e) => self.notice(format!("Remaining Tavily balance unavailable: {}", e)),
+        }
+    }
+}
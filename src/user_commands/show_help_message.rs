@@ -1,4 +1,4 @@
-use crate::{chat::Chat, ui::horizontal_line, user_commands::show_chat_history::HISTORY_LEN};
+use crate::{chat::Chat, user_commands::show_chat_history::HISTORY_LEN};
 
 pub trait ShowHelpMessage {
     fn show_help_message(&mut self);
@@ -7,20 +7,29 @@ pub trait ShowHelpMessage {
 impl ShowHelpMessage for Chat {
     fn show_help_message(&mut self) {
         self.clear_input();
-        horizontal_line();
-        println!("Commands:");
-        println!("  /model     - switch between available Claude models");
-        println!("  /tokens    - show token usage");
-        println!(
+        self.separator();
+        self.notice("Commands:");
+        self.notice("  /model     - switch between available Claude models");
+        self.notice("  /tokens    - show token usage");
+        self.notice("  /credits   - show Tavily credits used per tool and the remaining balance");
+        self.notice(format!(
             "  /history   - show last {} messages from chat history",
             HISTORY_LEN
+        ));
+        self.notice("  /save      - save chat history to file");
+        self.notice("  /import    - import chat history from a saved file");
+        self.notice("  /summarize - ask the agent to summarize the conversation");
+        self.notice("  /compact   - condense conversation history");
+        self.notice("  /review    - run rustfmt and clippy on a file and discuss the results");
+        self.notice("  /research  - research a question on the web and write a cited report");
+        self.notice("  /permissions - show or set tool approval (always, ask, never)");
+        self.notice("  /cache     - show web response cache stats, or clear it");
+        self.notice(
+            "  /snapshot  - save a documentation site locally so the agent can search it offline",
         );
-        println!("  /save      - save chat history to file");
-        println!("  /import    - import chat history from a saved file");
-        println!("  /summarize - ask the agent to summarize the conversation");
-        println!("  /compact   - condense conversation history");
-        println!("  /clear     - clear chat history");
-        println!("  /help      - list all available commands");
-        println!("  /exit      - print farewell message and end the session");
+        self.notice("  /sources   - list the web sources the agent has seen and can cite");
+        self.notice("  /clear     - clear chat history");
+        self.notice("  /help      - list all available commands");
+        self.notice("  /exit      - print farewell message and end the session");
     }
 }
@@ -0,0 +1,24 @@
+use crate::chat::Chat;
+
+pub trait ShowSources {
+    fn show_sources(&mut self);
+}
+
+impl ShowSources for Chat {
+    fn show_sources(&mut self) {
+        self.clear_input();
+        self.separator();
+        let sources = self.sources().all();
+        if sources.is_empty() {
+            self.notice("No web sources yet");
+            return;
+        }
+        self.notice(format!("Web sources this session: {}", sources.len()));
+        for source in sources {
+            match source.title {
+                Some(title) => self.notice(format!("  [{}] {} <{}>", source.id, title, source.url)),
+                None => self.notice(format!("  [{}] <{}>", source.id, source.url)),
+            }
+        }
+    }
+}
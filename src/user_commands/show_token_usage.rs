@@ -1,4 +1,4 @@
-use crate::{chat::Chat, ui::horizontal_line};
+use crate::chat::Chat;
 
 pub trait ShowTokenUsage {
     fn show_token_usage(&mut self);
@@ -23,15 +23,22 @@ impl Formatted for usize {
 impl ShowTokenUsage for Chat {
     fn show_token_usage(&mut self) {
         self.clear_input();
-        horizontal_line();
-        println!(
+        self.separator();
+        self.notice(format!(
             "Total Input Tokens Used: {}",
             self.total_input_tokens_used().formatted()
-        );
-        println!(
+        ));
+        self.notice(format!(
             "Total Output Tokens Used: {}",
             self.total_output_tokens_used().formatted()
-        );
+        ));
+        self.notice(format!("Estimated Cost: ${:.4}", self.total_cost_usd()));
+        if let Some(credits) = self.web_provider().credits() {
+            self.notice(format!(
+                "Tavily Credits Used: {} (see /credits)",
+                credits.total()
+            ));
+        }
     }
 }
 
@@ -1,7 +1,4 @@
-use crate::{
-    chat::{input::ChatInput, Chat},
-    ui::horizontal_line,
-};
+use crate::chat::{input::ChatInput, Chat};
 
 pub trait SwitchModel {
     fn switch_model(&mut self) -> anyhow::Result<()>;
@@ -9,16 +6,16 @@ pub trait SwitchModel {
 
 impl SwitchModel for Chat {
     fn switch_model(&mut self) -> anyhow::Result<()> {
-        horizontal_line();
+        self.separator();
         self.clear_input();
-        println!("Current model: {}", self.model());
-        for (i, model) in self.model_options().iter().enumerate() {
-            println!("{}. {}", i + 1, model.display_name);
+        self.notice(format!("Current model: {}", self.model()));
+        for (i, model) in self.model_options().to_vec().iter().enumerate() {
+            self.notice(format!("{}. {}", i + 1, model.display_name));
         }
-        horizontal_line();
+        self.separator();
         loop {
-            println!("Select a model");
-            horizontal_line();
+            self.notice("Select a model");
+            self.separator();
             self.get_input();
             match self.input() {
                 ChatInput::SendMessage(message) => {
@@ -39,7 +36,7 @@ impl SwitchModel for Chat {
                     };
                     self.clear_input();
                     self.set_agent(selection)?;
-                    println!("Model updated: {}", self.model());
+                    self.notice(format!("Model updated: {}", self.model()));
                     break;
                 }
                 _ => break, // any other command should go to main loop for triaging
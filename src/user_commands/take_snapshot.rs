@@ -0,0 +1,152 @@
+use crate::{
+    agent_tools::web::{
+        crawl::request::{CrawlArgs, CrawlFormat, MAX_TIMEOUT},
+        local::parse_url,
+        snapshot::store::SnapshotStore,
+        validate::Validate,
+    },
+    chat::Chat,
+};
+
+const USAGE: &str =
+    "Usage: /snapshot [<url> [--depth <1-5>] [--paths <regex,...>] [--limit <pages>]]";
+// deeper than a crawl's default, documentation sites nest their pages
+const SNAPSHOT_DEPTH: u8 = 2;
+const SNAPSHOT_LIMIT: u32 = 50;
+
+pub trait TakeSnapshot {
+    fn take_snapshot(&mut self, args: &str) -> impl Future<Output = anyhow::Result<()>>;
+}
+
+// the crawl a snapshot makes, staying on the site it starts from
+fn crawl_args(args: &str) -> Result<CrawlArgs, String> {
+    let mut words = args.split_whitespace();
+    let mut crawl = CrawlArgs {
+        max_depth: Some(SNAPSHOT_DEPTH),
+        limit: Some(SNAPSHOT_LIMIT),
+        allow_external: Some(false),
+        format: Some(CrawlFormat::Markdown),
+        timeout: Some(MAX_TIMEOUT),
+        ..CrawlArgs::default()
+    };
+    while let Some(word) = words.next() {
+        let mut value = |flag: &str| words.next().ok_or(format!("{} needs a value", flag));
+        match word {
+            "--depth" => {
+                let depth = value(word)?;
+                crawl.max_depth = Some(
+                    depth
+                        .parse()
+                        .map_err(|_| format!("--depth takes a number, got '{}'", depth))?,
+                );
+            }
+            "--limit" => {
+                let limit = value(word)?;
+                crawl.limit = Some(
+                    limit
+                        .parse()
+                        .map_err(|_| format!("--limit takes a number, got '{}'", limit))?,
+                );
+            }
+            "--paths" => {
+                crawl.select_paths = Some(value(word)?.split(',').map(str::to_string).collect());
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option {}", flag)),
+            url if crawl.url.is_empty() => crawl.url = url.to_string(),
+            extra => return Err(format!("Unexpected '{}'", extra)),
+        }
+    }
+    Ok(crawl)
+}
+
+impl TakeSnapshot for Chat {
+    async fn take_snapshot(&mut self, args: &str) -> anyhow::Result<()> {
+        self.clear_input();
+        self.separator();
+        let store = SnapshotStore::new(self.config().snapshots_dir());
+        if args.is_empty() {
+            let snapshots = store.list()?;
+            if snapshots.is_empty() {
+                self.notice("No snapshots yet");
+                self.notice(USAGE);
+            }
+            for snapshot in snapshots {
+                self.notice(format!(
+                    "  {}: {} pages from {}, taken {}",
+                    snapshot.name,
+                    snapshot.pages.len(),
+                    snapshot.url,
+                    snapshot.taken_at
+                ));
+            }
+            return Ok(());
+        }
+        let crawl = match crawl_args(args) {
+            Ok(crawl) if !crawl.url.is_empty() => crawl,
+            Ok(_) => {
+                self.notice(USAGE);
+                return Ok(());
+            }
+            Err(e) => {
+                self.notice(e);
+                self.notice(USAGE);
+                return Ok(());
+            }
+        };
+        crawl.validate()?;
+        let url = parse_url(&crawl.url)?;
+        self.notice(format!(
+            "Crawling {} (depth {}, up to {} pages)...",
+            url,
+            crawl.max_depth.unwrap_or(SNAPSHOT_DEPTH),
+            crawl.limit.unwrap_or(SNAPSHOT_LIMIT)
+        ));
+        let provider = self.web_provider();
+        let response = provider.crawl(&crawl).await?.value;
+        let pages: Vec<(String, String)> = response
+            .results
+            .into_iter()
+            .filter(|result| !result.raw_content.trim().is_empty())
+            .map(|result| (result.url, result.raw_content))
+            .collect();
+        if pages.is_empty() {
+            self.notice(format!("The crawl of {} found no pages to save", url));
+            return Ok(());
+        }
+        let snapshot = tokio::task::spawn_blocking(move || store.save(&url, pages)).await??;
+        self.notice(format!(
+            "Saved {} pages as snapshot {} in {}, search_snapshots can now answer from it offline",
+            snapshot.pages.len(),
+            snapshot.name,
+            self.config().snapshots_dir().display()
+        ));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crawl_args() {
+        let crawl =
+            crawl_args("https://docs.rs/serde --depth 3 --paths /serde/.*,/serde_json/.*").unwrap();
+        assert_eq!(crawl.url, "https://docs.rs/serde");
+        assert_eq!(crawl.max_depth, Some(3));
+        assert_eq!(crawl.limit, Some(SNAPSHOT_LIMIT));
+        assert_eq!(
+            crawl.select_paths.unwrap(),
+            vec!["/serde/.*", "/serde_json/.*"]
+        );
+        assert_eq!(
+            crawl_args("https://docs.rs --limit lots").err().unwrap(),
+            "--limit takes a number, got 'lots'"
+        );
+        assert_eq!(
+            crawl_args("https://docs.rs --depth").err().unwrap(),
+            "--depth needs a value"
+        );
+        assert!(crawl_args("https://a.example https://b.example").is_err());
+    }
+}